@@ -13,7 +13,7 @@
 use frame_support::{
     decl_module, decl_error, decl_event,
     dispatch::{DispatchResultWithPostInfo},
-    ensure,
+    ensure, transactional,
 };
 use sp_runtime::{
 	DispatchResult, DispatchError,
@@ -25,7 +25,7 @@ use orml_nft::Pallet as AssetModule;
 use gamepower_wallet::Module as WalletModule;
 use gamepower_primitives::{WalletClassData, WalletAssetData};
 use gamepower_traits::{
-	OnTransferHandler, OnBurnHandler, OnClaimHandler,
+	OnTransferHandler, OnBurnHandler, OnClaimHandler, OnMintHandler,
 };
 use sp_std::vec::Vec;
 
@@ -36,6 +36,8 @@ orml_nft::Config<
     ClassData=WalletClassData,
 >{
 	type Event: From<Event<Self>> + Into<<Self as system::Config>::Event>;
+	/// Called for each minted token. Returning an error aborts the mint.
+	type MintHandler: OnMintHandler<Self::AccountId, Self::ClassId, Self::TokenId>;
 }
 
 pub type ClassIdOf<T> = <T as orml_nft::Config>::ClassId;
@@ -54,6 +56,8 @@ decl_event!(
 	  AssetBurned(AccountId, ClassId, TokenId),
 	  /// Claiming assset [owner]
 	  AssetBeingClaimed(AccountId),
+	  /// Assets successfully minted [owner, classId, tokenIds]
+	  AssetsMinted(AccountId, ClassId, Vec<TokenId>),
 	}
 );
 
@@ -98,6 +102,7 @@ decl_module! {
 	/// - `properties`: properties for our class. This uses WalletClassData which you can replace with any type of data
 	/// - `quantity`: instructs the pallet on how many tokens to mint
     #[weight = 10_000]
+    #[transactional]
     pub fn mint(origin, class_id: ClassIdOf<T>, metadata: Vec<u8>, properties: Vec<u8>, quantity: u32) -> DispatchResultWithPostInfo {
 
         let sender = ensure_signed(origin)?;
@@ -110,12 +115,16 @@ decl_module! {
             properties: properties.clone(),
         };
 
-        let mut new_asset_ids: Vec<u64> = Vec::new();
+        let mut new_asset_ids: Vec<TokenIdOf<T>> = Vec::new();
 
         for _ in 0..quantity{
-          AssetModule::<T>::mint(&sender, class_id, metadata.clone(), new_asset_data.clone())?;
+          let asset_id = AssetModule::<T>::mint(&sender, class_id, metadata.clone(), new_asset_data.clone())?;
+          T::MintHandler::on_mint(&sender, (class_id, asset_id))?;
+          new_asset_ids.push(asset_id);
         }
 
+        Module::<T>::deposit_event(RawEvent::AssetsMinted(sender, class_id, new_asset_ids));
+
         Ok(().into())
     }
 
@@ -0,0 +1,193 @@
+// This file is part of GamePower Network.
+
+// Copyright (C) 2021 GamePower Network.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API definition for the GamePower market pallet.
+
+// Ensure we're `no_std` when compiling for Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(
+    clippy::multiple_bound_locations,
+    clippy::too_many_arguments,
+    clippy::type_complexity
+)]
+use codec::Codec;
+use gamepower_primitives::{
+    AuctionId, ClassListingStats, ClassMarketData, Listing, ListingId, MarketPalletInfo, Offer,
+    OfferId, Order, TokenDetail,
+};
+use sp_runtime::Perbill;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+    /// Read-only queries against the market pallet storage.
+    pub trait MarketApi<AccountId, ClassId, TokenId, Balance, BlockNumber, CurrencyId>
+    where
+        AccountId: Codec,
+        ClassId: Codec,
+        TokenId: Codec,
+        Balance: Codec,
+        BlockNumber: Codec,
+        CurrencyId: Codec,
+    {
+        /// Get the gift message attached to an asset
+        fn get_gift_message(asset: (ClassId, TokenId)) -> Option<Vec<u8>>;
+
+        /// Check if a collection has been verified
+        fn is_verified_collection(class_id: ClassId) -> bool;
+
+        /// Get the recorded sale prices of an asset, oldest first
+        fn get_price_history(asset: (ClassId, TokenId)) -> Vec<(Balance, BlockNumber)>;
+
+        /// Get `points` price history entries evenly spaced over block numbers
+        fn get_price_chart_data(asset: (ClassId, TokenId), points: u32) -> Vec<(Balance, BlockNumber)>;
+
+        /// Get sale and listing figures for a class
+        fn get_class_market_data(class_id: ClassId) -> ClassMarketData<Balance, BlockNumber>;
+
+        /// Get the number of times an asset has been listed
+        fn get_relist_count(asset: (ClassId, TokenId)) -> u32;
+
+        /// Get a page of an account's listings ordered by listing id, and the account's total number of listings
+        fn get_listings_by_owner(
+            owner: AccountId,
+            offset: u32,
+            limit: u32,
+        ) -> (Vec<Listing<ClassId, TokenId, AccountId, Balance, BlockNumber, CurrencyId>>, u32);
+
+        /// Get the owner of an asset and the storage key to request a read proof for
+        fn get_ownership_storage_key(asset: (ClassId, TokenId)) -> Option<(AccountId, Vec<u8>)>;
+
+        /// Get the block an asset was minted in
+        fn get_mint_block(asset: (ClassId, TokenId)) -> Option<BlockNumber>;
+
+        /// Get the serial number of an asset minted through the market
+        fn get_serial_number(asset: (ClassId, TokenId)) -> Option<u64>;
+
+        /// Get the asset minted with a serial number in a class
+        fn get_token_by_serial(class_id: ClassId, serial_number: u64) -> Option<TokenId>;
+
+        /// Get the number of tokens minted in a class
+        fn total_minted(class_id: ClassId) -> u64;
+
+        /// Get up to `limit` assets in open claims after `cursor`, and the cursor for the next page
+        fn get_all_claims_page(
+            cursor: Option<(ClassId, TokenId)>,
+            limit: u32,
+        ) -> (Vec<(ClassId, TokenId)>, Option<(ClassId, TokenId)>);
+
+        /// Get the creator address linked to a class, if one is set
+        fn get_class_creator_address(class_id: ClassId) -> Option<Vec<u8>>;
+
+        /// Check if an account has posted an emote, by name or emoji, on an asset
+        fn has_emoted(asset: (ClassId, TokenId), account: AccountId, emote: Vec<u8>) -> bool;
+
+        /// Get all emotes an account has posted on an asset
+        fn user_emotes_for_asset(asset: (ClassId, TokenId), account: AccountId) -> Vec<Vec<u8>>;
+
+        /// Get the number of times an emote, by name or emoji, has been posted on an asset
+        fn get_emote_count(class_id: ClassId, token_id: TokenId, emote: Vec<u8>) -> u64;
+
+        /// Get the value of a game attribute of an asset
+        fn get_token_attribute(class_id: ClassId, token_id: TokenId, key: Vec<u8>) -> Option<Vec<u8>>;
+
+        /// Get ownership, lock state, emotes, pricing and mint details of an asset
+        fn get_token_detail(
+            asset: (ClassId, TokenId),
+        ) -> Option<TokenDetail<AccountId, ClassId, TokenId, Balance, BlockNumber>>;
+
+        /// Get listing and sale figures for a class
+        fn get_class_listing_stats(class_id: ClassId) -> ClassListingStats<Balance>;
+
+        /// Get a page of a game's classes ordered by class id
+        fn get_classes_by_game(game_id: u32, page: u32, limit: u32) -> Vec<ClassId>;
+
+        /// Get a page of the open listings in a game's classes ordered by listing id
+        fn get_active_listings_by_game(
+            game_id: u32,
+            page: u32,
+            limit: u32,
+        ) -> Vec<Listing<ClassId, TokenId, AccountId, Balance, BlockNumber, CurrencyId>>;
+
+        /// Get a page of the assets carrying a tag, matched case-insensitively
+        fn search_tokens_by_tag(tag: Vec<u8>, page: u32, limit: u32) -> Vec<(ClassId, TokenId)>;
+
+        /// Get every asset an account owns
+        fn get_owned_assets(account: AccountId) -> Vec<(ClassId, TokenId)>;
+
+        /// Get the assets an account owns in a class
+        fn get_owned_assets_by_class(account: AccountId, class_id: ClassId) -> Vec<(ClassId, TokenId)>;
+
+        /// Get the estimated value of an account's assets from their latest listing prices
+        fn estimate_portfolio_value(account: AccountId) -> Balance;
+
+        /// Get the name and version of the market pallet
+        fn get_pallet_metadata() -> Option<MarketPalletInfo>;
+
+        /// Get the royalty rate and recipient of a class, if it has one
+        fn get_royalty(class_id: ClassId) -> Option<(Perbill, AccountId)>;
+
+        /// Get a page of the sales of an asset, oldest first
+        fn get_order_history(
+            asset: (ClassId, TokenId),
+            offset: u32,
+            limit: u32,
+        ) -> Vec<Order<ClassId, TokenId, AccountId, Balance, BlockNumber>>;
+
+        /// Get every sale of an asset, oldest first
+        fn get_order_history_for_asset(
+            class_id: ClassId,
+            token_id: TokenId,
+        ) -> Vec<Order<ClassId, TokenId, AccountId, Balance, BlockNumber>>;
+
+        /// Get a page of the open listings in the order their assets were listed
+        fn get_listings(
+            page: u32,
+            page_size: u32,
+        ) -> Vec<Listing<ClassId, TokenId, AccountId, Balance, BlockNumber, CurrencyId>>;
+
+        /// Get the number of open listings
+        fn listing_count() -> u64;
+
+        /// Get a listing by its id
+        fn get_listing(
+            listing_id: ListingId,
+        ) -> Option<Listing<ClassId, TokenId, AccountId, Balance, BlockNumber, CurrencyId>>;
+
+        /// Get a page of the open listings in a class in the order they were listed
+        fn get_listings_by_class(
+            class_id: ClassId,
+            page: u32,
+            page_size: u32,
+        ) -> Vec<Listing<ClassId, TokenId, AccountId, Balance, BlockNumber, CurrencyId>>;
+
+        /// Get the open listing of an asset
+        fn get_listing_by_asset(
+            class_id: ClassId,
+            token_id: TokenId,
+        ) -> Option<Listing<ClassId, TokenId, AccountId, Balance, BlockNumber, CurrencyId>>;
+
+        /// Get the bids placed on an open auction, oldest first
+        fn get_auction_bid_history(auction_id: AuctionId) -> Vec<(AccountId, Balance, BlockNumber)>;
+
+        /// Get a page of the open offers made by an account ordered by offer id
+        fn get_offers_by_buyer(
+            buyer: AccountId,
+            page: u32,
+            limit: u32,
+        ) -> Vec<(OfferId, Offer<ClassId, TokenId, AccountId, Balance, BlockNumber, CurrencyId>)>;
+    }
+}
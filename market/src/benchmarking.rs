@@ -0,0 +1,184 @@
+// This file is part of GamePower Network.
+
+// Copyright (C) 2021 GamePower Network.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Market pallet benchmarking.
+
+#![cfg(feature = "runtime-benchmarks")]
+#![allow(clippy::multiple_bound_locations)]
+
+use super::*;
+
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_system::RawOrigin;
+
+const SEED: u32 = 0;
+// Upper bound for the number of assets listed on the market
+const MAX_LISTINGS: u32 = 1_000;
+// Upper bound for the number of assets held in open claims
+const MAX_CLAIMS: u32 = 1_000;
+
+// Create a class and mint a single asset into it for `owner`
+fn create_asset<T: Config>(
+    owner: &T::AccountId,
+) -> Result<(ClassIdOf<T>, TokenIdOf<T>), &'static str> {
+    let class_id = AssetModule::<T>::create_class(owner, vec![1], Default::default())?;
+    let token_id = AssetModule::<T>::mint(owner, class_id, vec![1], Default::default())?;
+    Ok((class_id, token_id))
+}
+
+// Fill `AllListings` with `m` assets that are not the benchmarked one
+fn fill_listings<T: Config>(m: u32) {
+    let listed: Vec<(ClassIdOf<T>, TokenIdOf<T>)> =
+        (0..m).map(|i| (u32::MAX.into(), i.into())).collect();
+    AllListings::<T>::put(listed);
+}
+
+// Fill `AllClaims` with `c` assets that are not the benchmarked one
+fn fill_claims<T: Config>(c: u32) {
+    let claimed: Vec<(ClassIdOf<T>, TokenIdOf<T>)> =
+        (0..c).map(|i| (u32::MAX.into(), i.into())).collect();
+    AllClaims::<T>::put(claimed);
+}
+
+// A sale price well above the existential deposit, and enough funds to pay it
+fn fund_buyer<T: Config>(buyer: &T::AccountId) -> BalanceOf<T> {
+    let price = T::Currency::minimum_balance().saturating_mul(10u32.into());
+    T::Currency::make_free_balance_be(buyer, price.saturating_mul(10u32.into()));
+    price
+}
+
+benchmarks! {
+    // Benchmark `transfer` with `m` assets already listed. `is_locked` has to
+    // scan every listing before the asset can move.
+    transfer {
+        let m in 0 .. MAX_LISTINGS;
+
+        let caller: T::AccountId = whitelisted_caller();
+        let recipient: T::AccountId = account("recipient", 0, SEED);
+        let asset = create_asset::<T>(&caller)?;
+        fill_listings::<T>(m);
+    }: _(RawOrigin::Signed(caller), recipient.clone(), asset)
+    verify {
+        assert!(AssetModule::<T>::is_owner(&recipient, asset));
+    }
+
+    // Benchmark `burn` with `m` assets already listed
+    burn {
+        let m in 0 .. MAX_LISTINGS;
+
+        let caller: T::AccountId = whitelisted_caller();
+        let asset = create_asset::<T>(&caller)?;
+        fill_listings::<T>(m);
+    }: _(RawOrigin::Signed(caller), asset)
+    verify {
+        assert!(AssetModule::<T>::tokens(asset.0, asset.1).is_none());
+    }
+
+    // Benchmark `list` with `m` assets already listed
+    list {
+        let m in 0 .. MAX_LISTINGS;
+
+        let caller: T::AccountId = whitelisted_caller();
+        let asset = create_asset::<T>(&caller)?;
+        fill_listings::<T>(m);
+    }: _(RawOrigin::Signed(caller), asset, 100u32.into(), None, T::NativeCurrencyId::get())
+    verify {
+        assert!(Module::<T>::all_listings().contains(&asset));
+    }
+
+    // Benchmark `unlist` with `m` other assets listed. The asset is removed
+    // from the end of `AllListings`.
+    unlist {
+        let m in 0 .. MAX_LISTINGS;
+
+        let caller: T::AccountId = whitelisted_caller();
+        let asset = create_asset::<T>(&caller)?;
+        fill_listings::<T>(m);
+        let listing_id = Module::<T>::next_listing_id();
+        Module::<T>::list(RawOrigin::Signed(caller.clone()).into(), asset, 100u32.into(), None, T::NativeCurrencyId::get())?;
+    }: _(RawOrigin::Signed(caller.clone()), listing_id)
+    verify {
+        assert!(AssetModule::<T>::is_owner(&caller, asset));
+    }
+
+    // Benchmark `buy` with `m` other assets listed
+    buy {
+        let m in 0 .. MAX_LISTINGS;
+
+        let seller: T::AccountId = account("seller", 0, SEED);
+        let buyer: T::AccountId = whitelisted_caller();
+        let price = fund_buyer::<T>(&buyer);
+        let asset = create_asset::<T>(&seller)?;
+        fill_listings::<T>(m);
+        let listing_id = Module::<T>::next_listing_id();
+        Module::<T>::list(RawOrigin::Signed(seller).into(), asset, price, None, T::NativeCurrencyId::get())?;
+    }: _(RawOrigin::Signed(buyer.clone()), listing_id)
+    verify {
+        assert!(AssetModule::<T>::is_owner(&buyer, asset));
+    }
+
+    emote {
+        let caller: T::AccountId = whitelisted_caller();
+        let asset = create_asset::<T>(&caller)?;
+    }: _(RawOrigin::Signed(caller.clone()), asset, b"fish".to_vec())
+    verify {
+        assert_eq!(Module::<T>::emotes(asset, &caller).len(), 1);
+    }
+
+    // Benchmark `claim` with `c` other assets in open claims
+    claim {
+        let c in 0 .. MAX_CLAIMS;
+
+        let creator: T::AccountId = account("creator", 0, SEED);
+        let receiver: T::AccountId = whitelisted_caller();
+        let asset = create_asset::<T>(&creator)?;
+        fill_claims::<T>(c);
+        let claim_id = Module::<T>::next_claim_id();
+        Module::<T>::create_claim(RawOrigin::Signed(creator).into(), receiver.clone(), asset, None)?;
+    }: _(RawOrigin::Signed(receiver.clone()), claim_id)
+    verify {
+        assert!(AssetModule::<T>::is_owner(&receiver, asset));
+    }
+
+    // Benchmark `create_claim` with `c` assets already in open claims
+    create_claim {
+        let c in 0 .. MAX_CLAIMS;
+
+        let caller: T::AccountId = whitelisted_caller();
+        let receiver: T::AccountId = account("receiver", 0, SEED);
+        let asset = create_asset::<T>(&caller)?;
+        fill_claims::<T>(c);
+        let claim_id = Module::<T>::next_claim_id();
+    }: _(RawOrigin::Signed(caller), receiver.clone(), asset, None)
+    verify {
+        assert!(Module::<T>::open_claims(&receiver, claim_id).is_some());
+    }
+
+    // Cost of the lock lookup alone with `m` assets listed
+    #[extra]
+    is_locked_linear {
+        let m in 0 .. MAX_LISTINGS;
+
+        let caller: T::AccountId = whitelisted_caller();
+        let asset = create_asset::<T>(&caller)?;
+        fill_listings::<T>(m);
+    }: {
+        Module::<T>::is_locked(&asset);
+    }
+}
+
+impl_benchmark_test_suite!(Module, crate::mock::new_test_ext(), crate::mock::Test,);
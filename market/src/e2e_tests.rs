@@ -0,0 +1,124 @@
+use crate::mock::*;
+use crate::RawEvent;
+use frame_support::assert_ok;
+use sp_runtime::Perbill;
+
+const CHARLIE: AccountId = 3;
+const DAVE: AccountId = 4;
+
+fn has_event(event: RawEvent<AccountId, u32, u64, u64, u64>) -> bool {
+    System::events()
+        .iter()
+        .any(|record| record.event == Event::gamepower_market(event.clone()))
+}
+
+#[test]
+fn full_marketplace_scenario() {
+    new_test_ext().execute_with(|| {
+        // The class creator takes a 10% cut of every sale
+        assert_ok!(GamePowerMarket::set_fee_splits(
+            Origin::root(),
+            vec![(ALICE, Perbill::from_percent(10))]
+        ));
+
+        // Create class
+        assert_ok!(GamePowerMarket::create_class(
+            Origin::signed(ALICE),
+            vec![1],
+            Default::default(),
+            None
+        ));
+        assert!(has_event(RawEvent::MarketClassCreated(ALICE, CLASS_ID)));
+
+        // Mint
+        assert_ok!(GamePowerMarket::mint(
+            Origin::signed(ALICE),
+            CLASS_ID,
+            vec![1],
+            Default::default(),
+            2
+        ));
+        assert!(OrmlNFT::is_owner(&ALICE, (CLASS_ID, 0)));
+        assert!(OrmlNFT::is_owner(&ALICE, (CLASS_ID, 1)));
+        assert_eq!(GamePowerMarket::class_minted_count(CLASS_ID), 2);
+
+        // Hand an asset to a player
+        assert_ok!(GamePowerMarket::transfer(
+            Origin::signed(ALICE),
+            CHARLIE,
+            (CLASS_ID, 0)
+        ));
+        assert!(OrmlNFT::is_owner(&CHARLIE, (CLASS_ID, 0)));
+
+        // List
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(CHARLIE),
+            (CLASS_ID, 0),
+            1000,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert!(has_event(RawEvent::MarketAssetListed(
+            CHARLIE, 1000, 0, CLASS_ID, 0
+        )));
+        assert!(GamePowerMarket::is_locked(&(CLASS_ID, 0)));
+        assert!(OrmlNFT::is_owner(
+            &GamePowerMarket::get_class_escrow_account(CLASS_ID),
+            (CLASS_ID, 0)
+        ));
+        assert_eq!(GamePowerMarket::listing_count(), 1);
+
+        // Buy, paying the seller and the class creator
+        assert_ok!(GamePowerMarket::buy(Origin::signed(BOB), 0));
+        assert!(has_event(RawEvent::MarketAssetBuySuccess(
+            CHARLIE, BOB, 0, 1000
+        )));
+        assert!(OrmlNFT::is_owner(&BOB, (CLASS_ID, 0)));
+        assert!(!GamePowerMarket::is_locked(&(CLASS_ID, 0)));
+        assert_eq!(Balances::free_balance(BOB), 1000000 - 1000);
+        assert_eq!(Balances::free_balance(CHARLIE), 1000000 + 900);
+        assert_eq!(Balances::free_balance(ALICE), 1000000 + 100);
+        assert_eq!(GamePowerMarket::listing_count(), 0);
+        assert_eq!(GamePowerMarket::order_count(), 1);
+
+        // Buyer relists at a higher price
+        System::set_block_number(2);
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(BOB),
+            (CLASS_ID, 0),
+            2000,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_eq!(GamePowerMarket::listings_by_owner(&BOB), vec![1]);
+        assert_eq!(GamePowerMarket::get_relist_count((CLASS_ID, 0)), 2);
+
+        assert_ok!(GamePowerMarket::buy(Origin::signed(DAVE), 1));
+        assert!(OrmlNFT::is_owner(&DAVE, (CLASS_ID, 0)));
+        assert_eq!(Balances::free_balance(DAVE), 1000000 - 2000);
+        assert_eq!(Balances::free_balance(BOB), 1000000 - 1000 + 1800);
+        assert_eq!(Balances::free_balance(ALICE), 1000000 + 100 + 200);
+
+        // Market figures reflect both sales
+        assert_eq!(GamePowerMarket::order_count(), 2);
+        assert_eq!(GamePowerMarket::total_volume(), 3000);
+        assert_eq!(
+            GamePowerMarket::get_price_history((CLASS_ID, 0)),
+            vec![(1000, 1), (2000, 2)]
+        );
+        assert_eq!(GamePowerMarket::class_last_sale_price(CLASS_ID), Some(2000));
+
+        // The creator delivers the remaining asset through a claim
+        assert_ok!(GamePowerMarket::create_claim(
+            Origin::signed(ALICE),
+            BOB,
+            (CLASS_ID, 1),
+            None
+        ));
+        assert!(GamePowerMarket::is_locked(&(CLASS_ID, 1)));
+        assert_ok!(GamePowerMarket::claim(Origin::signed(BOB), 0));
+        assert!(has_event(RawEvent::MarketAssetClaimed(BOB, CLASS_ID, 1)));
+        assert!(OrmlNFT::is_owner(&BOB, (CLASS_ID, 1)));
+        assert!(!GamePowerMarket::is_locked(&(CLASS_ID, 1)));
+    });
+}
@@ -17,58 +17,253 @@
 
 // Ensure we're `no_std` when compiling for Wasm.
 #![cfg_attr(not(feature = "std"), no_std)]
-use codec::{Decode, Encode};
+// decl_module! recurses once per extrinsic
+#![recursion_limit = "512"]
+#![allow(clippy::unused_unit)]
+use codec::{Decode, DecodeAll, Encode};
 use frame_support::{
-    decl_error, decl_event, decl_module, decl_storage, ensure,
-    traits::{Currency, ExistenceRequirement, Get, ReservableCurrency},
+    decl_error, decl_event, decl_module, decl_storage,
+    dispatch::{DispatchResultWithPostInfo, Parameter},
+    ensure,
+    storage::{
+        migration::StorageKeyIterator, unhashed, with_transaction, StoragePrefixedMap,
+        TransactionOutcome,
+    },
+    traits::{
+        BalanceStatus, Currency, EnsureOrigin, ExistenceRequirement, Get, ReservableCurrency,
+    },
+    transactional,
+    weights::Weight,
+    Blake2_128Concat,
+};
+use frame_system::{
+    self as system, ensure_none, ensure_signed,
+    offchain::{SendTransactionTypes, SubmitTransaction},
 };
-use frame_system::{self as system, ensure_signed};
 use sp_runtime::{
-    traits::{AccountIdConversion, One},
-    DispatchError, DispatchResult, ModuleId, RuntimeDebug,
+    helpers_128bit::multiply_by_rational,
+    traits::{AccountIdConversion, Hash, Member, One, SaturatedConversion, Saturating, Zero},
+    transaction_validity::{
+        InvalidTransaction, TransactionPriority, TransactionSource, TransactionValidity,
+        ValidTransaction,
+    },
+    DispatchError, DispatchResult, ModuleId, Perbill, Permill, RuntimeDebug,
 };
 
-use gamepower_primitives::{ClaimId, ListingId};
+use gamepower_primitives::{
+    AssetData, AuctionId, BundleListingId, ClaimId, ClassData, ClassListingStats, ClassMarketData,
+    GameInfo, ListingId, LockReason, MarketPalletInfo, OfferId, OrderId, SecretListingId, SwapId,
+    TokenDetail, MAX_PALLET_DESCRIPTION_LENGTH, MAX_PALLET_NAME_LENGTH,
+};
+pub use gamepower_primitives::{Listing, Offer, Order};
 use gamepower_traits::*;
-use orml_nft::Pallet as AssetModule;
+use orml_nft::{ClassInfo, ClassInfoOf, Pallet as AssetModule, TokenInfo, TokenInfoOf};
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
+use sp_std::collections::{btree_map::BTreeMap, btree_set::BTreeSet};
 use sp_std::str;
-use sp_std::vec;
 use sp_std::vec::Vec;
 
 #[cfg(test)]
 mod mock;
+#[cfg(test)]
+mod mock_u128;
+
+mod benchmarking;
+pub mod weights;
 
+pub use weights::WeightInfo;
+
+#[cfg(test)]
+mod e2e_tests;
 #[cfg(test)]
 mod tests;
+#[cfg(test)]
+mod tests_u128;
 
 #[derive(Encode, Decode, Default, Clone, RuntimeDebug, PartialEq, Eq)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
-/// Listing data
-pub struct Listing<ClassIdOf, TokenIdOf, AccountId, Balance> {
-    /// Listing Id
-    pub id: ListingId,
+/// Secret listing data. The price is only revealed by the buyer.
+pub struct SecretListing<ClassIdOf, TokenIdOf, AccountId, Hash> {
+    /// Secret Listing Id
+    pub id: SecretListingId,
     /// Seller of the listing
     pub seller: AccountId,
     /// Asset - (class_id, token_id)
     pub asset: (ClassIdOf, TokenIdOf),
-    /// Price of the asset listed
+    /// Hash of (price, nonce)
+    pub price_hash: Hash,
+}
+
+#[derive(Encode, Decode, Default, Clone, RuntimeDebug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+/// Several assets sold together at one price
+pub struct BundleListing<ClassIdOf, TokenIdOf, AccountId, Balance> {
+    /// Bundle Listing Id
+    pub id: BundleListingId,
+    /// Seller of the bundle
+    pub seller: AccountId,
+    /// Assets - (class_id, token_id)
+    pub assets: Vec<(ClassIdOf, TokenIdOf)>,
+    /// Price of the whole bundle
     pub price: Balance,
 }
 
+#[derive(Encode, Decode, Default, Clone, RuntimeDebug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+/// English auction data. The highest bid at `end_block` wins if it meets the reserve.
+pub struct Auction<ClassIdOf, TokenIdOf, AccountId, Balance, BlockNumber> {
+    /// Auction Id
+    pub id: AuctionId,
+    /// Seller of the asset
+    pub seller: AccountId,
+    /// Asset - (class_id, token_id)
+    pub asset: (ClassIdOf, TokenIdOf),
+    /// Lowest accepted first bid
+    pub starting_price: Balance,
+    /// Lowest winning bid
+    pub reserve_price: Balance,
+    /// Block from which bidding is closed and the auction can be settled
+    pub end_block: BlockNumber,
+    /// Current highest bidder and bid
+    pub highest_bid: Option<(AccountId, Balance)>,
+}
+
+#[derive(Encode, Decode, Default, Clone, RuntimeDebug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+/// Dutch auction data. The price falls linearly from `start_price` at `start_block`
+/// to `floor_price` at `end_block` and the first buyer wins.
+pub struct DutchAuction<ClassIdOf, TokenIdOf, AccountId, Balance, BlockNumber> {
+    /// Auction Id
+    pub id: AuctionId,
+    /// Seller of the asset
+    pub seller: AccountId,
+    /// Asset - (class_id, token_id)
+    pub asset: (ClassIdOf, TokenIdOf),
+    /// Price at the start block
+    pub start_price: Balance,
+    /// Price from the end block on
+    pub floor_price: Balance,
+    /// Block the asset can first be bought in
+    pub start_block: BlockNumber,
+    /// Block the price reaches the floor
+    pub end_block: BlockNumber,
+}
+
+#[derive(Encode, Decode, Default, Clone, RuntimeDebug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+/// Proposal to trade one asset for another. The offered asset is held by the swap escrow.
+pub struct SwapProposal<ClassIdOf, TokenIdOf, AccountId, BlockNumber> {
+    /// Swap Id
+    pub swap_id: SwapId,
+    /// Account proposing the swap
+    pub initiator: AccountId,
+    /// Account asked to accept the swap
+    pub counterparty: AccountId,
+    /// Asset of the initiator - (class_id, token_id)
+    pub offered_asset: (ClassIdOf, TokenIdOf),
+    /// Asset of the counterparty - (class_id, token_id)
+    pub requested_asset: (ClassIdOf, TokenIdOf),
+    /// Last block the swap can be accepted in
+    pub expiry_block: BlockNumber,
+    /// Block the swap was proposed in
+    pub proposed_block: BlockNumber,
+}
+
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+/// State of a dispute over a swap, holding the party that raised it
+pub enum DisputeStatus<AccountId> {
+    /// Waiting for the arbitration origin
+    Open(AccountId),
+    /// Offered asset returned to the party chosen by the arbitration origin
+    Resolved(AccountId),
+}
+
 #[derive(Encode, Decode, Default, Clone, RuntimeDebug, PartialEq, Eq)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 /// Claim data
-pub struct Claim<ClassIdOf, TokenIdOf, AccountId> {
+pub struct Claim<ClassIdOf, TokenIdOf, AccountId, BlockNumber> {
     /// account this claim is meant for
     pub receiver: AccountId,
     /// Asset - (class_id, token_id)
     pub asset: (ClassIdOf, TokenIdOf),
+    /// account that created this claim
+    pub creator: AccountId,
+    /// Last block the asset can be claimed in. The creator can reclaim it afterwards.
+    pub deadline: Option<BlockNumber>,
+}
+
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+/// Verification state of a collection (class)
+pub enum VerificationStatus {
+    /// Class owner applied for verification
+    Pending,
+    /// Collection verified by the verification origin
+    Verified,
+    /// Collection rejected by the verification origin
+    Rejected,
+}
+
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, PartialOrd, Ord)]
+/// Storage layout versions of the pallet
+pub enum Releases {
+    /// All listed assets held by a single escrow account
+    V1_0_0,
+    /// Listed assets held by a per-class escrow account
+    V2_0_0,
+    /// ListingsByOwner keyed by (owner, listing id)
+    V3_0_0,
+    /// Assets in open claims indexed by ClaimIndex
+    V4_0_0,
+    /// Listings carry an optional expiry block
+    V5_0_0,
+    /// Claims record their creator and an optional deadline
+    V6_0_0,
+    /// Listings carry the currency they are priced in
+    V7_0_0,
+    /// Listed assets indexed by ListingsByAsset
+    V8_0_0,
+    /// Open listings indexed by ListingsByClass
+    V9_0_0,
+    /// Orders record the amount paid by the buyer
+    V10_0_0,
+    /// Open claims counted by ClaimCount
+    V11_0_0,
+    /// orml_nft class and token data stored as the current ClassData and AssetData
+    V12_0_0,
+    /// Offers carry the currency they are made in
+    V13_0_0,
+    /// Swaps record the block they were proposed in
+    V14_0_0,
+    /// Open secret listings indexed by SecretListingsByAsset and SecretListingsByClass
+    V15_0_0,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for Releases {
+    fn default() -> Self {
+        Releases::V1_0_0
+    }
 }
 
 /// The module configuration trait.
-pub trait Config: system::Config + orml_nft::Config {
+pub trait Config:
+    system::Config
+    + SendTransactionTypes<Call<Self>>
+    + orml_nft::Config<
+        ClassData = ClassData<
+            <Self as system::Config>::AccountId,
+            <Self as system::Config>::BlockNumber,
+        >,
+        TokenData = AssetData<
+            <Self as orml_nft::Config>::ClassId,
+            <Self as orml_nft::Config>::TokenId,
+            <Self as system::Config>::BlockNumber,
+        >,
+    >
+{
     type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
     /// Wallet Transfer Handler
     type Transfer: OnTransferHandler<Self::AccountId, Self::ClassId, Self::TokenId>;
@@ -76,6 +271,14 @@ pub trait Config: system::Config + orml_nft::Config {
     type Burn: OnBurnHandler<Self::AccountId, Self::ClassId, Self::TokenId>;
     /// Wallet Claim Handler
     type Claim: OnClaimHandler<Self::AccountId, Self::ClassId, Self::TokenId>;
+    /// Wallet Mint Handler, called after the market has recorded the new asset
+    type Mint: OnMintHandler<Self::AccountId, Self::ClassId, Self::TokenId>;
+    /// Market List Handler
+    type List: OnListHandler<Self::AccountId, Self::ClassId, Self::TokenId, BalanceOf<Self>>;
+    /// Market Unlist Handler
+    type Unlist: OnUnlistHandler<Self::AccountId, Self::ClassId, Self::TokenId>;
+    /// Market Buy Handler
+    type Buy: OnBuyHandler<Self::AccountId, Self::ClassId, Self::TokenId, BalanceOf<Self>>;
     /// Allow assets to be transferred through the wallet
     type AllowTransfer: Get<bool>;
     /// Allow assets to be burned from the wallet
@@ -86,8 +289,90 @@ pub trait Config: system::Config + orml_nft::Config {
     type AllowClaim: Get<bool>;
     /// Currency type for reserve/unreserve balance
     type Currency: Currency<Self::AccountId> + ReservableCurrency<Self::AccountId>;
+    /// Identifier of the currencies a listing can be priced in
+    type CurrencyId: Parameter + Member + Copy + Default;
+    /// Currencies other than the native one, used for sales and offers priced in them
+    type MultiCurrency: MultiCurrency<Self::AccountId, Self::CurrencyId, BalanceOf<Self>>;
+    /// Id of the native currency. Sales priced in it are paid through `Currency`.
+    type NativeCurrencyId: Get<Self::CurrencyId>;
+    /// Currencies besides the native one that listings can be priced in
+    type AcceptedCurrencies: Get<Vec<Self::CurrencyId>>;
     /// Wallet Module Id
     type ModuleId: Get<ModuleId>;
+    /// Maximum number of assets handled by a single batch call
+    type MaxBatchSize: Get<u32>;
+    /// Maximum length of a gift message
+    type MaxMessageLength: Get<u32>;
+    /// Maximum length of a transfer memo
+    type MaxMemoLength: Get<u32>;
+    /// Maximum number of accounts sharing the fee on each sale
+    type MaxFeeSplitRecipients: Get<u32>;
+    /// Maximum number of collaborators sharing a class royalty
+    type MaxRoyaltyRecipients: Get<u32>;
+    /// Maximum number of sales kept in the price history of an asset
+    type MaxPriceHistoryDepth: Get<u32>;
+    /// Number of blocks between marketplace stats events
+    type StatsEmitInterval: Get<Self::BlockNumber>;
+    /// Number of blocks in a day, used for daily class volume
+    type BlocksPerDay: Get<Self::BlockNumber>;
+    /// Maximum number of recipients in a single airdrop
+    type MaxAirdropRecipients: Get<u32>;
+    /// Maximum number of open claims
+    type MaxClaims: Get<u64>;
+    /// Escrow free balance below which a warning event is emitted
+    type EscrowWarningThreshold: Get<BalanceOf<Self>>;
+    /// Lowest price an asset can be listed for
+    type MinListingPrice: Get<BalanceOf<Self>>;
+    /// Minimum number of blocks between escrow balance warnings
+    type EscrowAlertCooldown: Get<Self::BlockNumber>;
+    /// Maximum length of a class creator address
+    type MaxCreatorAddressLength: Get<u32>;
+    /// Maximum number of search tags on an asset
+    type MaxTagsPerToken: Get<u32>;
+    /// Maximum length of a search tag
+    type MaxTagLength: Get<u32>;
+    /// Maximum number of emotes an account can post on a single asset
+    type MaxEmotesPerAccountPerAsset: Get<u32>;
+    /// Maximum number of game attributes on an asset
+    type MaxAttributeCount: Get<u32>;
+    /// Maximum number of bids that can be placed on a single auction
+    type MaxBidsPerAuction: Get<u32>;
+    /// Amount reserved from the seller while their auction is open
+    type AuctionDeposit: Get<BalanceOf<Self>>;
+    /// Share of the starting price slashed from the auction deposit to the treasury
+    /// when the seller cancels an auction before it ends
+    type EarlyUnlistPenalty: Get<Permill>;
+    /// Minimum number of blocks between transfers of an asset from one account
+    /// to the same other account. 0 disables the cooldown.
+    type TransferCooldownPeriod: Get<Self::BlockNumber>;
+    /// Minimum number of blocks a bought asset must be held before it can be listed.
+    /// 0 disables the holding period.
+    type MinHoldingPeriod: Get<Self::BlockNumber>;
+    /// Maximum number of listings an account can create in a single block
+    type MaxListingsPerAccountPerBlock: Get<u32>;
+    /// Maximum number of expired offers released in on_initialize. The rest of the
+    /// block's expired offers are released in on_finalize.
+    type MaxOfferExpiriesOnInitialize: Get<u32>;
+    /// Priority of the unsigned transactions expiring listings
+    type UnsignedPriority: Get<TransactionPriority>;
+    /// Share of every sale price paid to the fee recipient. Zero disables the fee.
+    type MarketplaceFeeRate: Get<Perbill>;
+    /// Account receiving the marketplace fee
+    type FeeRecipient: Get<Self::AccountId>;
+    /// Origin allowed to update marketplace fees
+    type GovernanceOrigin: EnsureOrigin<Self::Origin>;
+    /// Origin allowed to verify or reject collections
+    type VerificationOrigin: EnsureOrigin<Self::Origin>;
+    /// Origin allowed to ban accounts from the market
+    type ForceOrigin: EnsureOrigin<Self::Origin>;
+    /// Origin allowed to pause and unpause the market
+    type PauseOrigin: EnsureOrigin<Self::Origin>;
+    /// Number of blocks a swap must stay open before either party can dispute it
+    type SwapTimeoutBlocks: Get<Self::BlockNumber>;
+    /// Origin allowed to resolve swap disputes
+    type ArbitrationOrigin: EnsureOrigin<Self::Origin>;
+    /// Weight information for extrinsics in this pallet
+    type WeightInfo: WeightInfo;
 }
 
 /// Class Id
@@ -95,10 +380,79 @@ pub type ClassIdOf<T> = <T as orml_nft::Config>::ClassId;
 /// Token Id
 pub type TokenIdOf<T> = <T as orml_nft::Config>::TokenId;
 /// Listing Data
-pub type ListingOf<T> =
-    Listing<ClassIdOf<T>, TokenIdOf<T>, <T as system::Config>::AccountId, BalanceOf<T>>;
+pub type ListingOf<T> = Listing<
+    ClassIdOf<T>,
+    TokenIdOf<T>,
+    <T as system::Config>::AccountId,
+    BalanceOf<T>,
+    <T as system::Config>::BlockNumber,
+    <T as Config>::CurrencyId,
+>;
+/// Secret Listing Data
+pub type SecretListingOf<T> = SecretListing<
+    ClassIdOf<T>,
+    TokenIdOf<T>,
+    <T as system::Config>::AccountId,
+    <T as system::Config>::Hash,
+>;
 /// Claim Data
-pub type ClaimOf<T> = Claim<ClassIdOf<T>, TokenIdOf<T>, <T as system::Config>::AccountId>;
+pub type ClaimOf<T> = Claim<
+    ClassIdOf<T>,
+    TokenIdOf<T>,
+    <T as system::Config>::AccountId,
+    <T as system::Config>::BlockNumber,
+>;
+/// Auction Data
+pub type AuctionOf<T> = Auction<
+    ClassIdOf<T>,
+    TokenIdOf<T>,
+    <T as system::Config>::AccountId,
+    BalanceOf<T>,
+    <T as system::Config>::BlockNumber,
+>;
+/// Dutch Auction Data
+pub type DutchAuctionOf<T> = DutchAuction<
+    ClassIdOf<T>,
+    TokenIdOf<T>,
+    <T as system::Config>::AccountId,
+    BalanceOf<T>,
+    <T as system::Config>::BlockNumber,
+>;
+/// Order Data
+pub type OrderOf<T> = Order<
+    ClassIdOf<T>,
+    TokenIdOf<T>,
+    <T as system::Config>::AccountId,
+    BalanceOf<T>,
+    <T as system::Config>::BlockNumber,
+>;
+/// Bundle Listing Data
+pub type BundleListingOf<T> =
+    BundleListing<ClassIdOf<T>, TokenIdOf<T>, <T as system::Config>::AccountId, BalanceOf<T>>;
+/// Offer Data
+pub type OfferOf<T> = Offer<
+    ClassIdOf<T>,
+    TokenIdOf<T>,
+    <T as system::Config>::AccountId,
+    BalanceOf<T>,
+    <T as system::Config>::BlockNumber,
+    <T as Config>::CurrencyId,
+>;
+/// Swap Proposal Data
+pub type SwapProposalOf<T> = SwapProposal<
+    ClassIdOf<T>,
+    TokenIdOf<T>,
+    <T as system::Config>::AccountId,
+    <T as system::Config>::BlockNumber,
+>;
+/// Token Detail
+pub type TokenDetailOf<T> = TokenDetail<
+    <T as system::Config>::AccountId,
+    ClassIdOf<T>,
+    TokenIdOf<T>,
+    BalanceOf<T>,
+    <T as system::Config>::BlockNumber,
+>;
 type BalanceOf<T> =
     <<T as Config>::Currency as Currency<<T as system::Config>::AccountId>>::Balance;
 
@@ -108,23 +462,243 @@ decl_storage! {
     /// Get a listing by the listing_id
     pub Listings get(fn listings):
         map hasher(twox_64_concat) ListingId => Option<ListingOf<T>>;
-    /// Get all listings ids by an account
-    pub ListingsByOwner get(fn listings_by_owner):
-        map hasher(blake2_128_concat) T::AccountId => Option<Vec<ListingId>>;
+    /// Listing ids by the account that listed them
+    pub ListingsByOwner:
+        double_map hasher(blake2_128_concat) T::AccountId, hasher(twox_64_concat) ListingId => ();
+    /// Get the id of the open listing of an asset
+    pub ListingsByAsset get(fn listings_by_asset):
+        map hasher(twox_64_concat) (ClassIdOf<T>, TokenIdOf<T>) => Option<ListingId>;
+    /// Get the ids of the open listings in a class, in the order they were listed
+    pub ListingsByClass get(fn listings_by_class):
+        map hasher(twox_64_concat) ClassIdOf<T> => Vec<ListingId>;
     /// Get a vector of all listings. Used as a quick lookup.
     pub AllListings get(fn all_listings): Vec<(ClassIdOf<T>, TokenIdOf<T>)>;
     /// Get the next listing id
     pub NextListingId get(fn next_listing_id): ListingId;
+    /// Get an auction by the auction_id
+    pub Auctions get(fn auctions):
+        map hasher(twox_64_concat) AuctionId => Option<AuctionOf<T>>;
+    /// Get a dutch auction by the auction_id. Shares ids with English auctions.
+    pub DutchAuctions get(fn dutch_auctions):
+        map hasher(twox_64_concat) AuctionId => Option<DutchAuctionOf<T>>;
+    /// Get a vector of all assets in auctions. Used as a quick lookup.
+    pub AllAuctions get(fn all_auctions): Vec<(ClassIdOf<T>, TokenIdOf<T>)>;
+    /// Get the next auction id
+    pub NextAuctionId get(fn next_auction_id): AuctionId;
+    /// Get the amount a bidder has reserved in an auction
+    pub BidsByAuction get(fn bids_by_auction):
+        double_map hasher(twox_64_concat) AuctionId, hasher(blake2_128_concat) T::AccountId => BalanceOf<T>;
+    /// Get a bid placed on an open auction by its index [bidder, amount, block]
+    pub AuctionBidHistory get(fn auction_bid_history):
+        double_map hasher(twox_64_concat) AuctionId, hasher(twox_64_concat) u32 => Option<(T::AccountId, BalanceOf<T>, T::BlockNumber)>;
+    /// Get the number of bids placed on an open auction
+    pub AuctionBidCount get(fn auction_bid_count):
+        map hasher(twox_64_concat) AuctionId => u32;
+    /// Get the deposit reserved from the seller of an auction
+    pub AuctionDeposits get(fn auction_deposits):
+        map hasher(twox_64_concat) AuctionId => BalanceOf<T>;
+    /// Get an offer by the offer_id
+    pub Offers get(fn offers):
+        map hasher(twox_64_concat) OfferId => Option<OfferOf<T>>;
+    /// Check if an offer is open on an asset
+    pub OffersByAsset get(fn offers_by_asset):
+        double_map hasher(twox_64_concat) (ClassIdOf<T>, TokenIdOf<T>), hasher(twox_64_concat) OfferId => ();
+    /// Offers to release at a block, the first one after their expiry block
+    pub OfferExpiryQueue get(fn offer_expiry_queue):
+        double_map hasher(twox_64_concat) T::BlockNumber, hasher(twox_64_concat) OfferId => ();
+    /// Check if an offer must be refreshed by its buyer before it can be accepted
+    pub InvalidatedOffers get(fn invalidated_offers):
+        map hasher(twox_64_concat) OfferId => ();
+    /// Offer ids by the account that made them
+    pub OffersByBuyer:
+        double_map hasher(blake2_128_concat) T::AccountId, hasher(twox_64_concat) OfferId => ();
+    /// Get the number of open offers made by an account
+    pub OffersGiven get(fn offers_given):
+        map hasher(blake2_128_concat) T::AccountId => u32;
+    /// Get the next offer id
+    pub NextOfferId get(fn next_offer_id): OfferId;
+    /// Get a swap proposal by the swap id
+    pub SwapProposals get(fn swap_proposals):
+        map hasher(twox_64_concat) SwapId => Option<SwapProposalOf<T>>;
+    /// Get the next swap id
+    pub NextSwapId get(fn next_swap_id): SwapId;
+    /// Get the dispute raised over a swap
+    pub SwapDisputes get(fn swap_disputes):
+        map hasher(twox_64_concat) SwapId => Option<DisputeStatus<T::AccountId>>;
+    /// Get a bundle listing by the bundle listing id
+    pub BundleListings get(fn bundle_listings):
+        map hasher(twox_64_concat) BundleListingId => Option<BundleListingOf<T>>;
+    /// Get the next bundle listing id
+    pub NextBundleListingId get(fn next_bundle_listing_id): BundleListingId;
+    /// Get a past sale of an asset by the order id
+    pub OrderHistory get(fn order_history):
+        double_map hasher(twox_64_concat) (ClassIdOf<T>, TokenIdOf<T>), hasher(twox_64_concat) OrderId => Option<OrderOf<T>>;
+    /// Get the order ids of an asset, oldest first
+    pub OrdersByAsset get(fn orders_by_asset):
+        map hasher(twox_64_concat) (ClassIdOf<T>, TokenIdOf<T>) => Vec<OrderId>;
+    /// Get the next order id
+    pub NextOrderId get(fn next_order_id): OrderId;
     /// Get one or more claims by AccountId or a single claim including the claim_id
     pub OpenClaims get(fn open_claims):
         double_map hasher(blake2_128_concat) T::AccountId, hasher(twox_64_concat) ClaimId => Option<ClaimOf<T>>;
     /// Get a vector of all claims. Used as a quick lookup.
     pub AllClaims get(fn all_claims): Vec<(ClassIdOf<T>, TokenIdOf<T>)>;
+    /// Check if an asset is in an open claim
+    pub ClaimIndex get(fn claim_index):
+        map hasher(twox_64_concat) (ClassIdOf<T>, TokenIdOf<T>) => bool;
     /// Get the next claim id
     pub NextClaimId get(fn next_claim_id): ClaimId;
+    /// Get the receiver of an open claim
+    pub ClaimReceivers get(fn claim_receivers):
+        map hasher(twox_64_concat) ClaimId => Option<T::AccountId>;
     /// Emotes used by the wallet
     pub Emotes get(fn emotes):
         double_map hasher(twox_64_concat) (ClassIdOf<T>, TokenIdOf<T>), hasher(twox_64_concat) T::AccountId => Vec<Vec<u8>>;
+    /// Get the number of times an emoji has been posted on an asset
+    pub EmoteCountByAsset get(fn emote_count_by_asset):
+        double_map hasher(twox_64_concat) (ClassIdOf<T>, TokenIdOf<T>), hasher(blake2_128_concat) Vec<u8> => u64;
+    /// Get the account approved to move an asset once on behalf of its owner
+    pub TokenApprovals get(fn token_approvals):
+        map hasher(twox_64_concat) (ClassIdOf<T>, TokenIdOf<T>) => Option<T::AccountId>;
+    /// Check if an operator may act on all of an owner's assets in a class
+    pub ClassApprovals get(fn class_approvals):
+        double_map hasher(blake2_128_concat) T::AccountId, hasher(blake2_128_concat) (ClassIdOf<T>, T::AccountId) => bool;
+    /// Check if an operator may act on all of an owner's assets
+    pub Approvals get(fn approvals):
+        double_map hasher(blake2_128_concat) T::AccountId, hasher(blake2_128_concat) T::AccountId => bool;
+    /// Get the message attached to an asset the last time it was gifted
+    pub GiftMessages get(fn gift_messages):
+        map hasher(twox_64_concat) (ClassIdOf<T>, TokenIdOf<T>) => Option<Vec<u8>>;
+    /// Get a secret listing by the secret_listing_id
+    pub SecretListings get(fn secret_listings):
+        map hasher(twox_64_concat) SecretListingId => Option<SecretListingOf<T>>;
+    /// Get the next secret listing id
+    pub NextSecretListingId get(fn next_secret_listing_id): SecretListingId;
+    /// Get the id of the open secret listing of an asset
+    pub SecretListingsByAsset get(fn secret_listings_by_asset):
+        map hasher(twox_64_concat) (ClassIdOf<T>, TokenIdOf<T>) => Option<SecretListingId>;
+    /// Get the ids of the open secret listings in a class, in the order they were listed
+    pub SecretListingsByClass get(fn secret_listings_by_class):
+        map hasher(twox_64_concat) ClassIdOf<T> => Vec<SecretListingId>;
+    /// Accounts receiving a share of every sale
+    pub FeeSplits get(fn fee_splits): Vec<(T::AccountId, Perbill)>;
+    /// Get the issuer and reserved currency reward paid out with a claim
+    pub ClaimRewards get(fn claim_rewards):
+        map hasher(twox_64_concat) ClaimId => Option<(T::AccountId, BalanceOf<T>)>;
+    /// Get the verification status of a collection
+    pub VerifiedCollections get(fn verified_collections):
+        map hasher(twox_64_concat) ClassIdOf<T> => Option<VerificationStatus>;
+    /// Get the most recent sale prices of an asset, oldest first
+    pub TokenPriceHistory get(fn token_price_history):
+        map hasher(twox_64_concat) (ClassIdOf<T>, TokenIdOf<T>) => Vec<(BalanceOf<T>, T::BlockNumber)>;
+    /// Get the number of assets currently listed
+    pub ListingCount get(fn listing_count): u64;
+    /// Get the number of open claims
+    pub ClaimCount get(fn claim_count): u64;
+    /// Get the number of completed sales
+    pub OrderCount get(fn order_count): u64;
+    /// Get the total amount paid across all sales
+    pub TotalVolume get(fn total_volume): BalanceOf<T>;
+    /// Check if the market is paused
+    pub Paused get(fn paused): bool;
+    /// Get the last block an account is banned from the market
+    pub BlacklistedAccounts get(fn blacklisted_accounts):
+        map hasher(blake2_128_concat) T::AccountId => Option<T::BlockNumber>;
+    /// Get the block of the most recent sale in a class
+    pub ClassLastSaleBlock get(fn class_last_sale_block):
+        map hasher(twox_64_concat) ClassIdOf<T> => Option<T::BlockNumber>;
+    /// Get the price of the most recent sale in a class
+    pub ClassLastSalePrice get(fn class_last_sale_price):
+        map hasher(twox_64_concat) ClassIdOf<T> => Option<BalanceOf<T>>;
+    /// Get the number of completed sales in a class
+    pub ClassSalesCount get(fn class_sales_count):
+        map hasher(twox_64_concat) ClassIdOf<T> => u64;
+    /// Get the total amount paid across all sales in a class
+    pub ClassTotalVolume get(fn class_total_volume):
+        map hasher(twox_64_concat) ClassIdOf<T> => BalanceOf<T>;
+    /// Get the highest asking price among a class's open listings
+    pub HighestListingPrice get(fn highest_listing_price):
+        map hasher(twox_64_concat) ClassIdOf<T> => Option<BalanceOf<T>>;
+    /// Get the hard cap on tokens ever minted in a limited edition class
+    pub LimitedEditionCap get(fn limited_edition_cap):
+        map hasher(twox_64_concat) ClassIdOf<T> => Option<u32>;
+    /// Get the number of tokens ever minted in a class through the market. It
+    /// only numbers serials and never decreases; supply caps use `TotalMintedByClass`.
+    pub ClassMintedCount get(fn class_minted_count):
+        map hasher(twox_64_concat) ClassIdOf<T> => u32;
+    /// Get the number of tokens reported to the mint handler for a class,
+    /// including those minted outside the market. Never decreases on burn.
+    pub TotalMintedByClass get(fn total_minted_by_class):
+        map hasher(twox_64_concat) ClassIdOf<T> => u64;
+    /// Get the account that reserved a listing and the block the reservation ends
+    pub ReservedListings get(fn reserved_listings):
+        map hasher(twox_64_concat) ListingId => Option<(T::AccountId, T::BlockNumber)>;
+    /// Get the listings whose reservation ends at a block
+    pub ReservationExpiries get(fn reservation_expiries):
+        map hasher(twox_64_concat) T::BlockNumber => Vec<ListingId>;
+    /// Check if a class is kept off the secondary market
+    pub SecondaryMarketDisabled get(fn secondary_market_disabled):
+        map hasher(twox_64_concat) ClassIdOf<T> => bool;
+    /// Check if all trading is suspended for a class
+    pub NonTradeable get(fn non_tradeable):
+        map hasher(twox_64_concat) ClassIdOf<T> => bool;
+    /// Get the block of the last escrow balance warning
+    pub LastEscrowAlert get(fn last_escrow_alert): Option<T::BlockNumber>;
+    /// Get the block an asset was minted in. Kept after the asset is burned.
+    pub TokenMintedAt get(fn token_minted_at):
+        map hasher(twox_64_concat) (ClassIdOf<T>, TokenIdOf<T>) => Option<T::BlockNumber>;
+    /// Get the block the first asset of a class was minted in
+    pub ClassFirstMintBlock get(fn class_first_mint_block):
+        map hasher(twox_64_concat) ClassIdOf<T> => Option<T::BlockNumber>;
+    /// Get the asset minted with a serial number in a class
+    pub SerialNumberRegistry get(fn serial_number_registry):
+        double_map hasher(twox_64_concat) ClassIdOf<T>, hasher(twox_64_concat) u64 => Option<TokenIdOf<T>>;
+    /// Get a game hosted by the runtime
+    pub RegisteredGames get(fn registered_games):
+        map hasher(twox_64_concat) u32 => Option<GameInfo>;
+    /// Check if a class belongs to a game
+    pub ClassesByGame get(fn classes_by_game):
+        double_map hasher(twox_64_concat) u32, hasher(twox_64_concat) ClassIdOf<T> => ();
+    /// Check if an asset belongs to a game
+    pub AssetsByGame get(fn assets_by_game):
+        double_map hasher(twox_64_concat) u32, hasher(twox_64_concat) (ClassIdOf<T>, TokenIdOf<T>) => ();
+    /// Check if an asset carries a lowercase search tag
+    pub TokenTags get(fn token_tags):
+        double_map hasher(blake2_128_concat) Vec<u8>, hasher(twox_64_concat) (ClassIdOf<T>, TokenIdOf<T>) => ();
+    /// Get the lowercase search tags of an asset
+    pub TagsByToken get(fn tags_by_token):
+        map hasher(twox_64_concat) (ClassIdOf<T>, TokenIdOf<T>) => Vec<Vec<u8>>;
+    /// Get the price an asset was most recently listed at
+    pub LastListingPrice get(fn last_listing_price):
+        map hasher(twox_64_concat) (ClassIdOf<T>, TokenIdOf<T>) => Option<BalanceOf<T>>;
+    /// Get the estimated value of an account's assets, from their latest listing prices
+    pub AccountNFTValue get(fn account_nft_value):
+        map hasher(blake2_128_concat) T::AccountId => BalanceOf<T>;
+    /// Get the block an asset was last transferred from one account to another
+    pub TransferCooldownMap get(fn transfer_cooldown_map):
+        double_map hasher(blake2_128_concat) (T::AccountId, T::AccountId), hasher(twox_64_concat) (ClassIdOf<T>, TokenIdOf<T>) => Option<T::BlockNumber>;
+    /// Get the number of listings an account created in a block. Cleared at the start of the next block.
+    pub ListingsCreatedThisBlock get(fn listings_created_this_block):
+        map hasher(blake2_128_concat) (T::AccountId, T::BlockNumber) => u32;
+    /// Get the name and version of the pallet
+    pub PalletMetadata get(fn pallet_metadata): Option<MarketPalletInfo>;
+    /// Storage version of the pallet
+    StorageVersion get(fn storage_version): Releases;
+  }
+  add_extra_genesis {
+    config(pallet_metadata): Option<MarketPalletInfo>;
+    build(|config: &GenesisConfig| {
+      if let Some(info) = &config.pallet_metadata {
+        assert!(
+          info.name.len() <= MAX_PALLET_NAME_LENGTH
+            && info.description.len() <= MAX_PALLET_DESCRIPTION_LENGTH,
+          "pallet metadata is too long"
+        );
+        PalletMetadata::put(info);
+      }
+      // New chains start in the current layout, keep this on the latest release
+      StorageVersion::put(Releases::V15_0_0);
+    });
   }
 }
 
@@ -135,9 +709,12 @@ decl_event!(
     ClassId = ClassIdOf<T>,
     TokenId = TokenIdOf<T>,
     Balance = BalanceOf<T>,
+    BlockNumber = <T as frame_system::Config>::BlockNumber,
   {
     /// Asset successfully transferred through the wallet [from, to, classId, tokenId]
     MarketAssetTransferred(AccountId, AccountId, ClassId, TokenId),
+    /// Asset transferred with a memo [from, to, classId, tokenId, memo]
+    MarketAssetTransferredWithMemo(AccountId, AccountId, ClassId, TokenId, Vec<u8>),
     /// Asset successfully burned through the wallet [owner, classId, tokenId]
     MarketAssetBurned(AccountId, ClassId, TokenId),
     /// Asset successfully listed through the wallet [owner, price, listingId,, classId, tokenId]
@@ -154,6 +731,177 @@ decl_event!(
     MarketAssetBuySuccess(AccountId, AccountId, ListingId, Balance),
     /// New Emote posted [poster, classId, tokenId, emote]
     MarketAssetEmotePosted(AccountId, ClassId, TokenId, Vec<u8>),
+    /// Emote removed [poster, classId, tokenId, index]
+    MarketAssetEmoteRemoved(AccountId, ClassId, TokenId, u32),
+    /// Spender approved to move an asset once [owner, spender, classId, tokenId]
+    MarketTokenApproved(AccountId, AccountId, ClassId, TokenId),
+    /// Asset approval revoked [owner, classId, tokenId]
+    MarketTokenApprovalRevoked(AccountId, ClassId, TokenId),
+    /// Spender approved to move a batch of assets once [owner, spender, assets]
+    MarketBatchTokenApproved(AccountId, AccountId, Vec<(ClassId, TokenId)>),
+    /// Batch of assets transferred [from, to, count]
+    MarketBatchTransferred(AccountId, AccountId, u32),
+    /// Listing price changed [seller, listingId, oldPrice, newPrice]
+    MarketListingPriceUpdated(AccountId, ListingId, Balance, Balance),
+    /// Offer made on an asset [buyer, offerId, classId, tokenId, amount]
+    MarketOfferMade(AccountId, OfferId, ClassId, TokenId, Balance),
+    /// Offer accepted by the asset owner [seller, buyer, offerId, amount]
+    MarketOfferAccepted(AccountId, AccountId, OfferId, Balance),
+    /// Offer withdrawn by the buyer [offerId]
+    MarketOfferCancelled(OfferId),
+    /// Offer rejected by the asset owner [seller, buyer, offerId, amount]
+    MarketOfferRejected(AccountId, AccountId, OfferId, Balance),
+    /// Offer cleaned up after expiry [buyer, offerId, amount]
+    MarketOfferExpired(AccountId, OfferId, Balance),
+    /// Offers on a listed asset invalidated by a price change [listingId, count]
+    MarketOffersInvalidated(ListingId, u32),
+    /// Offer confirmed by the buyer at a new amount [buyer, offerId, amount]
+    MarketOfferRefreshed(AccountId, OfferId, Balance),
+    /// Swap proposed to a counterparty [initiator, counterparty, swapId]
+    MarketSwapProposed(AccountId, AccountId, SwapId),
+    /// Swap accepted and both assets exchanged
+    /// [initiator, counterparty, offeredClassId, offeredTokenId, requestedClassId, requestedTokenId]
+    MarketSwapCompleted(AccountId, AccountId, ClassId, TokenId, ClassId, TokenId),
+    /// Swap withdrawn by the initiator [initiator, swapId]
+    MarketSwapCancelled(AccountId, SwapId),
+    /// Swap disputed by one of its parties [complainant, swapId]
+    MarketSwapDisputeRaised(AccountId, SwapId),
+    /// Swap dispute resolved and the offered asset returned [swapId, inFavorOf]
+    MarketSwapDisputeResolved(SwapId, AccountId),
+    /// Expired listing returned to the seller [seller, listingId, classId, tokenId]
+    MarketListingExpired(AccountId, ListingId, ClassId, TokenId),
+    /// Marketplace fee taken from a sale [amount, recipient]
+    MarketFeeCollected(Balance, AccountId),
+    /// Assets listed together as a bundle [seller, bundleListingId, assets, price]
+    MarketBundleListed(AccountId, BundleListingId, Vec<(ClassId, TokenId)>, Balance),
+    /// Bundle bought [seller, buyer, bundleListingId, price]
+    MarketBundlePurchased(AccountId, AccountId, BundleListingId, Balance),
+    /// Some assets of a bundle bought [seller, buyer, bundleListingId, count, price]
+    MarketBundlePartiallyPurchased(AccountId, AccountId, BundleListingId, u32, Balance),
+    /// Bundle removed from the market [seller, bundleListingId]
+    MarketBundleUnlisted(AccountId, BundleListingId),
+    /// Assets burned in a batch [owner, count]
+    MarketBatchBurned(AccountId, u32),
+    /// Expired claim taken back by its creator [creator, receiver, classId, tokenId]
+    MarketClaimExpired(AccountId, AccountId, ClassId, TokenId),
+    /// Open claim revoked by its creator [creator, receiver, classId, tokenId]
+    MarketClaimRevoked(AccountId, AccountId, ClassId, TokenId),
+    /// Claim sent to another receiver [creator, oldReceiver, newReceiver, claimId]
+    MarketClaimReceiverChanged(AccountId, AccountId, AccountId, ClaimId),
+    /// Claims created in a batch [creator, count]
+    MarketBatchClaimCreated(AccountId, u32),
+    /// Approvals revoked for a batch of assets [owner, assets]
+    MarketBatchTokenApprovalRevoked(AccountId, Vec<(ClassId, TokenId)>),
+    /// Operator approved for a class [owner, operator, classId]
+    MarketClassApprovalSet(AccountId, AccountId, ClassId),
+    /// Operator approval revoked for a class [owner, operator, classId]
+    MarketClassApprovalRevoked(AccountId, AccountId, ClassId),
+    /// Operator approved for all of an owner's assets [owner, operator]
+    MarketOperatorApproved(AccountId, AccountId),
+    /// Operator approval revoked [owner, operator]
+    MarketOperatorRevoked(AccountId, AccountId),
+    /// Asset gifted with a message [from, to, classId, tokenId, message]
+    MarketAssetGifted(AccountId, AccountId, ClassId, TokenId, Vec<u8>),
+    /// Asset listed with a hidden price [owner, secretListingId, classId, tokenId]
+    MarketAssetSecretListed(AccountId, SecretListingId, ClassId, TokenId),
+    /// Secret listing removed by the seller [owner, secretListingId, classId, tokenId]
+    MarketAssetSecretUnlisted(AccountId, SecretListingId, ClassId, TokenId),
+    /// Secret listing bought at the revealed price [seller, buyer, secretListingId, price]
+    MarketSecretBuySuccess(AccountId, AccountId, SecretListingId, Balance),
+    /// Marketplace fee splits updated [splits]
+    MarketFeeSplitsUpdated(Vec<(AccountId, Perbill)>),
+    /// Currency reward attached to a claim [issuer, receiver, claimId, reward]
+    MarketClaimRewardCreated(AccountId, AccountId, ClaimId, Balance),
+    /// Claim reward paid to the receiver [receiver, claimId, reward]
+    MarketClaimRewardPaid(AccountId, ClaimId, Balance),
+    /// Claim reward cancelled and refunded to the issuer [issuer, claimId, reward]
+    MarketClaimRewardCancelled(AccountId, ClaimId, Balance),
+    /// Collection owner applied for verification [owner, classId]
+    MarketCollectionVerificationRequested(AccountId, ClassId),
+    /// Collection verified [classId]
+    MarketCollectionVerified(ClassId),
+    /// Collection verification rejected [classId]
+    MarketCollectionVerificationRejected(ClassId),
+    /// Periodic marketplace snapshot [listingCount, orderCount, totalVolume, blockNumber]
+    MarketStats(u64, u64, Balance, BlockNumber),
+    /// Account banned from the market [account, untilBlock]
+    MarketAccountBlacklisted(AccountId, BlockNumber),
+    /// Account ban lifted [account]
+    MarketAccountUnblacklisted(AccountId),
+    /// Class statistics recounted from storage [classId]
+    MarketClassStatsRecalculated(ClassId),
+    /// Market paused
+    MarketPaused,
+    /// Market unpaused
+    MarketUnpaused,
+    /// Class created [owner, classId]
+    MarketClassCreated(AccountId, ClassId),
+    /// Class created with a hard mint cap [classId, cap]
+    MarketLimitedEditionClassCreated(ClassId, u32),
+    /// Class max supply set [classId, maxSupply]
+    MarketClassMaxSupplySet(ClassId, u32),
+    /// Class assets locked until a block [classId, transferableAfter]
+    MarketClassTransferableAfterSet(ClassId, BlockNumber),
+    /// Class royalty split set [classId, splits]
+    MarketRoyaltySplitSet(ClassId, Vec<(AccountId, Perbill)>),
+    /// Royalties paid out on a sale [classId, totalRoyalty]
+    MarketRoyaltiesDistributed(ClassId, Balance),
+    /// Asset minted [minter, classId, tokenId]
+    MarketAssetMinted(AccountId, ClassId, TokenId),
+    /// Assets minted to a list of recipients [owner, classId, count]
+    MarketAirdropCompleted(AccountId, ClassId, u32),
+    /// Listing reserved while the seller negotiates [seller, listingId, expiryBlock]
+    MarketListingReserved(AccountId, ListingId, BlockNumber),
+    /// Listing reservation ended [listingId]
+    MarketListingUnreserved(ListingId),
+    /// Secondary market toggled for a class [classId, disabled]
+    MarketSecondaryMarketSet(ClassId, bool),
+    /// Trading toggled for a class [classId, tradeable]
+    MarketClassTradeabilityChanged(ClassId, bool),
+    /// Escrow free balance dropped below the warning threshold [freeBalance]
+    MarketEscrowBalanceLow(Balance),
+    /// Item equipped to a character [owner, itemClassId, itemTokenId, characterClassId, characterTokenId]
+    MarketAssetEquipped(AccountId, ClassId, TokenId, ClassId, TokenId),
+    /// Item unequipped [owner, classId, tokenId]
+    MarketAssetUnequipped(AccountId, ClassId, TokenId),
+    /// Class creator address updated [classId, address]
+    MarketCreatorAddressUpdated(ClassId, Vec<u8>),
+    /// Class schema version updated [classId, oldVersion, newVersion]
+    MarketClassSchemaUpdated(ClassId, u32, u32),
+    /// Asset properties updated [classId, tokenId, schemaVersion]
+    MarketTokenPropertiesUpdated(ClassId, TokenId, u32),
+    /// Asset game attribute set [classId, tokenId, key, value]
+    MarketTokenAttributeSet(ClassId, TokenId, Vec<u8>, Vec<u8>),
+    /// Class ownership transferred [classId, oldOwner, newOwner]
+    MarketClassOwnershipTransferred(ClassId, AccountId, AccountId),
+    /// Listed asset could not be returned from escrow [listingId]
+    MarketEscrowRecoveryFailed(ListingId),
+    /// Game registered [gameId]
+    MarketGameRegistered(u32),
+    /// Search tag added to an asset [classId, tokenId, tag]
+    MarketTokenTagAdded(ClassId, TokenId, Vec<u8>),
+    /// Search tag removed from an asset [classId, tokenId, tag]
+    MarketTokenTagRemoved(ClassId, TokenId, Vec<u8>),
+    /// Open claims processed one by one [receiver, (claimId, claimed)]
+    MarketBatchClaimResult(AccountId, Vec<(ClaimId, bool)>),
+    /// Pallet name and version updated [version]
+    MarketPalletMetadataUpdated(u32),
+    /// Auction created [seller, auctionId, classId, tokenId, startingPrice, endBlock]
+    MarketAuctionCreated(AccountId, AuctionId, ClassId, TokenId, Balance, BlockNumber),
+    /// Bid placed on an auction [bidder, auctionId, amount]
+    MarketBidPlaced(AccountId, AuctionId, Balance),
+    /// Auction won by the highest bidder [seller, winner, auctionId, amount]
+    MarketAuctionSettled(AccountId, AccountId, AuctionId, Balance),
+    /// Auction ended without a sale and the asset went back to the seller [auctionId]
+    MarketAuctionCancelled(AuctionId),
+    /// Part of the auction deposit sent to the treasury for cancelling early [seller, auctionId, penalty]
+    MarketEarlyUnlistPenaltyApplied(AccountId, AuctionId, Balance),
+    /// Dutch auction created [seller, auctionId, classId, tokenId, startPrice, floorPrice, startBlock, endBlock]
+    MarketDutchAuctionCreated(AccountId, AuctionId, ClassId, TokenId, Balance, Balance, BlockNumber, BlockNumber),
+    /// Asset bought from a dutch auction [seller, buyer, auctionId, price]
+    MarketDutchAuctionPurchased(AccountId, AccountId, AuctionId, Balance),
+    /// Dutch auction cancelled and the asset returned to the seller [auctionId]
+    MarketDutchAuctionCancelled(AuctionId),
   }
 );
 
@@ -193,8 +941,200 @@ decl_error! {
     NoAvailableOrderId,
     /// Invalid Emote
     InvalidEmote,
+    /// The account has posted the maximum number of emotes on this asset
+    EmoteLimitReached,
+    /// No emote of the sender at this index on the asset
+    InvalidEmoteIndex,
     /// No Permission for this action
     NoPermission,
+    /// Asset has no approved spender
+    ApprovalNotFound,
+    /// Too many assets in a single batch
+    BatchTooLarge,
+    /// Gift message is too long
+    MessageTooLong,
+    /// Transfer memo is too long
+    MemoTooLong,
+    /// Maximum secret listings in Escrow
+    NoAvailableSecretListingId,
+    /// Revealed price and nonce do not match the listing
+    InvalidPriceReveal,
+    /// Price is below the minimum listing price
+    PriceTooLow,
+    /// Fee splits add up to more than the sale price
+    InvalidFeeSplits,
+    /// Too many fee split recipients
+    TooManyFeeSplitRecipients,
+    /// Collection is not in a state that allows this verification step
+    InvalidVerificationStatus,
+    /// Account is banned from the market
+    AccountBlacklisted,
+    /// Market is paused
+    ModulePaused,
+    /// Account is not banned
+    AccountNotFound,
+    /// Mint quantity must be at least one
+    InvalidQuantity,
+    /// Minting would exceed the limited edition cap
+    LimitedEditionCapReached,
+    /// Minting would take the class past its max supply
+    MaxSupplyExceeded,
+    /// The asset was bought too recently to be listed
+    HoldingPeriodNotMet,
+    /// The account already created the maximum number of listings this block
+    RateLimitExceeded,
+    /// The class already has a supply cap
+    MaxSupplyAlreadySet,
+    /// Max supply is not above the number of assets already minted
+    InvalidMaxSupply,
+    /// Assets of the class can't be moved before its transferable after block
+    AssetNotYetTransferable,
+    /// The transferable after block must be in the future
+    InvalidTransferableAfter,
+    /// Too many collaborators in a royalty split
+    TooManyRoyaltyRecipients,
+    /// Royalty split shares add up to more than the sale price
+    InvalidRoyaltySplit,
+    /// Too many recipients in a single airdrop
+    TooManyAirdropRecipients,
+    /// Listing is reserved and cannot be bought
+    ListingReserved,
+    /// Listing is not reserved
+    ListingNotReserved,
+    /// Duration must be at least one block
+    InvalidDuration,
+    /// Asset data could not be updated
+    AssetDataUpdateFailed,
+    /// Class cannot be listed on the secondary market
+    SecondaryMarketDisabled,
+    /// Assets of this class cannot be transferred
+    ClassTransferRestricted,
+    /// Assets of this class cannot be listed
+    ClassListingRestricted,
+    /// Assets of this class cannot be burned
+    ClassBurnRestricted,
+    /// Trading is suspended for this class
+    ClassNonTradeable,
+    /// Item is already equipped
+    AlreadyEquipped,
+    /// Item is not equipped
+    NotEquipped,
+    /// An asset cannot be equipped to itself
+    InvalidEquipTarget,
+    /// Creator address is not valid UTF-8
+    InvalidCreatorAddress,
+    /// Creator address is too long
+    CreatorAddressTooLong,
+    /// Schema versions can only be increased
+    InvalidSchemaVersion,
+    /// Properties don't follow the class's current schema version
+    SchemaVersionMismatch,
+    /// The asset already has the maximum number of game attributes
+    TooManyAttributes,
+    /// The auction already has the maximum number of bids
+    TooManyBids,
+    /// Escrow no longer holds the listed asset
+    InsufficientEscrowBalance,
+    /// Game id is already registered
+    GameAlreadyRegistered,
+    /// Game id is not registered
+    GameNotRegistered,
+    /// Tag is empty
+    InvalidTag,
+    /// Tag is too long
+    TagTooLong,
+    /// Asset has the maximum number of tags
+    TooManyTags,
+    /// Asset already has the tag
+    TagAlreadyAdded,
+    /// Asset doesn't have the tag
+    TagNotFound,
+    /// Asset was transferred between the same accounts too recently
+    TransferCooldownActive,
+    /// Pallet name or description is too long
+    InvalidPalletMetadata,
+    /// Royalty rate and recipient must be set together
+    InvalidRoyalty,
+    /// Auction not found
+    AuctionNotFound,
+    /// Maximum auction id reached
+    NoAvailableAuctionId,
+    /// Auction end block has already passed
+    InvalidAuctionEnd,
+    /// Auction no longer takes bids
+    AuctionEnded,
+    /// Auction can't be settled before its end block
+    AuctionNotEnded,
+    /// Bid is below the starting price or the current highest bid
+    BidTooLow,
+    /// Seller can't bid on their own auction
+    CannotBidOnOwnAuction,
+    /// Auction already has bids
+    AuctionHasBids,
+    /// Floor price is above the start price
+    InvalidDutchAuctionPrice,
+    /// Auction hasn't started yet
+    AuctionNotStarted,
+    /// Offer not found
+    OfferNotFound,
+    /// Maximum offer id reached
+    NoAvailableOfferId,
+    /// Offer expiry block has already passed
+    InvalidOfferExpiry,
+    /// Offer can no longer be accepted
+    OfferExpired,
+    /// Offer must be refreshed by the buyer after a listing price change
+    OfferInvalidated,
+    /// Offer can't be cleaned up before it expires
+    OfferNotExpired,
+    /// Owner can't make an offer on their own asset
+    CannotOfferOnOwnAsset,
+    /// Swap not found
+    SwapNotFound,
+    /// Maximum swap id reached
+    NoAvailableSwapId,
+    /// Swap expiry block has already passed
+    InvalidSwapExpiry,
+    /// Swap can no longer be accepted
+    SwapExpired,
+    /// An account can't swap with itself
+    CannotSwapWithSelf,
+    /// Swap hasn't been open for SwapTimeoutBlocks yet
+    SwapDisputeTooEarly,
+    /// Swap has already been disputed
+    SwapAlreadyDisputed,
+    /// Swap has no open dispute
+    SwapNotDisputed,
+    /// Swap is held by an open dispute
+    SwapDisputed,
+    /// Listing expiry block has already passed
+    InvalidListingExpiry,
+    /// Listings can't be priced in this currency
+    CurrencyNotAccepted,
+    /// Listing can no longer be bought
+    ListingExpired,
+    /// Listing can't be expired before its expiry block
+    ListingNotExpired,
+    /// Bundle listing not found
+    BundleListingNotFound,
+    /// Maximum bundle listing id reached
+    NoAvailableBundleListingId,
+    /// A bundle needs at least one asset
+    EmptyBundle,
+    /// Asset index is past the end of the bundle
+    AssetIndexOutOfBounds,
+    /// The same bundle asset was selected twice
+    DuplicateAssetIndex,
+    /// Soulbound assets can't change owner
+    SoulboundAsset,
+    /// Claim deadline has already passed
+    InvalidClaimDeadline,
+    /// Claim can no longer be claimed
+    ClaimExpired,
+    /// Claim can't be reclaimed before its deadline
+    ClaimNotExpired,
+    /// Batch lists don't have the same length
+    BatchLengthMismatch,
   }
 }
 
@@ -208,28 +1148,159 @@ decl_module! {
         const AllowBurn: bool = T::AllowBurn::get();
         const AllowEscrow: bool = T::AllowEscrow::get();
         const AllowClaim: bool = T::AllowClaim::get();
+        const MaxBatchSize: u32 = T::MaxBatchSize::get();
+        const MaxMessageLength: u32 = T::MaxMessageLength::get();
+        const MaxMemoLength: u32 = T::MaxMemoLength::get();
+        const MaxFeeSplitRecipients: u32 = T::MaxFeeSplitRecipients::get();
+        const MaxRoyaltyRecipients: u32 = T::MaxRoyaltyRecipients::get();
+        const MaxPriceHistoryDepth: u32 = T::MaxPriceHistoryDepth::get();
+        const StatsEmitInterval: T::BlockNumber = T::StatsEmitInterval::get();
+        const BlocksPerDay: T::BlockNumber = T::BlocksPerDay::get();
+        const MaxAirdropRecipients: u32 = T::MaxAirdropRecipients::get();
+        const MaxClaims: u64 = T::MaxClaims::get();
+        const EscrowWarningThreshold: BalanceOf<T> = T::EscrowWarningThreshold::get();
+        const MinListingPrice: BalanceOf<T> = T::MinListingPrice::get();
+        const EscrowAlertCooldown: T::BlockNumber = T::EscrowAlertCooldown::get();
+        const MaxCreatorAddressLength: u32 = T::MaxCreatorAddressLength::get();
+        const MaxTagsPerToken: u32 = T::MaxTagsPerToken::get();
+        const MaxTagLength: u32 = T::MaxTagLength::get();
+        const MaxEmotesPerAccountPerAsset: u32 = T::MaxEmotesPerAccountPerAsset::get();
+        const MaxAttributeCount: u32 = T::MaxAttributeCount::get();
+        const MaxBidsPerAuction: u32 = T::MaxBidsPerAuction::get();
+        const AuctionDeposit: BalanceOf<T> = T::AuctionDeposit::get();
+        const EarlyUnlistPenalty: Permill = T::EarlyUnlistPenalty::get();
+        const TransferCooldownPeriod: T::BlockNumber = T::TransferCooldownPeriod::get();
+        const MinHoldingPeriod: T::BlockNumber = T::MinHoldingPeriod::get();
+        const MaxListingsPerAccountPerBlock: u32 = T::MaxListingsPerAccountPerBlock::get();
+        const MaxOfferExpiriesOnInitialize: u32 = T::MaxOfferExpiriesOnInitialize::get();
+        const UnsignedPriority: TransactionPriority = T::UnsignedPriority::get();
+        const MarketplaceFeeRate: Perbill = T::MarketplaceFeeRate::get();
+        const FeeRecipient: T::AccountId = T::FeeRecipient::get();
+        const SwapTimeoutBlocks: T::BlockNumber = T::SwapTimeoutBlocks::get();
+
+        fn on_runtime_upgrade() -> Weight {
+            let mut weight = T::DbWeight::get().reads(1);
+
+            // The steps below move assets through orml_nft, which can only
+            // decode class and token data once it is in the current layout
+            if Self::storage_version() < Releases::V12_0_0 {
+                weight = weight.saturating_add(Self::migrate_asset_data());
+            }
+            if Self::storage_version() == Releases::V1_0_0 {
+                weight = weight.saturating_add(Self::migrate_to_class_escrow());
+            }
+            if Self::storage_version() == Releases::V2_0_0 {
+                weight = weight.saturating_add(Self::migrate_listings_by_owner());
+            }
+            if Self::storage_version() == Releases::V3_0_0 {
+                weight = weight.saturating_add(Self::migrate_claim_index());
+            }
+            if Self::storage_version() == Releases::V4_0_0 {
+                weight = weight.saturating_add(Self::migrate_listing_expiry());
+            }
+            if Self::storage_version() == Releases::V5_0_0 {
+                weight = weight.saturating_add(Self::migrate_claim_deadline());
+            }
+            if Self::storage_version() == Releases::V6_0_0 {
+                weight = weight.saturating_add(Self::migrate_listing_currency());
+            }
+            if Self::storage_version() == Releases::V7_0_0 {
+                weight = weight.saturating_add(Self::migrate_listings_by_asset());
+            }
+            if Self::storage_version() == Releases::V8_0_0 {
+                weight = weight.saturating_add(Self::migrate_listings_by_class());
+            }
+            if Self::storage_version() == Releases::V9_0_0 {
+                weight = weight.saturating_add(Self::migrate_order_paid_amount());
+            }
+            if Self::storage_version() == Releases::V10_0_0 {
+                weight = weight.saturating_add(Self::migrate_claim_count());
+            }
+            if Self::storage_version() == Releases::V11_0_0 {
+                StorageVersion::put(Releases::V12_0_0);
+                weight = weight.saturating_add(T::DbWeight::get().writes(1));
+            }
+            if Self::storage_version() == Releases::V12_0_0 {
+                weight = weight.saturating_add(Self::migrate_offer_currency());
+            }
+            if Self::storage_version() == Releases::V13_0_0 {
+                weight = weight.saturating_add(Self::migrate_swap_proposed_block());
+            }
+            if Self::storage_version() == Releases::V14_0_0 {
+                weight = weight.saturating_add(Self::migrate_secret_listing_indexes());
+            }
+
+            weight
+        }
+
+        fn on_initialize(now: T::BlockNumber) -> Weight {
+            // on_finalize can't return a weight, so the offers it releases are counted here
+            let weight = Self::expire_reservations(now)
+                .saturating_add(Self::expire_offers(now, T::MaxOfferExpiriesOnInitialize::get()))
+                .saturating_add(Self::offer_expiries_left_weight(now))
+                .saturating_add(Self::clear_listing_rate_limits());
+
+            let interval = T::StatsEmitInterval::get();
+            if interval.is_zero() || !(now % interval).is_zero() {
+                return weight;
+            }
+
+            // Snapshot for indexers, storage is left untouched
+            Self::deposit_event(RawEvent::MarketStats(
+                Self::listing_count(),
+                Self::order_count(),
+                Self::total_volume(),
+                now
+            ));
+
+            weight.saturating_add(T::DbWeight::get().reads(4))
+        }
+
+        fn on_finalize(now: T::BlockNumber) {
+            // Release the offers left over by the on_initialize cap
+            Self::expire_offers(now, u32::MAX);
+        }
+
+        fn offchain_worker(now: T::BlockNumber) {
+            // The pool drops duplicates, so a listing still pending expiry is resubmitted safely
+            for listing in Listings::<T>::iter_values().filter(|listing| Self::is_listing_expired(listing, now)) {
+                let call = Call::expire_listing(listing.id);
+                SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call.into()).ok();
+            }
+        }
 
           /// Transfer asset
         ///
         /// - `to`: the token recipient
         /// - `asset`: (class_id, token_id)
-        #[weight = 10_000]
+        #[weight = T::WeightInfo::transfer(AllListings::<T>::decode_len().unwrap_or_default() as u32)]
         pub fn transfer(origin, to: T::AccountId, asset:(ClassIdOf<T>, TokenIdOf<T>)) -> DispatchResult{
 
             let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
 
-            // Check that the wallet has permission to transfer assets
-            ensure!(T::AllowTransfer::get(), Error::<T>::TransfersNotAllowed);
+            Self::do_wallet_transfer(&sender, &to, asset)?;
 
-            // Check that the sender owns this asset
-            let check_ownership = Self::check_ownership(&sender, &asset)?;
-            ensure!(check_ownership, Error::<T>::NoPermission);
+            Ok(())
+        }
 
-            // Ensure that the asset is not locked in Escrow or Claims
-            ensure!(!Self::is_locked(&asset), Error::<T>::AssetLocked);
+        /// Transfer asset with a note. The memo is only kept in the event.
+        ///
+        /// - `to`: the token recipient
+        /// - `asset`: (class_id, token_id)
+        /// - `memo`: short note about the transfer
+        #[weight = T::WeightInfo::transfer(AllListings::<T>::decode_len().unwrap_or_default() as u32)]
+        pub fn transfer_with_memo(origin, to: T::AccountId, asset:(ClassIdOf<T>, TokenIdOf<T>), memo: Vec<u8>) -> DispatchResult{
 
-            // Transfer the asset
-            ensure!(T::Transfer::transfer(&sender, &to, asset).is_ok(), Error::<T>::TransferCancelled);
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+            ensure!(memo.len() <= T::MaxMemoLength::get() as usize, Error::<T>::MemoTooLong);
+
+            Self::do_wallet_transfer(&sender, &to, asset)?;
+
+            Self::deposit_event(RawEvent::MarketAssetTransferredWithMemo(sender, to, asset.0, asset.1, memo));
 
             Ok(())
         }
@@ -237,23 +1308,35 @@ decl_module! {
         /// Burn asset
         ///
         /// - `asset`: (class_id, token_id)
-        #[weight = 10_000]
+        #[weight = T::WeightInfo::burn(AllListings::<T>::decode_len().unwrap_or_default() as u32)]
         pub fn burn(origin, asset:(ClassIdOf<T>, TokenIdOf<T>)) -> DispatchResult{
 
             let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
 
-            // Check that the wallet has permission to burn assets
-            ensure!(T::AllowBurn::get(), Error::<T>::BurningNotAllowed);
+            Self::do_wallet_burn(&sender, asset)
+        }
 
-            // Check that the sender owns this asset
-            let check_ownership = Self::check_ownership(&sender, &asset)?;
-            ensure!(check_ownership, Error::<T>::NoPermission);
+        /// Burn several assets at once. Nothing is burned if any of them fails.
+        ///
+        /// - `assets`: list of (class_id, token_id)
+        #[weight = 10_000 * assets.len() as Weight]
+        #[transactional]
+        pub fn batch_burn(origin, assets: Vec<(ClassIdOf<T>, TokenIdOf<T>)>) -> DispatchResult{
 
-            // Ensure that the asset is not locked in Escrow or Claims
-            ensure!(!Self::is_locked(&asset), Error::<T>::AssetLocked);
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            // Ensure the batch is within bounds
+            ensure!(assets.len() <= T::MaxBatchSize::get() as usize, Error::<T>::BatchTooLarge);
 
-            // Burn the asset
-            ensure!(T::Burn::burn(&sender, asset).is_ok(), Error::<T>::BurnCancelled);
+            for asset in assets.iter() {
+                Self::do_wallet_burn(&sender, *asset)?;
+            }
+
+            Self::deposit_event(RawEvent::MarketBatchBurned(sender, assets.len() as u32));
 
             Ok(())
         }
@@ -262,26 +1345,46 @@ decl_module! {
         ///
         /// - `asset`: (class_id, token_id)
         /// - `price`: price to sell the asset on the market
-        #[weight = 10_000]
-        pub fn list(origin, asset:(ClassIdOf<T>, TokenIdOf<T>), price: BalanceOf<T>) -> DispatchResult{
+        /// - `expiry_block`: last block the asset can be bought in, `None` to never expire
+        #[weight = T::WeightInfo::list(AllListings::<T>::decode_len().unwrap_or_default() as u32)]
+        #[transactional]
+        pub fn list(origin, asset:(ClassIdOf<T>, TokenIdOf<T>), price: BalanceOf<T>, expiry_block: Option<T::BlockNumber>, currency_id: T::CurrencyId) -> DispatchResult{
 
             let sender = ensure_signed(origin)?;
-
-            // Check that the wallet has permission to list assets
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+            ensure!(price >= T::MinListingPrice::get(), Error::<T>::PriceTooLow);
+            ensure!(
+                !matches!(expiry_block, Some(expiry) if expiry < <system::Module<T>>::block_number()),
+                Error::<T>::InvalidListingExpiry
+            );
+            ensure!(
+                currency_id == T::NativeCurrencyId::get() || T::AcceptedCurrencies::get().contains(&currency_id),
+                Error::<T>::CurrencyNotAccepted
+            );
+
+            // Check that the class and the wallet have permission to list assets
+            ensure!(Self::class_data(asset.0).allow_listing, Error::<T>::ClassListingRestricted);
             ensure!(T::AllowEscrow::get(), Error::<T>::EscrowNotAllowed);
+            ensure!(!Self::secondary_market_disabled(asset.0), Error::<T>::SecondaryMarketDisabled);
+            ensure!(!Self::non_tradeable(asset.0), Error::<T>::ClassNonTradeable);
+            Self::ensure_class_transferable(asset.0)?;
 
-            // Check that the sender owns this asset
-            let check_ownership = Self::check_ownership(&sender, &asset)?;
-            ensure!(check_ownership, Error::<T>::NoPermission);
+            // Check that the sender owns this asset or is approved to list it.
+            // The listing is always created on behalf of the owner.
+            let sender = Self::ensure_owner_or_approved(&sender, &asset)?;
 
             // Ensure this asset isn't already listed
             ensure!(!Self::is_locked(&asset), Error::<T>::AssetLocked);
+            ensure!(!Self::is_soulbound(&asset), Error::<T>::SoulboundAsset);
+            Self::ensure_holding_period_met(&asset)?;
+            Self::do_count_listing_this_block(&sender)?;
 
             // Escrow Account
-            let escrow_account: T::AccountId = Self::get_escrow_account();
+            let escrow_account: T::AccountId = Self::get_class_escrow_account(asset.0);
 
             // Transfer into escrow
-            Self::do_transfer(&sender, &escrow_account, asset).ok();
+            Self::do_transfer(&sender, &escrow_account, asset)?;
 
             // Add the new listing id to storage
             let listing_id = NextListingId::try_mutate(|id| -> Result<ListingId, DispatchError> {
@@ -297,266 +1400,3874 @@ decl_module! {
                 seller: sender.clone(),
                 asset,
                 price,
+                expiry_block,
+                currency_id,
             };
 
             // Add listing to storage
             Listings::<T>::insert(listing_id, listing);
+            Self::do_revalue_asset(&sender, asset, price);
+            HighestListingPrice::<T>::mutate(asset.0, |highest| {
+                *highest = (*highest).max(Some(price))
+            });
 
-            // Add listing to owner
-            // Get owner listing data
-            if ListingsByOwner::<T>::contains_key(&sender) {
-                ListingsByOwner::<T>::try_mutate(&sender, |owner_data| -> DispatchResult {
-                    let data = owner_data.as_mut().ok_or(Error::<T>::ListingNotFound)?;
-                    // Append the new listing id
-                    data.push(listing_id);
-
-                    // Update owner listings
-                    ListingsByOwner::<T>::insert(&sender, data);
-                    Ok(())
-                })?;
-            } else {
-                let listings = vec![listing_id];
-                ListingsByOwner::<T>::insert(&sender, listings)
-            }
+            // Add listing to owner, asset and class
+            ListingsByOwner::<T>::insert(&sender, listing_id, ());
+            ListingsByAsset::<T>::insert(asset, listing_id);
+            ListingsByClass::<T>::append(asset.0, listing_id);
 
             // Add asset to all listings
-            AllListings::<T>::append(&asset);
+            Self::add_to_all_listings(asset);
+            Self::do_increment_relist_count(asset)?;
+
+            T::List::on_list(&sender, asset, price)?;
 
             Self::deposit_event(RawEvent::MarketAssetListed(sender, price, listing_id, asset.0, asset.1));
 
             Ok(())
         }
 
-        /// Remove the asset from escrow
+        /// Send the asset to escrow with a price only known to the seller and buyer
         ///
-        /// - `listing_id`: id of the Listing
+        /// - `asset`: (class_id, token_id)
+        /// - `price`: price to sell the asset on the market
+        /// - `nonce`: salt hashed with the price
         #[weight = 10_000]
-        pub fn unlist(origin, listing_id: ListingId) -> DispatchResult{
+        #[transactional]
+        pub fn list_secret(origin, asset:(ClassIdOf<T>, TokenIdOf<T>), price: BalanceOf<T>, nonce: u64) -> DispatchResult{
 
             let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
 
-            // Check that the wallet has permission to list assets
+            // Check that the class and the wallet have permission to list assets
+            ensure!(Self::class_data(asset.0).allow_listing, Error::<T>::ClassListingRestricted);
             ensure!(T::AllowEscrow::get(), Error::<T>::EscrowNotAllowed);
+            ensure!(!Self::secondary_market_disabled(asset.0), Error::<T>::SecondaryMarketDisabled);
+            ensure!(!Self::non_tradeable(asset.0), Error::<T>::ClassNonTradeable);
+            Self::ensure_class_transferable(asset.0)?;
 
-            // Get listing data
-            Listings::<T>::try_mutate_exists(listing_id, |listing_data| -> DispatchResult {
-                let data = listing_data.as_mut().ok_or(Error::<T>::ListingNotFound)?;
-
-                // Ensure the listing is in storage for this user
-                ensure!(sender == data.seller, Error::<T>::NoPermission);
+            // Check that the sender owns this asset or is approved to list it
+            let sender = Self::ensure_owner_or_approved(&sender, &asset)?;
 
-                // Ensure listing data was removed
-                let is_unlisted = Self::do_unlist(&sender, data.clone(), false)?;
-                ensure!(is_unlisted, Error::<T>::UnlistingFailed);
+            // Ensure this asset isn't already listed
+            ensure!(!Self::is_locked(&asset), Error::<T>::AssetLocked);
+            ensure!(!Self::is_soulbound(&asset), Error::<T>::SoulboundAsset);
+            Self::ensure_holding_period_met(&asset)?;
+            Self::do_count_listing_this_block(&sender)?;
 
-                Self::deposit_event(RawEvent::MarketAssetUnlisted(sender, listing_id, data.asset.0, data.asset.1));
+            // Add the new secret listing id to storage
+            let secret_listing_id = NextSecretListingId::try_mutate(|id| -> Result<SecretListingId, DispatchError> {
+                let current_id = *id;
+                *id = id.checked_add(One::one()).ok_or(Error::<T>::NoAvailableSecretListingId)?;
 
-                Ok(())
+                Ok(current_id)
             })?;
 
-            // Remove the actual listing from state
-            Listings::<T>::remove(listing_id);
+            // Transfer into escrow
+            let escrow_account: T::AccountId = Self::get_class_escrow_account(asset.0);
+            Self::do_transfer(&sender, &escrow_account, asset)?;
+
+            let secret_listing = SecretListing {
+                id: secret_listing_id,
+                seller: sender.clone(),
+                asset,
+                price_hash: T::Hashing::hash_of(&(price, nonce)),
+            };
+            SecretListings::<T>::insert(secret_listing_id, secret_listing);
+
+            // Secret listings are indexed apart from listings, their ids are counted separately
+            SecretListingsByAsset::<T>::insert(asset, secret_listing_id);
+            SecretListingsByClass::<T>::append(asset.0, secret_listing_id);
+
+            // Secret listings lock the asset like any other listing
+            Self::add_to_all_listings(asset);
+            Self::do_increment_relist_count(asset)?;
+
+            T::List::on_list(&sender, asset, price)?;
+
+            Self::deposit_event(RawEvent::MarketAssetSecretListed(sender, secret_listing_id, asset.0, asset.1));
 
             Ok(())
         }
 
-        /// Buy the asset from the market
+        /// Remove a secret listing and return the asset from escrow
         ///
-        /// - `listing_id`: id of the Listing
+        /// - `secret_listing_id`: id of the secret listing
         #[weight = 10_000]
-        pub fn buy(origin, listing_id: ListingId) -> DispatchResult{
+        pub fn unlist_secret(origin, secret_listing_id: SecretListingId) -> DispatchResult{
 
             let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
 
-            // Check that the wallet has permission to list assets
-            ensure!(T::AllowEscrow::get(), Error::<T>::EscrowNotAllowed);
-
-            // Ensure the listing is in storage
-            ensure!(Listings::<T>::contains_key(listing_id), Error::<T>::ListingNotFound);
-
-            // Get listing data
-            Listings::<T>::try_mutate(listing_id, |listing_data| -> DispatchResult {
-                let data = listing_data.as_mut().ok_or(Error::<T>::ListingNotFound)?;
-
-                // Now that the order has been placed, let's remove the listing
-                // Ensure listing data was removed
-                let is_unlisted = Self::do_unlist(&data.seller, data.clone(), true)?;
-                ensure!(is_unlisted, Error::<T>::UnlistingFailed);
-
-                // Transfer funds to seller
-                <T as Config>::Currency::transfer(&sender, &data.seller, data.price, ExistenceRequirement::KeepAlive)?;
+            let data = Self::secret_listings(secret_listing_id).ok_or(Error::<T>::ListingNotFound)?;
 
-                // Transfer the asset out of escrow to the buyer
-                //Escrow Account
-                let escrow_account: T::AccountId = Self::get_escrow_account();
-                Self::do_transfer(&escrow_account, &sender, data.asset).ok();
+            // Ensure the listing belongs to this user
+            ensure!(sender == data.seller, Error::<T>::NoPermission);
 
-                Self::deposit_event(
-                    RawEvent::MarketAssetBuySuccess(
-                        data.seller.clone(),
-                        sender,
-                        data.id,
-                        data.price
-                    )
-                );
+            // Transfer out of escrow
+            let escrow_account: T::AccountId = Self::escrow_account_of(&data.asset);
+            Self::do_transfer(&escrow_account, &sender, data.asset)?;
 
-                Ok(())
-            })?;
+            Self::remove_from_all_listings(&data.asset)?;
+            Self::remove_secret_listing(&data);
 
-            // Remove the actual listing from state
-            Listings::<T>::remove(listing_id);
+            Self::deposit_event(RawEvent::MarketAssetSecretUnlisted(sender, secret_listing_id, data.asset.0, data.asset.1));
 
             Ok(())
         }
 
-        /// Post an emote for the asset
+        /// Buy a secret listing by revealing its price
         ///
-        /// - `asset`: (class_id, token_id)
-        /// - `emote`: name of the emote to use
+        /// - `secret_listing_id`: id of the secret listing
+        /// - `revealed_price`: the price agreed with the seller
+        /// - `nonce`: salt the seller hashed with the price
         #[weight = 10_000]
-        pub fn emote(origin, asset:(ClassIdOf<T>, TokenIdOf<T>), emote: Vec<u8>) -> DispatchResult{
+        #[transactional]
+        pub fn buy_secret(origin, secret_listing_id: SecretListingId, revealed_price: BalanceOf<T>, nonce: u64) -> DispatchResult{
 
             let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
 
-            // Ensure this token exists
-            ensure!(!AssetModule::<T>::tokens(asset.0, asset.1).is_none(), Error::<T>::AssetNotFound);
+            // Check that the wallet has permission to list assets
+            ensure!(T::AllowEscrow::get(), Error::<T>::EscrowNotAllowed);
 
-            // Convert the emote to a string
-            let str_emote = str::from_utf8(&emote).unwrap();
+            let data = Self::secret_listings(secret_listing_id).ok_or(Error::<T>::ListingNotFound)?;
+            ensure!(!Self::non_tradeable(data.asset.0), Error::<T>::ClassNonTradeable);
 
-            // Ensure this is a valid emote
-            ensure!(!emojis::lookup(str_emote).is_none(), Error::<T>::InvalidEmote);
+            // Ensure the revealed price is the one the seller committed to
+            ensure!(
+                T::Hashing::hash_of(&(revealed_price, nonce)) == data.price_hash,
+                Error::<T>::InvalidPriceReveal
+            );
 
-            // Get emoji
-            let emoji = emojis::lookup(str_emote).unwrap().as_str().as_bytes().to_vec();
+            // Transfer funds to seller
+            Self::do_pay_sale(&sender, &data.seller, data.asset.0, revealed_price, T::NativeCurrencyId::get())?;
 
-            // Get emotes data
-            let mut emotes_data = Emotes::<T>::get(asset, &sender);
+            // Transfer the asset out of escrow to the buyer
+            let escrow_account: T::AccountId = Self::escrow_account_of(&data.asset);
+            Self::do_transfer(&escrow_account, &sender, data.asset)?;
 
-            // Append the new emoji
-            emotes_data.push(emoji.clone());
+            Self::remove_from_all_listings(&data.asset)?;
+            Self::remove_secret_listing(&data);
 
-            // Add emote to storage
-            Emotes::<T>::insert(asset, &sender, emotes_data);
+            Self::do_record_sale(&data.seller, &sender, data.asset, revealed_price)?;
 
-            Self::deposit_event(RawEvent::MarketAssetEmotePosted(sender, asset.0, asset.1, emoji));
+            Self::deposit_event(RawEvent::MarketSecretBuySuccess(data.seller, sender, secret_listing_id, revealed_price));
 
             Ok(())
         }
 
-        /// Claim an asset
+        /// Change the price of a listing. The asset stays in escrow.
         ///
-        /// - `claim_id`: id of the claim
+        /// - `listing_id`: id of the Listing
+        /// - `new_price`: new price of the asset
         #[weight = 10_000]
-        pub fn claim(origin, claim_id: ClaimId) -> DispatchResult{
+        pub fn update_listing_price(origin, listing_id: ListingId, new_price: BalanceOf<T>) -> DispatchResult{
 
             let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+            ensure!(new_price >= T::MinListingPrice::get(), Error::<T>::PriceTooLow);
 
-            // Check that the wallet has permission to claim assets
-            ensure!(T::AllowClaim::get(), Error::<T>::ClaimingNotAllowed);
+            let (asset, old_price) = Listings::<T>::try_mutate(listing_id, |listing_data| -> Result<_, DispatchError> {
+                let data = listing_data.as_mut().ok_or(Error::<T>::ListingNotFound)?;
+
+                // Ensure that the sender is the seller
+                ensure!(sender == data.seller, Error::<T>::NoPermission);
+
+                let old_price = data.price;
+                data.price = new_price;
 
-            // Ensure the claim is for this sender
-            ensure!(OpenClaims::<T>::contains_key(&sender, claim_id), Error::<T>::ClaimNotFound);
+                Ok((data.asset, old_price))
+            })?;
+
+            Self::refresh_highest_listing_price(asset.0, None);
+            Self::do_revalue_asset(&sender, asset, new_price);
+
+            // Offers were made against the old price, so buyers have to confirm them again
+            let mut invalidated: u32 = 0;
+            for (offer_id, _) in OffersByAsset::<T>::iter_prefix(asset) {
+                InvalidatedOffers::insert(offer_id, ());
+                invalidated = invalidated.saturating_add(1);
+            }
+            if invalidated > 0 {
+                Self::deposit_event(RawEvent::MarketOffersInvalidated(listing_id, invalidated));
+            }
+
+            Self::deposit_event(RawEvent::MarketListingPriceUpdated(sender, listing_id, old_price, new_price));
 
-            // Get claim data
-            OpenClaims::<T>::try_mutate(sender.clone(), claim_id, |claim_data| -> DispatchResult {
-                let data = claim_data.as_mut().ok_or(Error::<T>::ClaimNotFound)?;
+            Ok(())
+        }
 
-                // Perform any domain related tasks to claiming
-                ensure!(T::Claim::claim(&sender, data.asset).is_ok(), Error::<T>::ClaimCancelled);
+        /// Remove the asset from escrow
+        ///
+        /// - `listing_id`: id of the Listing
+        #[weight = T::WeightInfo::unlist(AllListings::<T>::decode_len().unwrap_or_default() as u32)]
+        pub fn unlist(origin, listing_id: ListingId) -> DispatchResult{
 
-                // Claim Account
-                let claim_account: T::AccountId = Self::get_claim_account();
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
 
-                // Transfer asset into the reciever's account
-                Self::do_transfer(&claim_account, &sender, data.asset).ok();
+            // Check that the wallet has permission to list assets
+            ensure!(T::AllowEscrow::get(), Error::<T>::EscrowNotAllowed);
 
-                AllClaims::<T>::try_mutate(|asset_ids| -> DispatchResult {
-                    let asset_index = asset_ids.iter().position(|x| *x == data.asset).unwrap();
-                    asset_ids.remove(asset_index);
+            // Get listing data
+            Listings::<T>::try_mutate_exists(listing_id, |listing_data| -> DispatchResult {
+                let data = listing_data.as_mut().ok_or(Error::<T>::ListingNotFound)?;
 
-                    Ok(())
-                })?;
+                // Ensure the listing is in storage for this user
+                ensure!(sender == data.seller, Error::<T>::NoPermission);
 
-                // Remove the open claim
-                OpenClaims::<T>::remove(&sender, claim_id);
+                // Ensure listing data was removed
+                let is_unlisted = Self::do_unlist(&sender, data.clone(), false)?;
+                ensure!(is_unlisted, Error::<T>::UnlistingFailed);
 
-                Self::deposit_event(RawEvent::MarketAssetClaimed(sender, data.asset.0, data.asset.1));
+                Self::deposit_event(RawEvent::MarketAssetUnlisted(sender, listing_id, data.asset.0, data.asset.1));
 
                 Ok(())
             })?;
 
+            // Remove the actual listing from state
+            Listings::<T>::remove(listing_id);
+
             Ok(())
         }
 
-        /// Create an asset claim for this account
+        /// Return a listed asset to its seller once the escrow holds it again
         ///
-        /// - `receiver`: account to receive this asset
-        /// - `asset`: (class_id, token_id)
+        /// - `listing_id`: id of the Listing
         #[weight = 10_000]
-        pub fn create_claim(origin, receiver: T::AccountId, asset:(ClassIdOf<T>, TokenIdOf<T>)) -> DispatchResult{
-
-            let sender = ensure_signed(origin)?;
+        pub fn recover_escrow(origin, listing_id: ListingId) -> DispatchResult{
 
-            // Check that the wallet has permission to claim assets
-            ensure!(T::AllowClaim::get(), Error::<T>::ClaimingNotAllowed);
+            T::ForceOrigin::ensure_origin(origin)?;
 
-            // Check that the sender owns this asset
-            let check_ownership = Self::check_ownership(&sender, &asset)?;
-            ensure!(check_ownership, Error::<T>::NoPermission);
+            let data = Self::listings(listing_id).ok_or(Error::<T>::ListingNotFound)?;
 
-            // Ensure that the sender is the owner of this class
-            let class_info = AssetModule::<T>::classes(asset.0).ok_or(Error::<T>::AssetNotFound)?;
-            ensure!(sender == class_info.owner, Error::<T>::NoPermission);
+            let is_unlisted = Self::do_unlist(&data.seller, data.clone(), false)?;
+            ensure!(is_unlisted, Error::<T>::UnlistingFailed);
 
-            // Ensure the claim is created
-            let claim_created = Self::do_create_claim(&sender, &receiver, asset)?;
-            ensure!(claim_created, Error::<T>::ClaimCreateFailed);
+            Listings::<T>::remove(listing_id);
 
-            Self::deposit_event(RawEvent::MarketClaimCreated(sender, receiver, asset.0, asset.1));
+            Self::deposit_event(RawEvent::MarketAssetUnlisted(data.seller, listing_id, data.asset.0, data.asset.1));
 
             Ok(())
         }
 
-    }
-}
+        /// Send several assets to escrow to be sold together at one price
+        ///
+        /// - `assets`: list of (class_id, token_id)
+        /// - `price`: price of the whole bundle
+        #[weight = 10_000u64.saturating_mul(assets.len() as Weight)]
+        #[transactional]
+        pub fn list_bundle(origin, assets: Vec<(ClassIdOf<T>, TokenIdOf<T>)>, price: BalanceOf<T>) -> DispatchResult{
 
-// Module Implementation
-impl<T: Config> Module<T> {
-    fn check_ownership(
-        owner: &T::AccountId,
-        asset: &(ClassIdOf<T>, TokenIdOf<T>),
-    ) -> Result<bool, DispatchError> {
-        Ok(AssetModule::<T>::is_owner(owner, *asset))
-    }
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
 
-    fn do_transfer(
-        from: &T::AccountId,
-        to: &T::AccountId,
-        asset: (ClassIdOf<T>, TokenIdOf<T>),
-    ) -> Result<bool, DispatchError> {
-        AssetModule::<T>::transfer(from, to, asset).ok();
-        Ok(true)
-    }
+            // Check that the wallet has permission to list assets
+            ensure!(T::AllowEscrow::get(), Error::<T>::EscrowNotAllowed);
 
-    fn is_listed(asset: &(ClassIdOf<T>, TokenIdOf<T>)) -> bool {
-        Self::all_listings().contains(asset)
-    }
+            ensure!(price >= T::MinListingPrice::get(), Error::<T>::PriceTooLow);
+
+            // Ensure the bundle is within bounds
+            ensure!(!assets.is_empty(), Error::<T>::EmptyBundle);
+            ensure!(assets.len() <= T::MaxBatchSize::get() as usize, Error::<T>::BatchTooLarge);
+            Self::do_count_listing_this_block(&sender)?;
+
+            for asset in assets.iter() {
+                ensure!(Self::class_data(asset.0).allow_listing, Error::<T>::ClassListingRestricted);
+                ensure!(!Self::secondary_market_disabled(asset.0), Error::<T>::SecondaryMarketDisabled);
+                ensure!(!Self::non_tradeable(asset.0), Error::<T>::ClassNonTradeable);
+                Self::ensure_class_transferable(asset.0)?;
+                ensure!(Self::check_ownership(&sender, asset)?, Error::<T>::NoPermission);
+                ensure!(!Self::is_locked(asset), Error::<T>::AssetLocked);
+                Self::ensure_holding_period_met(asset)?;
+
+                // Transfer into escrow
+                let escrow_account: T::AccountId = Self::get_class_escrow_account(asset.0);
+                Self::do_transfer(&sender, &escrow_account, *asset)?;
+                Self::add_to_all_listings(*asset);
+            }
 
-    fn is_claiming(asset: &(ClassIdOf<T>, TokenIdOf<T>)) -> bool {
-        Self::all_claims().contains(asset)
-    }
+            let bundle_id = NextBundleListingId::try_mutate(|id| -> Result<BundleListingId, DispatchError> {
+                let current_id = *id;
+                *id = id.checked_add(One::one()).ok_or(Error::<T>::NoAvailableBundleListingId)?;
 
-    fn get_claim_account() -> T::AccountId {
+                Ok(current_id)
+            })?;
+
+            BundleListings::<T>::insert(bundle_id, BundleListing {
+                id: bundle_id,
+                seller: sender.clone(),
+                assets: assets.clone(),
+                price,
+            });
+
+            // Each asset is listed at the share of the price `buy_bundle` sells it for
+            let count: BalanceOf<T> = (assets.len() as u32).into();
+            let share = price / count;
+            let mut remainder = price;
+            for (index, asset) in assets.iter().enumerate() {
+                let asset_price = if index + 1 == assets.len() { remainder } else { share };
+                remainder = remainder.saturating_sub(asset_price);
+                T::List::on_list(&sender, *asset, asset_price)?;
+            }
+
+            Self::deposit_event(RawEvent::MarketBundleListed(sender, bundle_id, assets, price));
+
+            Ok(())
+        }
+
+        /// Buy every asset of a bundle
+        ///
+        /// - `bundle_id`: id of the BundleListing
+        #[weight = 10_000u64.saturating_mul(
+            BundleListings::<T>::get(bundle_id).map(|bundle| bundle.assets.len()).unwrap_or_default() as Weight
+        )]
+        #[transactional]
+        pub fn buy_bundle(origin, bundle_id: BundleListingId) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            // Check that the wallet has permission to list assets
+            ensure!(T::AllowEscrow::get(), Error::<T>::EscrowNotAllowed);
+
+            let bundle = Self::bundle_listings(bundle_id).ok_or(Error::<T>::BundleListingNotFound)?;
+            Self::remove_bundle_listing(&bundle)?;
+
+            // Each asset counts as a sale at an equal share of the bundle price,
+            // so royalties and class stats follow the class of every asset
+            let count: BalanceOf<T> = (bundle.assets.len() as u32).into();
+            let share = bundle.price / count;
+            let mut remainder = bundle.price;
+
+            for (index, asset) in bundle.assets.iter().enumerate() {
+                let asset_price = if index + 1 == bundle.assets.len() { remainder } else { share };
+                remainder = remainder.saturating_sub(asset_price);
+
+                Self::do_pay_sale(&sender, &bundle.seller, asset.0, asset_price, T::NativeCurrencyId::get())?;
+
+                let escrow_account: T::AccountId = Self::escrow_account_of(asset);
+                Self::do_transfer(&escrow_account, &sender, *asset)?;
+
+                Self::do_record_sale(&bundle.seller, &sender, *asset, asset_price)?;
+            }
+
+            Self::deposit_event(RawEvent::MarketBundlePurchased(bundle.seller, sender, bundle_id, bundle.price));
+
+            Ok(())
+        }
+
+        /// Buy some assets of a bundle at an equal share of the bundle price each.
+        /// The rest of the bundle stays listed at the rest of the price.
+        ///
+        /// - `bundle_id`: id of the BundleListing
+        /// - `asset_indices`: positions of the assets to buy in the bundle
+        #[weight = 10_000u64.saturating_mul(asset_indices.len() as Weight)]
+        #[transactional]
+        pub fn partial_buy_bundle(origin, bundle_id: BundleListingId, asset_indices: Vec<u32>) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            // Check that the wallet has permission to list assets
+            ensure!(T::AllowEscrow::get(), Error::<T>::EscrowNotAllowed);
+
+            let mut bundle = Self::bundle_listings(bundle_id).ok_or(Error::<T>::BundleListingNotFound)?;
+            ensure!(!asset_indices.is_empty(), Error::<T>::EmptyBundle);
+
+            // Validate the whole selection before anything moves
+            let mut selected = asset_indices.clone();
+            selected.sort_unstable();
+            for pair in selected.windows(2) {
+                ensure!(pair[0] != pair[1], Error::<T>::DuplicateAssetIndex);
+            }
+            ensure!(
+                selected.iter().all(|index| (*index as usize) < bundle.assets.len()),
+                Error::<T>::AssetIndexOutOfBounds
+            );
+
+            // Each asset costs the same share as in `buy_bundle`. Buying what is
+            // left of the bundle pays the rest of the price.
+            let count: BalanceOf<T> = (bundle.assets.len() as u32).into();
+            let share = bundle.price / count;
+            let buys_all = selected.len() == bundle.assets.len();
+            let mut paid: BalanceOf<T> = Zero::zero();
+
+            for (position, index) in selected.iter().enumerate() {
+                let asset = bundle.assets[*index as usize];
+                let asset_price = if buys_all && position + 1 == selected.len() {
+                    bundle.price.saturating_sub(paid)
+                } else {
+                    share
+                };
+                paid = paid.saturating_add(asset_price);
+
+                Self::do_pay_sale(&sender, &bundle.seller, asset.0, asset_price, T::NativeCurrencyId::get())?;
+
+                let escrow_account: T::AccountId = Self::escrow_account_of(&asset);
+                Self::do_transfer(&escrow_account, &sender, asset)?;
+                Self::remove_from_all_listings(&asset)?;
+
+                Self::do_record_sale(&bundle.seller, &sender, asset, asset_price)?;
+            }
+
+            if buys_all {
+                BundleListings::<T>::remove(bundle_id);
+            } else {
+                // Drop the bought assets from the back so earlier indices stay valid
+                for index in selected.iter().rev() {
+                    bundle.assets.remove(*index as usize);
+                }
+                bundle.price = bundle.price.saturating_sub(paid);
+                BundleListings::<T>::insert(bundle_id, &bundle);
+            }
+
+            Self::deposit_event(RawEvent::MarketBundlePartiallyPurchased(bundle.seller, sender, bundle_id, selected.len() as u32, paid));
+
+            Ok(())
+        }
+
+        /// Return every asset of a bundle from escrow
+        ///
+        /// - `bundle_id`: id of the BundleListing
+        #[weight = 10_000]
+        #[transactional]
+        pub fn unlist_bundle(origin, bundle_id: BundleListingId) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            let bundle = Self::bundle_listings(bundle_id).ok_or(Error::<T>::BundleListingNotFound)?;
+            ensure!(sender == bundle.seller, Error::<T>::NoPermission);
+
+            Self::remove_bundle_listing(&bundle)?;
+
+            for asset in bundle.assets.iter() {
+                let escrow_account: T::AccountId = Self::escrow_account_of(asset);
+                Self::do_transfer(&escrow_account, &sender, *asset)?;
+            }
+
+            Self::deposit_event(RawEvent::MarketBundleUnlisted(sender, bundle_id));
+
+            Ok(())
+        }
+
+        /// Return the asset of an expired listing from escrow to the seller.
+        /// Submitted as an unsigned transaction by the offchain worker.
+        ///
+        /// - `listing_id`: id of the Listing
+        #[weight = 10_000]
+        pub fn expire_listing(origin, listing_id: ListingId) -> DispatchResult{
+
+            ensure_none(origin)?;
+
+            let data = Self::listings(listing_id).ok_or(Error::<T>::ListingNotFound)?;
+            ensure!(
+                Self::is_listing_expired(&data, <system::Module<T>>::block_number()),
+                Error::<T>::ListingNotExpired
+            );
+
+            let is_unlisted = Self::do_unlist(&data.seller, data.clone(), false)?;
+            ensure!(is_unlisted, Error::<T>::UnlistingFailed);
+
+            Listings::<T>::remove(listing_id);
+
+            Self::deposit_event(RawEvent::MarketListingExpired(data.seller, listing_id, data.asset.0, data.asset.1));
+
+            Ok(())
+        }
+
+        /// Buy the asset from the market
+        ///
+        /// - `listing_id`: id of the Listing
+        #[weight = T::WeightInfo::buy(AllListings::<T>::decode_len().unwrap_or_default() as u32)]
+        #[transactional]
+        pub fn buy(origin, listing_id: ListingId) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            // Check that the wallet has permission to list assets
+            ensure!(T::AllowEscrow::get(), Error::<T>::EscrowNotAllowed);
+
+            // Ensure the listing is in storage
+            let listing = Self::listings(listing_id).ok_or(Error::<T>::ListingNotFound)?;
+            ensure!(!Self::non_tradeable(listing.asset.0), Error::<T>::ClassNonTradeable);
+
+            // Reserved listings can't be bought until the seller releases them
+            ensure!(!ReservedListings::<T>::contains_key(listing_id), Error::<T>::ListingReserved);
+            ensure!(
+                !Self::is_listing_expired(&listing, <system::Module<T>>::block_number()),
+                Error::<T>::ListingExpired
+            );
+
+            // Get listing data
+            Listings::<T>::try_mutate(listing_id, |listing_data| -> DispatchResult {
+                let data = listing_data.as_mut().ok_or(Error::<T>::ListingNotFound)?;
+
+                // Now that the order has been placed, let's remove the listing
+                // Ensure listing data was removed
+                let is_unlisted = Self::do_unlist(&data.seller, data.clone(), true)?;
+                ensure!(is_unlisted, Error::<T>::UnlistingFailed);
+
+                // Transfer funds to seller
+                Self::do_pay_sale(&sender, &data.seller, data.asset.0, data.price, data.currency_id)?;
+
+                // Transfer the asset out of escrow to the buyer
+                //Escrow Account
+                let escrow_account: T::AccountId = Self::escrow_account_of(&data.asset);
+                Self::do_transfer(&escrow_account, &sender, data.asset)?;
+
+                Self::do_record_sale(&data.seller, &sender, data.asset, data.price)?;
+
+                // The asset keeps its listing price as its value
+                AccountNFTValue::<T>::mutate(&data.seller, |total| *total = total.saturating_sub(data.price));
+                AccountNFTValue::<T>::mutate(&sender, |total| *total = total.saturating_add(data.price));
+
+                Self::deposit_event(
+                    RawEvent::MarketAssetBuySuccess(
+                        data.seller.clone(),
+                        sender,
+                        data.id,
+                        data.price
+                    )
+                );
+
+                Ok(())
+            })?;
+
+            // Remove the actual listing from state
+            Listings::<T>::remove(listing_id);
+
+            Ok(())
+        }
+
+        /// Post an emote for the asset
+        ///
+        /// - `asset`: (class_id, token_id)
+        /// - `emote`: name of the emote to use
+        #[weight = T::WeightInfo::emote()]
+        pub fn emote(origin, asset:(ClassIdOf<T>, TokenIdOf<T>), emote: Vec<u8>) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            Self::do_emote(&sender, asset, &emote)?;
+
+            Ok(())
+        }
+
+        /// Remove one of the sender's emotes from the asset
+        ///
+        /// - `asset`: (class_id, token_id)
+        /// - `index`: position of the emote among the sender's emotes on the asset
+        #[weight = 10_000]
+        pub fn remove_emote(origin, asset:(ClassIdOf<T>, TokenIdOf<T>), index: u32) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            // The asset isn't required to exist so emotes on burned assets can be cleared
+            let mut emotes_data = Emotes::<T>::get(asset, &sender);
+            ensure!((index as usize) < emotes_data.len(), Error::<T>::InvalidEmoteIndex);
+
+            let emoji = emotes_data.remove(index as usize);
+            EmoteCountByAsset::<T>::mutate_exists(asset, emoji, |count| {
+                *count = count.map(|count| count.saturating_sub(1)).filter(|count| !count.is_zero());
+            });
+
+            if emotes_data.is_empty() {
+                Emotes::<T>::remove(asset, &sender);
+            } else {
+                Emotes::<T>::insert(asset, &sender, emotes_data);
+            }
+
+            Self::deposit_event(RawEvent::MarketAssetEmoteRemoved(sender, asset.0, asset.1, index));
+
+            Ok(())
+        }
+
+        /// Gift an asset with an emote and a message that stays with the asset
+        ///
+        /// - `to`: the token recipient
+        /// - `asset`: (class_id, token_id)
+        /// - `emote`: name of the emote to post on the asset
+        /// - `message`: greeting stored on-chain with the asset
+        #[weight = 10_000]
+        #[transactional]
+        pub fn gift_with_message(origin, to: T::AccountId, asset:(ClassIdOf<T>, TokenIdOf<T>), emote: Vec<u8>, message: Vec<u8>) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            // Ensure the message fits
+            ensure!(message.len() <= T::MaxMessageLength::get() as usize, Error::<T>::MessageTooLong);
+
+            // Post the emote before the asset changes hands
+            Self::do_emote(&sender, asset, &emote)?;
+
+            Self::do_wallet_transfer(&sender, &to, asset)?;
+
+            // Store the message with the asset
+            GiftMessages::<T>::insert(asset, &message);
+
+            Self::deposit_event(RawEvent::MarketAssetGifted(sender, to, asset.0, asset.1, message));
+
+            Ok(())
+        }
+
+        /// Claim an asset
+        ///
+        /// - `claim_id`: id of the claim
+        #[weight = T::WeightInfo::claim(AllClaims::<T>::decode_len().unwrap_or_default() as u32)]
+        #[transactional]
+        pub fn claim(origin, claim_id: ClaimId) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            // Check that the wallet has permission to claim assets
+            ensure!(T::AllowClaim::get(), Error::<T>::ClaimingNotAllowed);
+
+            Self::do_execute_claim(&sender, claim_id)
+        }
+
+        /// Claim each open claim of the sender on its own, up to MaxBatchSize.
+        /// A failed claim is left open and doesn't stop the others.
+        /// Only successful claims are charged.
+        #[weight = T::WeightInfo::claim(AllClaims::<T>::decode_len().unwrap_or_default() as u32)
+            .saturating_mul(T::MaxBatchSize::get() as Weight)]
+        pub fn try_claim_all(origin) -> DispatchResultWithPostInfo{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            // Check that the wallet has permission to claim assets
+            ensure!(T::AllowClaim::get(), Error::<T>::ClaimingNotAllowed);
+
+            // Only read as many claims as can be processed
+            let mut claim_ids: Vec<ClaimId> = OpenClaims::<T>::iter_prefix(&sender)
+                .take(T::MaxBatchSize::get() as usize)
+                .map(|(claim_id, _)| claim_id)
+                .collect();
+            claim_ids.sort_unstable();
+
+            let results: Vec<(ClaimId, bool)> = claim_ids
+                .into_iter()
+                .map(|claim_id| {
+                    let claimed = with_transaction(|| match Self::do_execute_claim(&sender, claim_id) {
+                        Ok(()) => TransactionOutcome::Commit(true),
+                        Err(_) => TransactionOutcome::Rollback(false),
+                    });
+                    (claim_id, claimed)
+                })
+                .collect();
+
+            let claimed_count = results.iter().filter(|(_, claimed)| *claimed).count();
+
+            Self::deposit_event(RawEvent::MarketBatchClaimResult(sender, results));
+
+            let claim_weight = T::WeightInfo::claim(AllClaims::<T>::decode_len().unwrap_or_default() as u32);
+            Ok(Some(claim_weight.saturating_mul(claimed_count as Weight)).into())
+        }
+
+        /// Create an asset claim for this account
+        ///
+        /// - `receiver`: account to receive this asset
+        /// - `asset`: (class_id, token_id)
+        /// - `deadline`: last block the asset can be claimed in, `None` to never expire
+        #[weight = T::WeightInfo::create_claim(AllClaims::<T>::decode_len().unwrap_or_default() as u32)]
+        pub fn create_claim(origin, receiver: T::AccountId, asset:(ClassIdOf<T>, TokenIdOf<T>), deadline: Option<T::BlockNumber>) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            // Check that the wallet has permission to claim assets
+            ensure!(T::AllowClaim::get(), Error::<T>::ClaimingNotAllowed);
+
+            // Ensure the claim is created
+            Self::do_create_claim(&sender, &receiver, asset, deadline)?;
+
+            Self::deposit_event(RawEvent::MarketClaimCreated(sender, receiver, asset.0, asset.1));
+
+            Ok(())
+        }
+
+        /// Create a claim for each receiver and asset pair. Nothing is created if any of them fails.
+        ///
+        /// - `receivers`: accounts to receive the assets
+        /// - `assets`: list of (class_id, token_id), one per receiver
+        #[weight = 10_000 * assets.len() as Weight]
+        #[transactional]
+        pub fn batch_create_claim(origin, receivers: Vec<T::AccountId>, assets: Vec<(ClassIdOf<T>, TokenIdOf<T>)>) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            // Check that the wallet has permission to claim assets
+            ensure!(T::AllowClaim::get(), Error::<T>::ClaimingNotAllowed);
+
+            // Ensure the batch is within bounds
+            ensure!(receivers.len() == assets.len(), Error::<T>::BatchLengthMismatch);
+            ensure!(assets.len() <= T::MaxBatchSize::get() as usize, Error::<T>::BatchTooLarge);
+
+            for (receiver, asset) in receivers.iter().zip(assets.iter()) {
+                Self::do_create_claim(&sender, receiver, *asset, None)?;
+            }
+
+            Self::deposit_event(RawEvent::MarketBatchClaimCreated(sender, assets.len() as u32));
+
+            Ok(())
+        }
+
+        /// Take back the asset of a claim whose deadline has passed
+        ///
+        /// - `receiver`: account the claim was meant for
+        /// - `claim_id`: id of the claim
+        #[weight = 10_000]
+        #[transactional]
+        pub fn reclaim_expired_claim(origin, receiver: T::AccountId, claim_id: ClaimId) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            let data = Self::open_claims(&receiver, claim_id).ok_or(Error::<T>::ClaimNotFound)?;
+            ensure!(sender == data.creator, Error::<T>::NoPermission);
+            ensure!(
+                Self::is_claim_expired(&data, <system::Module<T>>::block_number()),
+                Error::<T>::ClaimNotExpired
+            );
+
+            Self::do_return_claim(&receiver, claim_id, &data)?;
+
+            Self::deposit_event(RawEvent::MarketClaimExpired(sender, receiver, data.asset.0, data.asset.1));
+
+            Ok(())
+        }
+
+        /// Cancel an open claim before its receiver redeems it
+        ///
+        /// - `receiver`: account the claim was meant for
+        /// - `claim_id`: id of the claim
+        #[weight = 10_000]
+        #[transactional]
+        pub fn revoke_claim(origin, receiver: T::AccountId, claim_id: ClaimId) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            let data = Self::open_claims(&receiver, claim_id).ok_or(Error::<T>::ClaimNotFound)?;
+            ensure!(sender == data.creator, Error::<T>::NoPermission);
+
+            Self::do_return_claim(&receiver, claim_id, &data)?;
+
+            Self::deposit_event(RawEvent::MarketClaimRevoked(sender, receiver, data.asset.0, data.asset.1));
+
+            Ok(())
+        }
+
+        /// Send an open claim to another receiver
+        ///
+        /// - `claim_id`: id of the claim
+        /// - `new_receiver`: account to receive the asset instead
+        #[weight = 10_000]
+        pub fn transfer_claim_receiver(origin, claim_id: ClaimId, new_receiver: T::AccountId) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            let old_receiver = Self::claim_receivers(claim_id).ok_or(Error::<T>::ClaimNotFound)?;
+            let mut data = Self::open_claims(&old_receiver, claim_id).ok_or(Error::<T>::ClaimNotFound)?;
+            ensure!(sender == data.creator, Error::<T>::NoPermission);
+
+            data.receiver = new_receiver.clone();
+            OpenClaims::<T>::remove(&old_receiver, claim_id);
+            OpenClaims::<T>::insert(&new_receiver, claim_id, data);
+            ClaimReceivers::<T>::insert(claim_id, &new_receiver);
+
+            Self::deposit_event(RawEvent::MarketClaimReceiverChanged(sender, old_receiver, new_receiver, claim_id));
+
+            Ok(())
+        }
+
+        /// Create an asset claim that also pays the receiver a currency reward
+        ///
+        /// - `receiver`: account to receive this asset
+        /// - `asset`: (class_id, token_id)
+        /// - `reward`: amount reserved from the sender and paid out on claim
+        #[weight = 10_000]
+        #[transactional]
+        pub fn create_claim_with_reward(origin, receiver: T::AccountId, asset:(ClassIdOf<T>, TokenIdOf<T>), reward: BalanceOf<T>) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            // Check that the wallet has permission to claim assets
+            ensure!(T::AllowClaim::get(), Error::<T>::ClaimingNotAllowed);
+
+            // Ensure the claim is created
+            let claim_id = Self::do_create_claim(&sender, &receiver, asset, None)?;
+
+            // Lock the reward until the claim is completed or the reward cancelled
+            <T as Config>::Currency::reserve(&sender, reward)?;
+            ClaimRewards::<T>::insert(claim_id, (sender.clone(), reward));
+
+            Self::deposit_event(RawEvent::MarketClaimCreated(sender.clone(), receiver.clone(), asset.0, asset.1));
+            Self::deposit_event(RawEvent::MarketClaimRewardCreated(sender, receiver, claim_id, reward));
+
+            Ok(())
+        }
+
+        /// Cancel the reward attached to a claim and refund it to the issuer.
+        /// The claim itself stays open.
+        ///
+        /// - `claim_id`: claim carrying the reward
+        #[weight = 10_000]
+        pub fn cancel_claim_reward(origin, claim_id: ClaimId) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            let (issuer, reward) = Self::claim_rewards(claim_id).ok_or(Error::<T>::ClaimNotFound)?;
+            ensure!(sender == issuer, Error::<T>::NoPermission);
+
+            <T as Config>::Currency::unreserve(&issuer, reward);
+            ClaimRewards::<T>::remove(claim_id);
+
+            Self::deposit_event(RawEvent::MarketClaimRewardCancelled(issuer, claim_id, reward));
+
+            Ok(())
+        }
+
+        /// Transfer an asset by creating a claim and completing it in one step.
+        /// Indexers see the same events as the two-step claim path.
+        ///
+        /// - `to`: account to receive this asset
+        /// - `asset`: (class_id, token_id)
+        #[weight = 10_000]
+        #[transactional]
+        pub fn transfer_via_claim(origin, to: T::AccountId, asset:(ClassIdOf<T>, TokenIdOf<T>)) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            // AllowClaim is not checked, the issuer completes the claim itself
+            let claim_id = Self::do_create_claim(&sender, &to, asset, None)?;
+
+            Self::deposit_event(RawEvent::MarketClaimCreated(sender, to.clone(), asset.0, asset.1));
+
+            Self::do_execute_claim(&to, claim_id)
+        }
+
+        /// Set the accounts receiving a share of every sale
+        ///
+        /// - `splits`: (recipient, share of the sale price)
+        #[weight = 10_000]
+        pub fn set_fee_splits(origin, splits: Vec<(T::AccountId, Perbill)>) -> DispatchResult{
+
+            T::GovernanceOrigin::ensure_origin(origin)?;
+
+            ensure!(
+                splits.len() <= T::MaxFeeSplitRecipients::get() as usize,
+                Error::<T>::TooManyFeeSplitRecipients
+            );
+
+            // Ensure the shares never exceed the sale price
+            let total = splits.iter().map(|(_, share)| share.deconstruct() as u64).sum::<u64>();
+            ensure!(total <= Perbill::one().deconstruct() as u64, Error::<T>::InvalidFeeSplits);
+
+            FeeSplits::<T>::put(&splits);
+
+            Self::deposit_event(RawEvent::MarketFeeSplitsUpdated(splits));
+
+            Ok(())
+        }
+
+        /// Approve an account to transfer or list an asset once on the owner's behalf
+        ///
+        /// - `asset`: (class_id, token_id)
+        /// - `spender`: account allowed to move the asset
+        #[weight = 10_000]
+        pub fn approve_token(origin, asset:(ClassIdOf<T>, TokenIdOf<T>), spender: T::AccountId) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            // Check that the sender owns this asset
+            let check_ownership = Self::check_ownership(&sender, &asset)?;
+            ensure!(check_ownership, Error::<T>::NoPermission);
+
+            // Replace any previous approval
+            TokenApprovals::<T>::insert(asset, &spender);
+
+            Self::deposit_event(RawEvent::MarketTokenApproved(sender, spender, asset.0, asset.1));
+
+            Ok(())
+        }
+
+        /// Revoke the approval set on an asset
+        ///
+        /// - `asset`: (class_id, token_id)
+        #[weight = 10_000]
+        pub fn revoke_token_approval(origin, asset:(ClassIdOf<T>, TokenIdOf<T>)) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            // Check that the sender owns this asset
+            let check_ownership = Self::check_ownership(&sender, &asset)?;
+            ensure!(check_ownership, Error::<T>::NoPermission);
+
+            // Ensure there is something to revoke
+            ensure!(TokenApprovals::<T>::contains_key(asset), Error::<T>::ApprovalNotFound);
+
+            TokenApprovals::<T>::remove(asset);
+
+            Self::deposit_event(RawEvent::MarketTokenApprovalRevoked(sender, asset.0, asset.1));
+
+            Ok(())
+        }
+
+        /// Transfer a batch of assets to one account. Either every asset moves or none do.
+        ///
+        /// - `to`: account receiving the assets
+        /// - `assets`: list of (class_id, token_id)
+        #[weight = 10_000u64.saturating_mul(assets.len() as Weight)]
+        #[transactional]
+        pub fn batch_transfer(origin, to: T::AccountId, assets: Vec<(ClassIdOf<T>, TokenIdOf<T>)>) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            // Ensure the batch is within bounds
+            ensure!(assets.len() <= T::MaxBatchSize::get() as usize, Error::<T>::BatchTooLarge);
+
+            // Moved directly so the batch emits a single event
+            for asset in assets.iter() {
+                let owner = Self::ensure_can_wallet_transfer(&sender, &to, *asset)?;
+                Self::do_transfer(&owner, &to, *asset)?;
+                Self::on_wallet_transfer(&owner, &to, *asset);
+            }
+
+            Self::deposit_event(RawEvent::MarketBatchTransferred(sender, to, assets.len() as u32));
+
+            Ok(())
+        }
+
+        /// Approve an account to transfer or list each asset in a batch once
+        ///
+        /// - `assets`: list of (class_id, token_id)
+        /// - `spender`: account allowed to move the assets
+        #[weight = 10_000u64.saturating_mul(assets.len() as Weight)]
+        pub fn batch_approve_tokens(origin, assets: Vec<(ClassIdOf<T>, TokenIdOf<T>)>, spender: T::AccountId) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            // Ensure the batch is within bounds
+            ensure!(assets.len() <= T::MaxBatchSize::get() as usize, Error::<T>::BatchTooLarge);
+
+            // Check that the sender owns every asset before approving any of them
+            for asset in assets.iter() {
+                let check_ownership = Self::check_ownership(&sender, asset)?;
+                ensure!(check_ownership, Error::<T>::NoPermission);
+            }
+
+            for asset in assets.iter() {
+                TokenApprovals::<T>::insert(asset, &spender);
+            }
+
+            Self::deposit_event(RawEvent::MarketBatchTokenApproved(sender, spender, assets));
+
+            Ok(())
+        }
+
+        /// Revoke the approvals set on a batch of assets
+        ///
+        /// - `assets`: list of (class_id, token_id)
+        #[weight = 10_000u64.saturating_mul(assets.len() as Weight)]
+        pub fn batch_revoke_token_approvals(origin, assets: Vec<(ClassIdOf<T>, TokenIdOf<T>)>) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            // Ensure the batch is within bounds
+            ensure!(assets.len() <= T::MaxBatchSize::get() as usize, Error::<T>::BatchTooLarge);
+
+            // Check that the sender owns every asset before revoking any approval
+            for asset in assets.iter() {
+                let check_ownership = Self::check_ownership(&sender, asset)?;
+                ensure!(check_ownership, Error::<T>::NoPermission);
+            }
+
+            for asset in assets.iter() {
+                TokenApprovals::<T>::remove(asset);
+            }
+
+            Self::deposit_event(RawEvent::MarketBatchTokenApprovalRevoked(sender, assets));
+
+            Ok(())
+        }
+
+        /// Approve an operator to transfer, list and burn all of the sender's assets in a class
+        ///
+        /// - `class_id`: the class the approval applies to
+        /// - `operator`: account allowed to act on the sender's behalf
+        #[weight = 10_000]
+        pub fn approve_for_class(origin, class_id: ClassIdOf<T>, operator: T::AccountId) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            // Ensure the class exists
+            ensure!(AssetModule::<T>::classes(class_id).is_some(), Error::<T>::AssetNotFound);
+
+            ClassApprovals::<T>::insert(&sender, (class_id, &operator), true);
+
+            Self::deposit_event(RawEvent::MarketClassApprovalSet(sender, operator, class_id));
+
+            Ok(())
+        }
+
+        /// Revoke an operator's approval for a class
+        ///
+        /// - `class_id`: the class the approval applies to
+        /// - `operator`: account to remove
+        #[weight = 10_000]
+        pub fn revoke_class_approval(origin, class_id: ClassIdOf<T>, operator: T::AccountId) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            // Ensure there is something to revoke
+            ensure!(
+                ClassApprovals::<T>::get(&sender, (class_id, &operator)),
+                Error::<T>::ApprovalNotFound
+            );
+
+            ClassApprovals::<T>::remove(&sender, (class_id, &operator));
+
+            Self::deposit_event(RawEvent::MarketClassApprovalRevoked(sender, operator, class_id));
+
+            Ok(())
+        }
+
+        /// Approve or remove an operator that can transfer, list, burn and create claims
+        /// for all of the sender's assets
+        ///
+        /// - `operator`: account allowed to act on the sender's behalf
+        /// - `approved`: whether the operator is approved
+        #[weight = 10_000]
+        pub fn approve_operator(origin, operator: T::AccountId, approved: bool) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            if approved {
+                Approvals::<T>::insert(&sender, &operator, true);
+                Self::deposit_event(RawEvent::MarketOperatorApproved(sender, operator));
+            } else {
+                Approvals::<T>::remove(&sender, &operator);
+                Self::deposit_event(RawEvent::MarketOperatorRevoked(sender, operator));
+            }
+
+            Ok(())
+        }
+
+        /// Revoke an operator's approval for all of the sender's assets
+        ///
+        /// - `operator`: account to remove
+        #[weight = 10_000]
+        pub fn revoke_operator(origin, operator: T::AccountId) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            // Ensure there is something to revoke
+            ensure!(Approvals::<T>::get(&sender, &operator), Error::<T>::ApprovalNotFound);
+
+            Approvals::<T>::remove(&sender, &operator);
+
+            Self::deposit_event(RawEvent::MarketOperatorRevoked(sender, operator));
+
+            Ok(())
+        }
+
+        /// Apply for verification of a collection
+        ///
+        /// - `class_id`: class owned by the sender
+        #[weight = 10_000]
+        pub fn apply_for_verification(origin, class_id: ClassIdOf<T>) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            // Ensure that the sender is the owner of this class
+            let class_info = AssetModule::<T>::classes(class_id).ok_or(Error::<T>::AssetNotFound)?;
+            ensure!(sender == class_info.owner, Error::<T>::NoPermission);
+
+            // Rejected collections may apply again
+            ensure!(
+                matches!(Self::verified_collections(class_id), None | Some(VerificationStatus::Rejected)),
+                Error::<T>::InvalidVerificationStatus
+            );
+
+            VerifiedCollections::<T>::insert(class_id, VerificationStatus::Pending);
+
+            Self::deposit_event(RawEvent::MarketCollectionVerificationRequested(sender, class_id));
+
+            Ok(())
+        }
+
+        /// Approve a pending verification
+        ///
+        /// - `class_id`: class pending verification
+        #[weight = 10_000]
+        pub fn approve_verification(origin, class_id: ClassIdOf<T>) -> DispatchResult{
+
+            T::VerificationOrigin::ensure_origin(origin)?;
+
+            ensure!(
+                Self::verified_collections(class_id) == Some(VerificationStatus::Pending),
+                Error::<T>::InvalidVerificationStatus
+            );
+
+            VerifiedCollections::<T>::insert(class_id, VerificationStatus::Verified);
+
+            Self::deposit_event(RawEvent::MarketCollectionVerified(class_id));
+
+            Ok(())
+        }
+
+        /// Reject a pending verification or revoke a verified collection
+        ///
+        /// - `class_id`: class pending verification or verified
+        #[weight = 10_000]
+        pub fn reject_verification(origin, class_id: ClassIdOf<T>) -> DispatchResult{
+
+            T::VerificationOrigin::ensure_origin(origin)?;
+
+            ensure!(
+                matches!(
+                    Self::verified_collections(class_id),
+                    Some(VerificationStatus::Pending) | Some(VerificationStatus::Verified)
+                ),
+                Error::<T>::InvalidVerificationStatus
+            );
+
+            VerifiedCollections::<T>::insert(class_id, VerificationStatus::Rejected);
+
+            Self::deposit_event(RawEvent::MarketCollectionVerificationRejected(class_id));
+
+            Ok(())
+        }
+
+        /// Ban an account from the market
+        ///
+        /// - `account`: account to ban
+        /// - `until_block`: last block of the ban. Use the maximum block number for a permanent ban
+        #[weight = 10_000]
+        pub fn blacklist_account(origin, account: T::AccountId, until_block: T::BlockNumber) -> DispatchResult{
+
+            T::ForceOrigin::ensure_origin(origin)?;
+
+            BlacklistedAccounts::<T>::insert(&account, until_block);
+
+            Self::deposit_event(RawEvent::MarketAccountBlacklisted(account, until_block));
+
+            Ok(())
+        }
+
+        /// Lift an account's ban
+        ///
+        /// - `account`: banned account
+        #[weight = 10_000]
+        pub fn unblacklist_account(origin, account: T::AccountId) -> DispatchResult{
+
+            T::ForceOrigin::ensure_origin(origin)?;
+
+            ensure!(BlacklistedAccounts::<T>::contains_key(&account), Error::<T>::AccountNotFound);
+            BlacklistedAccounts::<T>::remove(&account);
+
+            Self::deposit_event(RawEvent::MarketAccountUnblacklisted(account));
+
+            Ok(())
+        }
+
+        /// Recount the minted tokens, sales and volume of a class from the orml_nft
+        /// tokens and the order history, overwriting counters left inconsistent by bugs
+        ///
+        /// - `class_id`: class to recount
+        #[weight = T::DbWeight::get().reads_writes(
+            AssetModule::<T>::next_token_id(class_id).saturated_into::<u64>().saturating_mul(2).saturating_add(2),
+            5,
+        )]
+        pub fn recalculate_class_stats(origin, class_id: ClassIdOf<T>) -> DispatchResultWithPostInfo{
+
+            T::ForceOrigin::ensure_origin(origin)?;
+
+            ensure!(AssetModule::<T>::classes(class_id).is_some(), Error::<T>::AssetNotFound);
+
+            let mut token_count: u64 = 0;
+            let mut sales_count: u64 = 0;
+            let mut volume: BalanceOf<T> = Zero::zero();
+            let mut last_sale: Option<OrderOf<T>> = None;
+            for (token_id, _) in orml_nft::Tokens::<T>::iter_prefix(class_id) {
+                token_count = token_count.saturating_add(1);
+                for (_, order) in OrderHistory::<T>::iter_prefix((class_id, token_id)) {
+                    sales_count = sales_count.saturating_add(1);
+                    volume = volume.saturating_add(order.price);
+                    last_sale = match last_sale {
+                        Some(last) if last.id > order.id => Some(last),
+                        _ => Some(order),
+                    };
+                }
+            }
+
+            // orml_nft never reuses token ids, so burned tokens still count towards the cap
+            let minted = token_count.max(AssetModule::<T>::next_token_id(class_id).saturated_into());
+            TotalMintedByClass::<T>::insert(class_id, minted);
+            ClassSalesCount::<T>::insert(class_id, sales_count);
+            ClassTotalVolume::<T>::insert(class_id, volume);
+            match last_sale {
+                Some(order) => {
+                    ClassLastSaleBlock::<T>::insert(class_id, order.block_number);
+                    ClassLastSalePrice::<T>::insert(class_id, order.price);
+                }
+                None => {
+                    ClassLastSaleBlock::<T>::remove(class_id);
+                    ClassLastSalePrice::<T>::remove(class_id);
+                }
+            }
+
+            Self::deposit_event(RawEvent::MarketClassStatsRecalculated(class_id));
+
+            Ok(Some(T::DbWeight::get().reads_writes(
+                token_count.saturating_add(sales_count).saturating_add(2),
+                5,
+            )).into())
+        }
+
+        /// Halt every user operation on the market
+        #[weight = 10_000]
+        pub fn pause(origin) -> DispatchResult{
+
+            T::PauseOrigin::ensure_origin(origin)?;
+
+            Paused::put(true);
+
+            Self::deposit_event(RawEvent::MarketPaused);
+
+            Ok(())
+        }
+
+        /// Resume user operations on the market
+        #[weight = 10_000]
+        pub fn unpause(origin) -> DispatchResult{
+
+            T::PauseOrigin::ensure_origin(origin)?;
+
+            Paused::put(false);
+
+            Self::deposit_event(RawEvent::MarketUnpaused);
+
+            Ok(())
+        }
+
+        /// Mark a listing as under negotiation so it can't be bought
+        ///
+        /// - `listing_id`: id of the Listing
+        /// - `duration_blocks`: number of blocks the reservation lasts
+        #[weight = 10_000]
+        pub fn reserve_listing(origin, listing_id: ListingId, duration_blocks: T::BlockNumber) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            let listing = Self::listings(listing_id).ok_or(Error::<T>::ListingNotFound)?;
+            ensure!(sender == listing.seller, Error::<T>::NoPermission);
+            ensure!(!duration_blocks.is_zero(), Error::<T>::InvalidDuration);
+
+            // Replace any earlier reservation
+            Self::do_unreserve_listing(listing_id);
+
+            let expiry = <system::Module<T>>::block_number().saturating_add(duration_blocks);
+            ReservedListings::<T>::insert(listing_id, (&sender, expiry));
+            ReservationExpiries::<T>::append(expiry, listing_id);
+
+            Self::deposit_event(RawEvent::MarketListingReserved(sender, listing_id, expiry));
+
+            Ok(())
+        }
+
+        /// Release a reserved listing before it expires
+        ///
+        /// - `listing_id`: id of the Listing
+        #[weight = 10_000]
+        pub fn unreserve_listing(origin, listing_id: ListingId) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            let listing = Self::listings(listing_id).ok_or(Error::<T>::ListingNotFound)?;
+            ensure!(sender == listing.seller, Error::<T>::NoPermission);
+
+            ensure!(Self::do_unreserve_listing(listing_id), Error::<T>::ListingNotReserved);
+
+            Self::deposit_event(RawEvent::MarketListingUnreserved(listing_id));
+
+            Ok(())
+        }
+
+        /// Create an asset class
+        ///
+        /// - `metadata`: data for the class. usually an IPFS hash
+        /// - `data`: class data stored by orml_nft
+        /// - `limited_edition`: hard cap on tokens ever minted in this class. Can never be changed
+        #[weight = 10_000]
+        pub fn create_class(origin, metadata: Vec<u8>, data: T::ClassData, limited_edition: Option<u32>) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            Self::ensure_valid_creator_address(&data.creator_address)?;
+            ensure!(
+                data.royalty_rate.is_some() == data.royalty_recipient.is_some(),
+                Error::<T>::InvalidRoyalty
+            );
+            Self::ensure_valid_royalty_split(&data.royalty_recipients)?;
+
+            // Game id 0 leaves the class outside of any game
+            let game_id = data.game_id;
+            ensure!(game_id == 0 || RegisteredGames::contains_key(game_id), Error::<T>::GameNotRegistered);
+
+            let class_id = AssetModule::<T>::create_class(&sender, metadata, data)?;
+            if game_id != 0 {
+                ClassesByGame::<T>::insert(game_id, class_id, ());
+            }
+
+            Self::deposit_event(RawEvent::MarketClassCreated(sender, class_id));
+
+            if let Some(cap) = limited_edition {
+                LimitedEditionCap::<T>::insert(class_id, cap);
+                Self::deposit_event(RawEvent::MarketLimitedEditionClassCreated(class_id, cap));
+            }
+
+            Ok(())
+        }
+
+        /// Mint assets into a class owned by the sender
+        ///
+        /// - `class_id`: the class to place the assets in
+        /// - `metadata`: data for each asset. usually an IPFS hash
+        /// - `data`: token data stored by orml_nft
+        /// - `quantity`: number of assets to mint
+        #[weight = 10_000u64.saturating_mul(*quantity as Weight)]
+        #[transactional]
+        pub fn mint(origin, class_id: ClassIdOf<T>, metadata: Vec<u8>, data: T::TokenData, quantity: u32) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            ensure!(quantity >= 1, Error::<T>::InvalidQuantity);
+            Self::ensure_can_mint(&sender, class_id, quantity)?;
+
+            for _ in 0..quantity {
+                Self::do_mint(&sender, class_id, metadata.clone(), data.clone())?;
+            }
+
+            Ok(())
+        }
+
+        /// Mint one asset to each recipient
+        ///
+        /// - `class_id`: class owned by the sender
+        /// - `recipients`: accounts receiving an asset
+        /// - `metadata`: data for each asset. usually an IPFS hash
+        /// - `data`: token data stored by orml_nft
+        #[weight = 10_000u64.saturating_mul(recipients.len() as Weight)]
+        #[transactional]
+        pub fn airdrop(origin, class_id: ClassIdOf<T>, recipients: Vec<T::AccountId>, metadata: Vec<u8>, data: T::TokenData) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            ensure!(!recipients.is_empty(), Error::<T>::InvalidQuantity);
+            ensure!(recipients.len() <= T::MaxAirdropRecipients::get() as usize, Error::<T>::TooManyAirdropRecipients);
+
+            let count = recipients.len() as u32;
+            Self::ensure_can_mint(&sender, class_id, count)?;
+
+            for recipient in recipients.iter() {
+                Self::do_mint(recipient, class_id, metadata.clone(), data.clone())?;
+            }
+
+            Self::deposit_event(RawEvent::MarketAirdropCompleted(sender, class_id, count));
+
+            Ok(())
+        }
+
+        /// Keep a class off the secondary market. Existing listings are unaffected.
+        ///
+        /// - `class_id`: class owned by the sender
+        /// - `disabled`: true to block new listings
+        #[weight = 10_000]
+        pub fn set_secondary_market(origin, class_id: ClassIdOf<T>, disabled: bool) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            // Ensure that the sender is the owner of this class
+            let class_info = AssetModule::<T>::classes(class_id).ok_or(Error::<T>::AssetNotFound)?;
+            ensure!(sender == class_info.owner, Error::<T>::NoPermission);
+
+            if disabled {
+                SecondaryMarketDisabled::<T>::insert(class_id, true);
+            } else {
+                SecondaryMarketDisabled::<T>::remove(class_id);
+            }
+
+            Self::deposit_event(RawEvent::MarketSecondaryMarketSet(class_id, disabled));
+
+            Ok(())
+        }
+
+        /// Suspend or resume listing, buying and claiming for a class.
+        /// Transfers and burns are still allowed.
+        ///
+        /// - `class_id`: class owned by the sender
+        /// - `tradeable`: false to suspend trading
+        #[weight = 10_000]
+        pub fn set_class_tradeable(origin, class_id: ClassIdOf<T>, tradeable: bool) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            // Ensure that the sender is the owner of this class
+            let class_info = AssetModule::<T>::classes(class_id).ok_or(Error::<T>::AssetNotFound)?;
+            ensure!(sender == class_info.owner, Error::<T>::NoPermission);
+
+            if tradeable {
+                NonTradeable::<T>::remove(class_id);
+            } else {
+                NonTradeable::<T>::insert(class_id, true);
+            }
+
+            Self::deposit_event(RawEvent::MarketClassTradeabilityChanged(class_id, tradeable));
+
+            Ok(())
+        }
+
+        /// Update the off-chain identity linked to a class
+        ///
+        /// - `class_id`: class owned by the sender
+        /// - `address`: UTF-8 creator address, such as a website or address
+        #[weight = 10_000]
+        pub fn update_creator_address(origin, class_id: ClassIdOf<T>, address: Vec<u8>) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            Self::ensure_valid_creator_address(&address)?;
+
+            orml_nft::Classes::<T>::try_mutate(class_id, |class_info| -> DispatchResult {
+                let info = class_info.as_mut().ok_or(Error::<T>::AssetNotFound)?;
+
+                // Ensure that the sender is the owner of this class
+                ensure!(sender == info.owner, Error::<T>::NoPermission);
+
+                info.data.creator_address = address.clone();
+                Ok(())
+            })?;
+
+            Self::deposit_event(RawEvent::MarketCreatorAddressUpdated(class_id, address));
+
+            Ok(())
+        }
+
+        /// Move a class to a newer version of its asset schema
+        ///
+        /// - `class_id`: class owned by the sender
+        /// - `new_version`: schema version, higher than the current one
+        #[weight = 10_000]
+        pub fn update_class_schema_version(origin, class_id: ClassIdOf<T>, new_version: u32) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            let old_version = orml_nft::Classes::<T>::try_mutate(class_id, |class_info| -> Result<u32, DispatchError> {
+                let info = class_info.as_mut().ok_or(Error::<T>::AssetNotFound)?;
+
+                // Ensure that the sender is the owner of this class
+                ensure!(sender == info.owner, Error::<T>::NoPermission);
+
+                // Schema versions only move forward
+                let old_version = info.data.schema_version;
+                ensure!(new_version > old_version, Error::<T>::InvalidSchemaVersion);
+
+                info.data.schema_version = new_version;
+                Ok(old_version)
+            })?;
+
+            Self::deposit_event(RawEvent::MarketClassSchemaUpdated(class_id, old_version, new_version));
+
+            Ok(())
+        }
+
+        /// Replace the properties of an asset, written for the class's current schema
+        ///
+        /// - `asset`: (class_id, token_id) in a class owned by the sender
+        /// - `new_properties`: encoded properties of the asset
+        /// - `schema_version`: schema the properties follow, must be the class's current one
+        #[weight = 10_000]
+        pub fn update_token_properties(origin, asset: (ClassIdOf<T>, TokenIdOf<T>), new_properties: Vec<u8>, schema_version: u32) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            Self::ensure_class_owner_of_asset(&sender, &asset)?;
+            ensure!(
+                schema_version == Self::class_data(asset.0).schema_version,
+                Error::<T>::SchemaVersionMismatch
+            );
+
+            orml_nft::Tokens::<T>::try_mutate(asset.0, asset.1, |token_info| -> DispatchResult {
+                let info = token_info.as_mut().ok_or(Error::<T>::AssetNotFound)?;
+                info.data.properties = new_properties;
+                info.data.schema_version = schema_version;
+                Ok(())
+            })?;
+
+            Self::deposit_event(RawEvent::MarketTokenPropertiesUpdated(asset.0, asset.1, schema_version));
+
+            Ok(())
+        }
+
+        /// Set a game attribute of an asset, replacing the value if the key is already set
+        ///
+        /// - `asset`: (class_id, token_id) in a class owned by the sender
+        /// - `key`: name of the attribute
+        /// - `value`: value of the attribute
+        #[weight = 10_000]
+        pub fn set_token_attribute(origin, asset: (ClassIdOf<T>, TokenIdOf<T>), key: Vec<u8>, value: Vec<u8>) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            Self::ensure_class_owner_of_asset(&sender, &asset)?;
+
+            orml_nft::Tokens::<T>::try_mutate(asset.0, asset.1, |token_info| -> DispatchResult {
+                let info = token_info.as_mut().ok_or(Error::<T>::AssetNotFound)?;
+                let attributes = &mut info.data.game_attributes;
+                match attributes.iter_mut().find(|(existing, _)| *existing == key) {
+                    Some((_, existing_value)) => *existing_value = value.clone(),
+                    None => {
+                        ensure!(
+                            attributes.len() < T::MaxAttributeCount::get() as usize,
+                            Error::<T>::TooManyAttributes
+                        );
+                        attributes.push((key.clone(), value.clone()));
+                    }
+                }
+                Ok(())
+            })?;
+
+            Self::deposit_event(RawEvent::MarketTokenAttributeSet(asset.0, asset.1, key, value));
+
+            Ok(())
+        }
+
+        /// Cap the number of assets that can ever be minted in a class. The cap can't be changed once set.
+        ///
+        /// - `class_id`: class owned by the sender
+        /// - `max_supply`: maximum number of assets, above the number already minted
+        #[weight = 10_000]
+        pub fn set_class_max_supply(origin, class_id: ClassIdOf<T>, max_supply: u32) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            // Limited editions are already capped
+            ensure!(Self::limited_edition_cap(class_id).is_none(), Error::<T>::MaxSupplyAlreadySet);
+            ensure!((max_supply as u64) > Self::total_minted(class_id), Error::<T>::InvalidMaxSupply);
+
+            orml_nft::Classes::<T>::try_mutate(class_id, |class_info| -> DispatchResult {
+                let info = class_info.as_mut().ok_or(Error::<T>::AssetNotFound)?;
+
+                // Ensure that the sender is the owner of this class
+                ensure!(sender == info.owner, Error::<T>::NoPermission);
+                ensure!(info.data.max_supply.is_none(), Error::<T>::MaxSupplyAlreadySet);
+
+                info.data.max_supply = Some(max_supply);
+                Ok(())
+            })?;
+
+            Self::deposit_event(RawEvent::MarketClassMaxSupplySet(class_id, max_supply));
+
+            Ok(())
+        }
+
+        /// Lock the assets of a class until a block. They can't be transferred, listed
+        /// or claimed before it.
+        ///
+        /// - `class_id`: class owned by the sender
+        /// - `block`: first block the assets can be moved in. Must be in the future.
+        #[weight = 10_000]
+        pub fn set_transferable_after(origin, class_id: ClassIdOf<T>, block: T::BlockNumber) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            ensure!(block > <system::Module<T>>::block_number(), Error::<T>::InvalidTransferableAfter);
+
+            orml_nft::Classes::<T>::try_mutate(class_id, |class_info| -> DispatchResult {
+                let info = class_info.as_mut().ok_or(Error::<T>::AssetNotFound)?;
+
+                // Ensure that the sender is the owner of this class
+                ensure!(sender == info.owner, Error::<T>::NoPermission);
+
+                info.data.transferable_after = Some(block);
+                Ok(())
+            })?;
+
+            Self::deposit_event(RawEvent::MarketClassTransferableAfterSet(class_id, block));
+
+            Ok(())
+        }
+
+        /// Share the royalty of a class among collaborators. Replaces any previous split.
+        ///
+        /// - `class_id`: class owned by the sender
+        /// - `splits`: (collaborator, share of the sale price)
+        #[weight = 10_000]
+        pub fn set_royalty_split(origin, class_id: ClassIdOf<T>, splits: Vec<(T::AccountId, Perbill)>) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            Self::ensure_valid_royalty_split(&splits)?;
+
+            orml_nft::Classes::<T>::try_mutate(class_id, |class_info| -> DispatchResult {
+                let info = class_info.as_mut().ok_or(Error::<T>::AssetNotFound)?;
+
+                // Ensure that the sender is the owner of this class
+                ensure!(sender == info.owner, Error::<T>::NoPermission);
+
+                info.data.royalty_recipients = splits.clone();
+                Ok(())
+            })?;
+
+            Self::deposit_event(RawEvent::MarketRoyaltySplitSet(class_id, splits));
+
+            Ok(())
+        }
+
+        /// Hand control of a class to another account. Assets of the class keep their owners.
+        ///
+        /// - `class_id`: class owned by the sender
+        /// - `new_owner`: account becoming the class owner
+        #[weight = 10_000]
+        pub fn transfer_class_ownership(origin, class_id: ClassIdOf<T>, new_owner: T::AccountId) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+            ensure!(!Self::is_blacklisted(&new_owner), Error::<T>::AccountBlacklisted);
+
+            Self::do_transfer_class_ownership(&sender, &new_owner, class_id)?;
+
+            Self::deposit_event(RawEvent::MarketClassOwnershipTransferred(class_id, sender, new_owner));
+
+            Ok(())
+        }
+
+        /// Equip an item to a character. The item can't be moved until it is unequipped.
+        ///
+        /// - `item_asset`: (class_id, token_id) of the item
+        /// - `character_asset`: (class_id, token_id) of the character
+        #[weight = 10_000]
+        pub fn equip(origin, item_asset: (ClassIdOf<T>, TokenIdOf<T>), character_asset: (ClassIdOf<T>, TokenIdOf<T>)) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            ensure!(item_asset != character_asset, Error::<T>::InvalidEquipTarget);
+
+            // Check that the sender owns both assets
+            ensure!(Self::check_ownership(&sender, &item_asset)?, Error::<T>::NoPermission);
+            ensure!(Self::check_ownership(&sender, &character_asset)?, Error::<T>::NoPermission);
+
+            ensure!(!Self::is_equipped(&item_asset), Error::<T>::AlreadyEquipped);
+            ensure!(!Self::is_locked(&item_asset), Error::<T>::AssetLocked);
+
+            Self::do_set_equipped_to(item_asset, Some(character_asset))?;
+
+            Self::deposit_event(RawEvent::MarketAssetEquipped(sender, item_asset.0, item_asset.1, character_asset.0, character_asset.1));
+
+            Ok(())
+        }
+
+        /// Unequip an item so it can be moved again
+        ///
+        /// - `item_asset`: (class_id, token_id) of the item
+        #[weight = 10_000]
+        pub fn unequip(origin, item_asset: (ClassIdOf<T>, TokenIdOf<T>)) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            ensure!(Self::check_ownership(&sender, &item_asset)?, Error::<T>::NoPermission);
+            ensure!(Self::is_equipped(&item_asset), Error::<T>::NotEquipped);
+
+            Self::do_set_equipped_to(item_asset, None)?;
+
+            Self::deposit_event(RawEvent::MarketAssetUnequipped(sender, item_asset.0, item_asset.1));
+
+            Ok(())
+        }
+
+        /// Register a game that classes can be created under
+        ///
+        /// - `game_id`: id of the game, must not be 0
+        /// - `info`: name and metadata of the game
+        #[weight = 10_000]
+        pub fn register_game(origin, game_id: u32, info: GameInfo) -> DispatchResult{
+
+            T::GovernanceOrigin::ensure_origin(origin)?;
+
+            ensure!(game_id != 0 && !RegisteredGames::contains_key(game_id), Error::<T>::GameAlreadyRegistered);
+
+            RegisteredGames::insert(game_id, info);
+
+            Self::deposit_event(RawEvent::MarketGameRegistered(game_id));
+
+            Ok(())
+        }
+
+        /// Send the asset to escrow to be sold to the highest bidder
+        ///
+        /// - `asset`: (class_id, token_id)
+        /// - `starting_price`: lowest accepted first bid
+        /// - `reserve_price`: lowest bid that wins the auction
+        /// - `end_block`: block from which bidding closes
+        #[weight = 10_000]
+        #[transactional]
+        pub fn create_auction(origin, asset: (ClassIdOf<T>, TokenIdOf<T>), starting_price: BalanceOf<T>, reserve_price: BalanceOf<T>, end_block: T::BlockNumber) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            ensure!(starting_price >= T::MinListingPrice::get(), Error::<T>::PriceTooLow);
+
+            // Check that the class and the wallet have permission to escrow assets
+            ensure!(Self::class_data(asset.0).allow_listing, Error::<T>::ClassListingRestricted);
+            ensure!(T::AllowEscrow::get(), Error::<T>::EscrowNotAllowed);
+            ensure!(!Self::secondary_market_disabled(asset.0), Error::<T>::SecondaryMarketDisabled);
+            ensure!(!Self::non_tradeable(asset.0), Error::<T>::ClassNonTradeable);
+            Self::ensure_class_transferable(asset.0)?;
+
+            ensure!(Self::check_ownership(&sender, &asset)?, Error::<T>::NoPermission);
+            ensure!(!Self::is_locked(&asset), Error::<T>::AssetLocked);
+            Self::ensure_holding_period_met(&asset)?;
+
+            ensure!(end_block > <system::Module<T>>::block_number(), Error::<T>::InvalidAuctionEnd);
+
+            // Transfer into escrow
+            let escrow_account: T::AccountId = Self::get_class_escrow_account(asset.0);
+            Self::do_transfer(&sender, &escrow_account, asset)?;
+
+            let auction_id = NextAuctionId::try_mutate(|id| -> Result<AuctionId, DispatchError> {
+                let current_id = *id;
+                *id = id.checked_add(One::one()).ok_or(Error::<T>::NoAvailableAuctionId)?;
+
+                Ok(current_id)
+            })?;
+
+            let deposit = T::AuctionDeposit::get();
+            <T as Config>::Currency::reserve(&sender, deposit)?;
+            AuctionDeposits::<T>::insert(auction_id, deposit);
+
+            Auctions::<T>::insert(auction_id, Auction {
+                id: auction_id,
+                seller: sender.clone(),
+                asset,
+                starting_price,
+                reserve_price,
+                end_block,
+                highest_bid: None,
+            });
+            AllAuctions::<T>::append(asset);
+
+            T::List::on_list(&sender, asset, starting_price)?;
+
+            Self::deposit_event(RawEvent::MarketAuctionCreated(sender, auction_id, asset.0, asset.1, starting_price, end_block));
+
+            Ok(())
+        }
+
+        /// Bid on an auction. The bid is reserved until the auction is settled.
+        /// Bidding again only reserves the difference.
+        ///
+        /// - `auction_id`: id of the Auction
+        /// - `amount`: total bid, above the current highest bid
+        #[weight = 10_000]
+        #[transactional]
+        pub fn bid(origin, auction_id: AuctionId, amount: BalanceOf<T>) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            Auctions::<T>::try_mutate(auction_id, |maybe_auction| -> DispatchResult {
+                let auction = maybe_auction.as_mut().ok_or(Error::<T>::AuctionNotFound)?;
+
+                ensure!(<system::Module<T>>::block_number() < auction.end_block, Error::<T>::AuctionEnded);
+                ensure!(sender != auction.seller, Error::<T>::CannotBidOnOwnAuction);
+
+                match &auction.highest_bid {
+                    Some((_, highest)) => ensure!(amount > *highest, Error::<T>::BidTooLow),
+                    None => ensure!(amount >= auction.starting_price, Error::<T>::BidTooLow),
+                }
+
+                // Record the bid in the auction's history
+                let bid_index = Self::auction_bid_count(auction_id);
+                ensure!(bid_index < T::MaxBidsPerAuction::get(), Error::<T>::TooManyBids);
+                AuctionBidHistory::<T>::insert(
+                    auction_id,
+                    bid_index,
+                    (sender.clone(), amount, <system::Module<T>>::block_number()),
+                );
+                AuctionBidCount::insert(auction_id, bid_index.saturating_add(1));
+
+                // Reserve on top of what the bidder already has in the auction
+                let reserved = Self::bids_by_auction(auction_id, &sender);
+                <T as Config>::Currency::reserve(&sender, amount.saturating_sub(reserved))?;
+                BidsByAuction::<T>::insert(auction_id, &sender, amount);
+
+                auction.highest_bid = Some((sender.clone(), amount));
+
+                Ok(())
+            })?;
+
+            Self::deposit_event(RawEvent::MarketBidPlaced(sender, auction_id, amount));
+
+            Ok(())
+        }
+
+        /// Settle an auction once it has ended. The highest bid wins if it meets the
+        /// reserve price, otherwise the asset goes back to the seller. All other bids
+        /// are unreserved.
+        ///
+        /// - `auction_id`: id of the Auction
+        #[weight = 10_000]
+        #[transactional]
+        pub fn settle_auction(origin, auction_id: AuctionId) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            let auction = Self::auctions(auction_id).ok_or(Error::<T>::AuctionNotFound)?;
+            ensure!(<system::Module<T>>::block_number() >= auction.end_block, Error::<T>::AuctionNotEnded);
+
+            // Release every bid, the winning one is paid out of free balance below
+            for (bidder, reserved) in BidsByAuction::<T>::drain_prefix(auction_id) {
+                <T as Config>::Currency::unreserve(&bidder, reserved);
+            }
+            <T as Config>::Currency::unreserve(&auction.seller, Self::auction_deposits(auction_id));
+
+            let escrow_account: T::AccountId = Self::get_class_escrow_account(auction.asset.0);
+
+            // The winner may have reserved their whole balance, so the bid is paid
+            // even if it reaps their account. A bid that still can't be paid sends
+            // the asset back to the seller rather than locking the auction.
+            let paid_winner = auction.highest_bid.clone().filter(|(winner, amount)| {
+                *amount >= auction.reserve_price
+                    && with_transaction(|| match Self::do_pay_sale_with(
+                        winner,
+                        &auction.seller,
+                        auction.asset.0,
+                        *amount,
+                        T::NativeCurrencyId::get(),
+                        ExistenceRequirement::AllowDeath,
+                    ) {
+                        Ok(()) => TransactionOutcome::Commit(true),
+                        Err(_) => TransactionOutcome::Rollback(false),
+                    })
+            });
+
+            match paid_winner {
+                Some((winner, amount)) => {
+                    Self::do_transfer(&escrow_account, &winner, auction.asset)?;
+                    Self::do_record_sale(&auction.seller, &winner, auction.asset, amount)?;
+
+                    Self::deposit_event(RawEvent::MarketAuctionSettled(auction.seller.clone(), winner, auction_id, amount));
+                }
+                None => {
+                    Self::do_transfer(&escrow_account, &auction.seller, auction.asset)?;
+
+                    Self::deposit_event(RawEvent::MarketAuctionCancelled(auction_id));
+                }
+            }
+
+            Self::remove_auction(&auction);
+
+            Ok(())
+        }
+
+        /// Cancel an auction that has no bids and return the asset to the seller.
+        /// Cancelling before the end block sends EarlyUnlistPenalty of the starting
+        /// price, up to the whole deposit, to the treasury.
+        ///
+        /// - `auction_id`: id of the Auction
+        #[weight = 10_000]
+        #[transactional]
+        pub fn cancel_auction(origin, auction_id: AuctionId) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+
+            let auction = Self::auctions(auction_id).ok_or(Error::<T>::AuctionNotFound)?;
+            ensure!(sender == auction.seller, Error::<T>::NoPermission);
+            ensure!(auction.highest_bid.is_none(), Error::<T>::AuctionHasBids);
+
+            let escrow_account: T::AccountId = Self::get_class_escrow_account(auction.asset.0);
+            Self::do_transfer(&escrow_account, &sender, auction.asset)?;
+
+            let deposit = Self::auction_deposits(auction_id);
+            let penalty = if <system::Module<T>>::block_number() < auction.end_block {
+                (T::EarlyUnlistPenalty::get() * auction.starting_price).min(deposit)
+            } else {
+                Zero::zero()
+            };
+            if !penalty.is_zero() {
+                <T as Config>::Currency::repatriate_reserved(&sender, &Self::get_treasury_account(), penalty, BalanceStatus::Free)?;
+                Self::deposit_event(RawEvent::MarketEarlyUnlistPenaltyApplied(sender.clone(), auction_id, penalty));
+            }
+            <T as Config>::Currency::unreserve(&sender, deposit.saturating_sub(penalty));
+
+            Self::remove_auction(&auction);
+
+            Self::deposit_event(RawEvent::MarketAuctionCancelled(auction_id));
+
+            Ok(())
+        }
+
+        /// Send the asset to escrow to be sold at a price falling over time
+        ///
+        /// - `asset`: (class_id, token_id)
+        /// - `start_price`: price at the start block
+        /// - `floor_price`: price from the end block on
+        /// - `start_block`: block the asset can first be bought in
+        /// - `end_block`: block the price reaches the floor
+        #[weight = 10_000]
+        #[transactional]
+        pub fn create_dutch_auction(origin, asset: (ClassIdOf<T>, TokenIdOf<T>), start_price: BalanceOf<T>, floor_price: BalanceOf<T>, start_block: T::BlockNumber, end_block: T::BlockNumber) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            // The price never drops below the floor
+            ensure!(floor_price >= T::MinListingPrice::get(), Error::<T>::PriceTooLow);
+
+            // Check that the class and the wallet have permission to escrow assets
+            ensure!(Self::class_data(asset.0).allow_listing, Error::<T>::ClassListingRestricted);
+            ensure!(T::AllowEscrow::get(), Error::<T>::EscrowNotAllowed);
+            ensure!(!Self::secondary_market_disabled(asset.0), Error::<T>::SecondaryMarketDisabled);
+            ensure!(!Self::non_tradeable(asset.0), Error::<T>::ClassNonTradeable);
+            Self::ensure_class_transferable(asset.0)?;
+
+            ensure!(Self::check_ownership(&sender, &asset)?, Error::<T>::NoPermission);
+            ensure!(!Self::is_locked(&asset), Error::<T>::AssetLocked);
+            Self::ensure_holding_period_met(&asset)?;
+
+            ensure!(floor_price <= start_price, Error::<T>::InvalidDutchAuctionPrice);
+            ensure!(
+                end_block > start_block && end_block > <system::Module<T>>::block_number(),
+                Error::<T>::InvalidAuctionEnd
+            );
+
+            // Transfer into escrow
+            let escrow_account: T::AccountId = Self::get_class_escrow_account(asset.0);
+            Self::do_transfer(&sender, &escrow_account, asset)?;
+
+            let auction_id = NextAuctionId::try_mutate(|id| -> Result<AuctionId, DispatchError> {
+                let current_id = *id;
+                *id = id.checked_add(One::one()).ok_or(Error::<T>::NoAvailableAuctionId)?;
+
+                Ok(current_id)
+            })?;
+
+            DutchAuctions::<T>::insert(auction_id, DutchAuction {
+                id: auction_id,
+                seller: sender.clone(),
+                asset,
+                start_price,
+                floor_price,
+                start_block,
+                end_block,
+            });
+            AllAuctions::<T>::append(asset);
+
+            T::List::on_list(&sender, asset, start_price)?;
+
+            Self::deposit_event(RawEvent::MarketDutchAuctionCreated(
+                sender,
+                auction_id,
+                asset.0,
+                asset.1,
+                start_price,
+                floor_price,
+                start_block,
+                end_block
+            ));
+
+            Ok(())
+        }
+
+        /// Buy the asset of a dutch auction at its current price
+        ///
+        /// - `auction_id`: id of the Dutch Auction
+        #[weight = 10_000]
+        #[transactional]
+        pub fn buy_dutch(origin, auction_id: AuctionId) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            let auction = Self::dutch_auctions(auction_id).ok_or(Error::<T>::AuctionNotFound)?;
+            ensure!(!Self::non_tradeable(auction.asset.0), Error::<T>::ClassNonTradeable);
+            ensure!(sender != auction.seller, Error::<T>::CannotBidOnOwnAuction);
+
+            let now = <system::Module<T>>::block_number();
+            ensure!(now >= auction.start_block, Error::<T>::AuctionNotStarted);
+            let price = Self::get_dutch_auction_price(&auction, now);
+
+            Self::do_pay_sale(&sender, &auction.seller, auction.asset.0, price, T::NativeCurrencyId::get())?;
+
+            let escrow_account: T::AccountId = Self::get_class_escrow_account(auction.asset.0);
+            Self::do_transfer(&escrow_account, &sender, auction.asset)?;
+            Self::do_record_sale(&auction.seller, &sender, auction.asset, price)?;
+
+            Self::remove_dutch_auction(&auction);
+
+            Self::deposit_event(RawEvent::MarketDutchAuctionPurchased(auction.seller, sender, auction_id, price));
+
+            Ok(())
+        }
+
+        /// Cancel a dutch auction and return the asset to the seller
+        ///
+        /// - `auction_id`: id of the Dutch Auction
+        #[weight = 10_000]
+        #[transactional]
+        pub fn cancel_dutch_auction(origin, auction_id: AuctionId) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+
+            let auction = Self::dutch_auctions(auction_id).ok_or(Error::<T>::AuctionNotFound)?;
+            ensure!(sender == auction.seller, Error::<T>::NoPermission);
+
+            let escrow_account: T::AccountId = Self::get_class_escrow_account(auction.asset.0);
+            Self::do_transfer(&escrow_account, &sender, auction.asset)?;
+
+            Self::remove_dutch_auction(&auction);
+
+            Self::deposit_event(RawEvent::MarketDutchAuctionCancelled(auction_id));
+
+            Ok(())
+        }
+
+        /// Offer to buy an asset. The amount is reserved until the offer is accepted,
+        /// cancelled or cleaned up.
+        ///
+        /// - `asset`: (class_id, token_id)
+        /// - `amount`: amount offered
+        /// - `expiry_block`: last block the offer can be accepted in
+        #[weight = 10_000]
+        #[transactional]
+        pub fn make_offer(origin, asset: (ClassIdOf<T>, TokenIdOf<T>), amount: BalanceOf<T>, expiry_block: T::BlockNumber, currency_id: T::CurrencyId) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+            ensure!(!Self::non_tradeable(asset.0), Error::<T>::ClassNonTradeable);
+            ensure!(
+                currency_id == T::NativeCurrencyId::get() || T::AcceptedCurrencies::get().contains(&currency_id),
+                Error::<T>::CurrencyNotAccepted
+            );
+
+            let token_info = AssetModule::<T>::tokens(asset.0, asset.1).ok_or(Error::<T>::AssetNotFound)?;
+            ensure!(token_info.owner != sender, Error::<T>::CannotOfferOnOwnAsset);
+            ensure!(expiry_block >= <system::Module<T>>::block_number(), Error::<T>::InvalidOfferExpiry);
+
+            let offer_id = NextOfferId::try_mutate(|id| -> Result<OfferId, DispatchError> {
+                let current_id = *id;
+                *id = id.checked_add(One::one()).ok_or(Error::<T>::NoAvailableOfferId)?;
+
+                Ok(current_id)
+            })?;
+
+            Self::do_reserve(currency_id, &sender, amount)?;
+
+            Offers::<T>::insert(offer_id, Offer {
+                offer_id,
+                buyer: sender.clone(),
+                asset,
+                amount,
+                expiry_block,
+                currency_id,
+            });
+            OffersByAsset::<T>::insert(asset, offer_id, ());
+            OffersByBuyer::<T>::insert(&sender, offer_id, ());
+            OffersGiven::<T>::mutate(&sender, |count| *count = count.saturating_add(1));
+            OfferExpiryQueue::<T>::insert(expiry_block.saturating_add(One::one()), offer_id, ());
+
+            Self::deposit_event(RawEvent::MarketOfferMade(sender, offer_id, asset.0, asset.1, amount));
+
+            Ok(())
+        }
+
+        /// Sell an asset to an offer made on it. The seller of a listed asset can
+        /// accept offers on it, which closes the listing first.
+        ///
+        /// - `offer_id`: id of the Offer
+        #[weight = 10_000]
+        #[transactional]
+        pub fn accept_offer(origin, offer_id: OfferId) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            let offer = Self::offers(offer_id).ok_or(Error::<T>::OfferNotFound)?;
+            ensure!(<system::Module<T>>::block_number() <= offer.expiry_block, Error::<T>::OfferExpired);
+            ensure!(!InvalidatedOffers::contains_key(offer_id), Error::<T>::OfferInvalidated);
+            ensure!(!Self::non_tradeable(offer.asset.0), Error::<T>::ClassNonTradeable);
+
+            // A listed asset is held by escrow, take it back for its seller
+            if let Some(listing) = Self::listings_by_asset(offer.asset).and_then(Self::listings) {
+                ensure!(sender == listing.seller, Error::<T>::NoPermission);
+                Self::do_clear_listing(&sender, &listing, false)?;
+                Listings::<T>::remove(listing.id);
+
+                Self::deposit_event(RawEvent::MarketAssetUnlisted(sender.clone(), listing.id, listing.asset.0, listing.asset.1));
+            }
+
+            // Check that the sender owns this asset and can move it
+            ensure!(Self::check_ownership(&sender, &offer.asset)?, Error::<T>::NoPermission);
+            Self::ensure_can_wallet_transfer(&sender, &offer.buyer, offer.asset)?;
+
+            Self::remove_offer(&offer);
+
+            // Pay out of the reserved amount
+            Self::do_unreserve(offer.currency_id, &offer.buyer, offer.amount);
+            Self::do_pay_sale(&offer.buyer, &sender, offer.asset.0, offer.amount, offer.currency_id)?;
+            Self::do_transfer(&sender, &offer.buyer, offer.asset)?;
+            Self::on_wallet_transfer(&sender, &offer.buyer, offer.asset);
+            Self::do_record_sale(&sender, &offer.buyer, offer.asset, offer.amount)?;
+
+            Self::deposit_event(RawEvent::MarketOfferAccepted(sender, offer.buyer, offer_id, offer.amount));
+
+            Ok(())
+        }
+
+        /// Withdraw an offer and unreserve its amount
+        ///
+        /// - `offer_id`: id of the Offer
+        #[weight = 10_000]
+        pub fn cancel_offer(origin, offer_id: OfferId) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+
+            let offer = Self::offers(offer_id).ok_or(Error::<T>::OfferNotFound)?;
+            ensure!(sender == offer.buyer, Error::<T>::NoPermission);
+
+            Self::do_release_offer(&offer);
+
+            Self::deposit_event(RawEvent::MarketOfferCancelled(offer_id));
+
+            Ok(())
+        }
+
+        /// Confirm an offer at a new amount, reserving or unreserving the difference.
+        /// Offers invalidated by a listing price change can be accepted again afterwards.
+        ///
+        /// - `offer_id`: id of the Offer
+        /// - `amount`: new amount offered
+        #[weight = 10_000]
+        #[transactional]
+        pub fn refresh_offer(origin, offer_id: OfferId, amount: BalanceOf<T>) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            Offers::<T>::try_mutate(offer_id, |maybe_offer| -> DispatchResult {
+                let offer = maybe_offer.as_mut().ok_or(Error::<T>::OfferNotFound)?;
+
+                ensure!(sender == offer.buyer, Error::<T>::NoPermission);
+                ensure!(<system::Module<T>>::block_number() <= offer.expiry_block, Error::<T>::OfferExpired);
+
+                if amount > offer.amount {
+                    Self::do_reserve(offer.currency_id, &sender, amount.saturating_sub(offer.amount))?;
+                } else {
+                    Self::do_unreserve(offer.currency_id, &sender, offer.amount.saturating_sub(amount));
+                }
+                offer.amount = amount;
+
+                Ok(())
+            })?;
+            InvalidatedOffers::remove(offer_id);
+
+            Self::deposit_event(RawEvent::MarketOfferRefreshed(sender, offer_id, amount));
+
+            Ok(())
+        }
+
+        /// Turn down an offer made on an asset of the sender and unreserve its amount
+        ///
+        /// - `offer_id`: id of the Offer
+        #[weight = 10_000]
+        pub fn reject_offer(origin, offer_id: OfferId) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            let offer = Self::offers(offer_id).ok_or(Error::<T>::OfferNotFound)?;
+
+            // Check that the sender owns this asset
+            ensure!(Self::check_ownership(&sender, &offer.asset)?, Error::<T>::NoPermission);
+
+            Self::do_release_offer(&offer);
+
+            Self::deposit_event(RawEvent::MarketOfferRejected(sender, offer.buyer, offer_id, offer.amount));
+
+            Ok(())
+        }
+
+        /// Remove an expired offer and unreserve its amount. Callable by anyone.
+        ///
+        /// - `offer_id`: id of the Offer
+        #[weight = 10_000]
+        pub fn cleanup_offer(origin, offer_id: OfferId) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            let offer = Self::offers(offer_id).ok_or(Error::<T>::OfferNotFound)?;
+            ensure!(<system::Module<T>>::block_number() > offer.expiry_block, Error::<T>::OfferNotExpired);
+
+            Self::do_release_offer(&offer);
+
+            Self::deposit_event(RawEvent::MarketOfferExpired(offer.buyer, offer_id, offer.amount));
+
+            Ok(())
+        }
+
+        /// Propose trading an asset of the sender for an asset of another account.
+        /// The sender's asset is held by the swap escrow until the swap is accepted or cancelled.
+        ///
+        /// - `my_asset`: (class_id, token_id) owned by the sender
+        /// - `their_asset`: (class_id, token_id) owned by the counterparty
+        /// - `counterparty`: account that can accept the swap
+        /// - `expiry_block`: last block the swap can be accepted in
+        #[weight = 10_000]
+        #[transactional]
+        pub fn propose_swap(origin, my_asset: (ClassIdOf<T>, TokenIdOf<T>), their_asset: (ClassIdOf<T>, TokenIdOf<T>), counterparty: T::AccountId, expiry_block: T::BlockNumber) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            ensure!(sender != counterparty, Error::<T>::CannotSwapWithSelf);
+            ensure!(expiry_block >= <system::Module<T>>::block_number(), Error::<T>::InvalidSwapExpiry);
+            ensure!(AssetModule::<T>::tokens(their_asset.0, their_asset.1).is_some(), Error::<T>::AssetNotFound);
+
+            // Check that the sender owns this asset and can move it
+            ensure!(Self::check_ownership(&sender, &my_asset)?, Error::<T>::NoPermission);
+            Self::ensure_can_wallet_transfer(&sender, &counterparty, my_asset)?;
+
+            let swap_id = NextSwapId::try_mutate(|id| -> Result<SwapId, DispatchError> {
+                let current_id = *id;
+                *id = id.checked_add(One::one()).ok_or(Error::<T>::NoAvailableSwapId)?;
+
+                Ok(current_id)
+            })?;
+
+            // Transfer into the swap escrow
+            Self::do_transfer(&sender, &Self::get_swap_escrow_account(), my_asset)?;
+
+            SwapProposals::<T>::insert(swap_id, SwapProposal {
+                swap_id,
+                initiator: sender.clone(),
+                counterparty: counterparty.clone(),
+                offered_asset: my_asset,
+                requested_asset: their_asset,
+                expiry_block,
+                proposed_block: <system::Module<T>>::block_number(),
+            });
+
+            Self::deposit_event(RawEvent::MarketSwapProposed(sender, counterparty, swap_id));
+
+            Ok(())
+        }
+
+        /// Accept a swap proposed to the sender, exchanging both assets
+        ///
+        /// - `swap_id`: id of the SwapProposal
+        #[weight = 10_000]
+        #[transactional]
+        pub fn accept_swap(origin, swap_id: SwapId) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            let swap = Self::swap_proposals(swap_id).ok_or(Error::<T>::SwapNotFound)?;
+            ensure!(sender == swap.counterparty, Error::<T>::NoPermission);
+            ensure!(<system::Module<T>>::block_number() <= swap.expiry_block, Error::<T>::SwapExpired);
+            ensure!(!SwapDisputes::<T>::contains_key(swap_id), Error::<T>::SwapDisputed);
+
+            // Check that the counterparty still owns the requested asset and can move it
+            ensure!(Self::check_ownership(&sender, &swap.requested_asset)?, Error::<T>::NoPermission);
+            Self::ensure_can_wallet_transfer(&sender, &swap.initiator, swap.requested_asset)?;
+
+            SwapProposals::<T>::remove(swap_id);
+
+            Self::do_transfer(&sender, &swap.initiator, swap.requested_asset)?;
+            Self::do_transfer(&Self::get_swap_escrow_account(), &sender, swap.offered_asset)?;
+            Self::on_wallet_transfer(&sender, &swap.initiator, swap.requested_asset);
+            Self::on_wallet_transfer(&swap.initiator, &sender, swap.offered_asset);
+
+            Self::deposit_event(RawEvent::MarketSwapCompleted(
+                swap.initiator,
+                sender,
+                swap.offered_asset.0,
+                swap.offered_asset.1,
+                swap.requested_asset.0,
+                swap.requested_asset.1
+            ));
+
+            Ok(())
+        }
+
+        /// Withdraw a swap and return the offered asset to the sender
+        ///
+        /// - `swap_id`: id of the SwapProposal
+        #[weight = 10_000]
+        pub fn cancel_swap(origin, swap_id: SwapId) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+
+            let swap = Self::swap_proposals(swap_id).ok_or(Error::<T>::SwapNotFound)?;
+            ensure!(sender == swap.initiator, Error::<T>::NoPermission);
+            ensure!(!SwapDisputes::<T>::contains_key(swap_id), Error::<T>::SwapDisputed);
+
+            Self::do_transfer(&Self::get_swap_escrow_account(), &sender, swap.offered_asset)?;
+            SwapProposals::<T>::remove(swap_id);
+
+            Self::deposit_event(RawEvent::MarketSwapCancelled(sender, swap_id));
+
+            Ok(())
+        }
+
+        /// Dispute a swap that has been open for longer than SwapTimeoutBlocks.
+        /// The swap can't be accepted or cancelled until the dispute is resolved.
+        ///
+        /// - `swap_id`: id of the SwapProposal
+        #[weight = 10_000]
+        pub fn raise_swap_dispute(origin, swap_id: SwapId) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+
+            let swap = Self::swap_proposals(swap_id).ok_or(Error::<T>::SwapNotFound)?;
+            ensure!(sender == swap.initiator || sender == swap.counterparty, Error::<T>::NoPermission);
+            ensure!(!SwapDisputes::<T>::contains_key(swap_id), Error::<T>::SwapAlreadyDisputed);
+            ensure!(
+                <system::Module<T>>::block_number() > swap.proposed_block.saturating_add(T::SwapTimeoutBlocks::get()),
+                Error::<T>::SwapDisputeTooEarly
+            );
+
+            SwapDisputes::<T>::insert(swap_id, DisputeStatus::Open(sender.clone()));
+
+            Self::deposit_event(RawEvent::MarketSwapDisputeRaised(sender, swap_id));
+
+            Ok(())
+        }
+
+        /// Resolve a swap dispute by returning the offered asset to one of the parties
+        ///
+        /// - `swap_id`: id of the disputed SwapProposal
+        /// - `in_favor_of`: party receiving the offered asset
+        #[weight = 10_000]
+        #[transactional]
+        pub fn resolve_swap_dispute(origin, swap_id: SwapId, in_favor_of: T::AccountId) -> DispatchResult{
+
+            T::ArbitrationOrigin::ensure_origin(origin)?;
+
+            let swap = Self::swap_proposals(swap_id).ok_or(Error::<T>::SwapNotFound)?;
+            let complainant = match Self::swap_disputes(swap_id) {
+                Some(DisputeStatus::Open(complainant)) => complainant,
+                _ => return Err(Error::<T>::SwapNotDisputed.into()),
+            };
+            ensure!(in_favor_of == swap.initiator || in_favor_of == swap.counterparty, Error::<T>::NoPermission);
+
+            Self::do_transfer(&Self::get_swap_escrow_account(), &in_favor_of, swap.offered_asset)?;
+            if in_favor_of != swap.initiator {
+                Self::on_wallet_transfer(&swap.initiator, &in_favor_of, swap.offered_asset);
+            }
+            SwapProposals::<T>::remove(swap_id);
+            SwapDisputes::<T>::insert(swap_id, DisputeStatus::Resolved(complainant));
+
+            Self::deposit_event(RawEvent::MarketSwapDisputeResolved(swap_id, in_favor_of));
+
+            Ok(())
+        }
+
+        /// Update the name and version of the pallet
+        ///
+        /// - `info`: new pallet metadata
+        #[weight = 10_000]
+        pub fn update_pallet_metadata(origin, info: MarketPalletInfo) -> DispatchResult{
+
+            T::GovernanceOrigin::ensure_origin(origin)?;
+
+            ensure!(
+                info.name.len() <= MAX_PALLET_NAME_LENGTH
+                    && info.description.len() <= MAX_PALLET_DESCRIPTION_LENGTH,
+                Error::<T>::InvalidPalletMetadata
+            );
+
+            let version = info.version;
+            PalletMetadata::put(info);
+
+            Self::deposit_event(RawEvent::MarketPalletMetadataUpdated(version));
+
+            Ok(())
+        }
+
+        /// Add a search tag to an asset. Tags are stored in lowercase.
+        ///
+        /// - `asset`: (class_id, token_id) in a class owned by the sender
+        /// - `tag`: tag to add
+        #[weight = 10_000]
+        pub fn add_token_tag(origin, asset: (ClassIdOf<T>, TokenIdOf<T>), tag: Vec<u8>) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            Self::ensure_class_owner_of_asset(&sender, &asset)?;
+
+            ensure!(!tag.is_empty(), Error::<T>::InvalidTag);
+            ensure!(tag.len() <= T::MaxTagLength::get() as usize, Error::<T>::TagTooLong);
+            let tag = tag.to_ascii_lowercase();
+
+            TagsByToken::<T>::try_mutate(asset, |tags| -> DispatchResult {
+                ensure!(!tags.contains(&tag), Error::<T>::TagAlreadyAdded);
+                ensure!(tags.len() < T::MaxTagsPerToken::get() as usize, Error::<T>::TooManyTags);
+                tags.push(tag.clone());
+                Ok(())
+            })?;
+            TokenTags::<T>::insert(&tag, asset, ());
+
+            Self::deposit_event(RawEvent::MarketTokenTagAdded(asset.0, asset.1, tag));
+
+            Ok(())
+        }
+
+        /// Remove a search tag from an asset
+        ///
+        /// - `asset`: (class_id, token_id) in a class owned by the sender
+        /// - `tag`: tag to remove, in any case
+        #[weight = 10_000]
+        pub fn remove_token_tag(origin, asset: (ClassIdOf<T>, TokenIdOf<T>), tag: Vec<u8>) -> DispatchResult{
+
+            let sender = ensure_signed(origin)?;
+            ensure!(!Self::is_blacklisted(&sender), Error::<T>::AccountBlacklisted);
+            ensure!(!Self::paused(), Error::<T>::ModulePaused);
+
+            Self::ensure_class_owner_of_asset(&sender, &asset)?;
+
+            let tag = tag.to_ascii_lowercase();
+
+            TagsByToken::<T>::try_mutate_exists(asset, |maybe_tags| -> DispatchResult {
+                let tags = maybe_tags.as_mut().ok_or(Error::<T>::TagNotFound)?;
+                let index = tags.iter().position(|t| *t == tag).ok_or(Error::<T>::TagNotFound)?;
+                tags.swap_remove(index);
+                if tags.is_empty() {
+                    *maybe_tags = None;
+                }
+                Ok(())
+            })?;
+            TokenTags::<T>::remove(&tag, asset);
+
+            Self::deposit_event(RawEvent::MarketTokenTagRemoved(asset.0, asset.1, tag));
+
+            Ok(())
+        }
+
+    }
+}
+
+// Module Implementation
+impl<T: Config> Module<T> {
+    fn check_ownership(
+        owner: &T::AccountId,
+        asset: &(ClassIdOf<T>, TokenIdOf<T>),
+    ) -> Result<bool, DispatchError> {
+        Ok(AssetModule::<T>::is_owner(owner, *asset))
+    }
+
+    /// Returns the owner of the asset if `who` is the owner or an operator of the owner
+    fn ensure_owner_or_operator(
+        who: &T::AccountId,
+        asset: &(ClassIdOf<T>, TokenIdOf<T>),
+    ) -> Result<T::AccountId, DispatchError> {
+        if Self::check_ownership(who, asset)? {
+            return Ok(who.clone());
+        }
+
+        let token_info =
+            AssetModule::<T>::tokens(asset.0, asset.1).ok_or(Error::<T>::NoPermission)?;
+        ensure!(
+            Self::approvals(&token_info.owner, who)
+                || Self::class_approvals(&token_info.owner, (asset.0, who)),
+            Error::<T>::NoPermission
+        );
+
+        Ok(token_info.owner)
+    }
+
+    /// Check if `who` owns an asset or is approved to act on it
+    pub fn is_approved_or_owner(who: &T::AccountId, asset: &(ClassIdOf<T>, TokenIdOf<T>)) -> bool {
+        Self::ensure_owner_or_approved(who, asset).is_ok()
+    }
+
+    /// Returns the owner of the asset if `who` is the owner, a class operator or its approved spender
+    fn ensure_owner_or_approved(
+        who: &T::AccountId,
+        asset: &(ClassIdOf<T>, TokenIdOf<T>),
+    ) -> Result<T::AccountId, DispatchError> {
+        if Self::token_approvals(asset).as_ref() == Some(who) {
+            let token_info =
+                AssetModule::<T>::tokens(asset.0, asset.1).ok_or(Error::<T>::AssetNotFound)?;
+            return Ok(token_info.owner);
+        }
+
+        Self::ensure_owner_or_operator(who, asset)
+    }
+
+    /// Transfer an asset through the wallet on behalf of `sender`
+    fn do_wallet_transfer(
+        sender: &T::AccountId,
+        to: &T::AccountId,
+        asset: (ClassIdOf<T>, TokenIdOf<T>),
+    ) -> DispatchResult {
+        let owner = Self::ensure_can_wallet_transfer(sender, to, asset)?;
+
+        // Transfer the asset
+        ensure!(
+            T::Transfer::transfer(&owner, to, asset).is_ok(),
+            Error::<T>::TransferCancelled
+        );
+
+        Self::on_wallet_transfer(&owner, to, asset);
+
+        Ok(())
+    }
+
+    /// Burn an asset owned or operated by `sender` through the Burn handler
+    fn do_wallet_burn(
+        sender: &T::AccountId,
+        asset: (ClassIdOf<T>, TokenIdOf<T>),
+    ) -> DispatchResult {
+        // Check that the class and the wallet have permission to burn assets
+        ensure!(
+            Self::class_data(asset.0).allow_burn,
+            Error::<T>::ClassBurnRestricted
+        );
+        ensure!(T::AllowBurn::get(), Error::<T>::BurningNotAllowed);
+
+        // Check that the sender owns this asset or operates its class
+        let owner = Self::ensure_owner_or_operator(sender, &asset)?;
+
+        // Ensure that the asset is not locked in Escrow or Claims
+        ensure!(!Self::is_locked(&asset), Error::<T>::AssetLocked);
+
+        let game_id = AssetModule::<T>::tokens(asset.0, asset.1)
+            .map(|token_info| token_info.data.game_id)
+            .unwrap_or_default();
+
+        // Burn the asset
+        ensure!(
+            T::Burn::burn(&owner, asset).is_ok(),
+            Error::<T>::BurnCancelled
+        );
+
+        AssetsByGame::<T>::remove(game_id, asset);
+        if let Some(value) = LastListingPrice::<T>::take(asset) {
+            AccountNFTValue::<T>::mutate(&owner, |total| *total = total.saturating_sub(value));
+        }
+        for tag in TagsByToken::<T>::take(asset) {
+            TokenTags::<T>::remove(tag, asset);
+        }
+
+        // Drop any approval or message left on the burned asset
+        TokenApprovals::<T>::remove(asset);
+        GiftMessages::<T>::remove(asset);
+
+        Ok(())
+    }
+
+    /// Run the checks for a wallet transfer and return the owner of the asset
+    fn ensure_can_wallet_transfer(
+        sender: &T::AccountId,
+        to: &T::AccountId,
+        asset: (ClassIdOf<T>, TokenIdOf<T>),
+    ) -> Result<T::AccountId, DispatchError> {
+        // Check that the class and the wallet have permission to transfer assets
+        ensure!(
+            Self::class_data(asset.0).allow_transfer,
+            Error::<T>::ClassTransferRestricted
+        );
+        ensure!(T::AllowTransfer::get(), Error::<T>::TransfersNotAllowed);
+        Self::ensure_class_transferable(asset.0)?;
+
+        // Check that the sender owns this asset or is approved to move it
+        let owner = Self::ensure_owner_or_approved(sender, &asset)?;
+
+        // Ensure that the asset is not locked in Escrow or Claims
+        ensure!(!Self::is_locked(&asset), Error::<T>::AssetLocked);
+        ensure!(!Self::is_soulbound(&asset), Error::<T>::SoulboundAsset);
+
+        // Stop the same two accounts passing an asset back and forth
+        let cooldown = T::TransferCooldownPeriod::get();
+        if !cooldown.is_zero() {
+            let pair = (owner.clone(), to.clone());
+            if let Some(last_block) = Self::transfer_cooldown_map(&pair, asset) {
+                let now = <system::Module<T>>::block_number();
+                ensure!(
+                    now.saturating_sub(last_block) >= cooldown,
+                    Error::<T>::TransferCooldownActive
+                );
+            }
+        }
+
+        Ok(owner)
+    }
+
+    /// Record a completed wallet transfer
+    fn on_wallet_transfer(
+        owner: &T::AccountId,
+        to: &T::AccountId,
+        asset: (ClassIdOf<T>, TokenIdOf<T>),
+    ) {
+        if !T::TransferCooldownPeriod::get().is_zero() {
+            let now = <system::Module<T>>::block_number();
+            TransferCooldownMap::<T>::insert((owner.clone(), to.clone()), asset, now);
+        }
+
+        // Approvals are single use
+        TokenApprovals::<T>::remove(asset);
+    }
+
+    /// Get the emoji bytes stored for an emote name
+    fn lookup_emoji(emote: &[u8]) -> Option<Vec<u8>> {
+        let str_emote = str::from_utf8(emote).ok()?;
+        emojis::lookup(str_emote).map(|emoji| emoji.as_str().as_bytes().to_vec())
+    }
+
+    /// Post an emote on an asset
+    fn do_emote(
+        sender: &T::AccountId,
+        asset: (ClassIdOf<T>, TokenIdOf<T>),
+        emote: &[u8],
+    ) -> DispatchResult {
+        // Ensure this token exists
+        ensure!(
+            AssetModule::<T>::tokens(asset.0, asset.1).is_some(),
+            Error::<T>::AssetNotFound
+        );
+
+        // Get emoji, ensuring this is a valid emote
+        let emoji = Self::lookup_emoji(emote).ok_or(Error::<T>::InvalidEmote)?;
+
+        // Get emotes data
+        let mut emotes_data = Emotes::<T>::get(asset, sender);
+
+        // Ensure the sender hasn't reached the emote limit on this asset
+        ensure!(
+            emotes_data.len() < T::MaxEmotesPerAccountPerAsset::get() as usize,
+            Error::<T>::EmoteLimitReached
+        );
+
+        // Append the new emoji
+        emotes_data.push(emoji.clone());
+
+        // Add emote to storage
+        Emotes::<T>::insert(asset, sender, emotes_data);
+        EmoteCountByAsset::<T>::mutate(asset, &emoji, |count| *count = count.saturating_add(1));
+
+        Self::deposit_event(RawEvent::MarketAssetEmotePosted(
+            sender.clone(),
+            asset.0,
+            asset.1,
+            emoji,
+        ));
+
+        Ok(())
+    }
+
+    fn do_transfer(
+        from: &T::AccountId,
+        to: &T::AccountId,
+        asset: (ClassIdOf<T>, TokenIdOf<T>),
+    ) -> Result<bool, DispatchError> {
+        // Soulbound assets never change hands
+        ensure!(!Self::is_soulbound(&asset), Error::<T>::SoulboundAsset);
+        AssetModule::<T>::transfer(from, to, asset)?;
+        // Any approval is tied to the current owner
+        TokenApprovals::<T>::remove(asset);
+        Ok(true)
+    }
+
+    /// Check if an asset is bound to its owner
+    pub fn is_soulbound(asset: &(ClassIdOf<T>, TokenIdOf<T>)) -> bool {
+        AssetModule::<T>::tokens(asset.0, asset.1)
+            .map(|token| token.data.soulbound)
+            .unwrap_or(false)
+    }
+
+    fn is_listed(asset: &(ClassIdOf<T>, TokenIdOf<T>)) -> bool {
+        Self::all_listings().contains(asset)
+    }
+
+    fn is_claiming(asset: &(ClassIdOf<T>, TokenIdOf<T>)) -> bool {
+        Self::claim_index(asset)
+    }
+
+    fn get_claim_account() -> T::AccountId {
         T::ModuleId::get().into_sub_account(100u32)
     }
 
-    fn get_escrow_account() -> T::AccountId {
-        T::ModuleId::get().into_account()
+    /// Escrow account used for listings made before per-class escrow
+    fn get_escrow_account() -> T::AccountId {
+        T::ModuleId::get().into_account()
+    }
+
+    /// Escrow account holding the listed assets of a class.
+    /// Prefixed so it never collides with the claim sub-account.
+    pub fn get_class_escrow_account(class_id: ClassIdOf<T>) -> T::AccountId {
+        T::ModuleId::get().into_sub_account((*b"esc", class_id))
+    }
+
+    /// Escrow account holding the assets offered in open swaps
+    pub fn get_swap_escrow_account() -> T::AccountId {
+        T::ModuleId::get().into_sub_account(*b"swp")
+    }
+
+    /// Treasury account used to refund listed assets missing from escrow
+    pub fn get_treasury_account() -> T::AccountId {
+        T::ModuleId::get().into_sub_account(*b"trs")
+    }
+
+    /// Escrow account currently holding a listed asset. Assets listed before
+    /// the per-class escrow migration stay in the global escrow until moved.
+    fn escrow_account_of(asset: &(ClassIdOf<T>, TokenIdOf<T>)) -> T::AccountId {
+        let global_escrow = Self::get_escrow_account();
+        if AssetModule::<T>::is_owner(&global_escrow, *asset) {
+            global_escrow
+        } else {
+            Self::get_class_escrow_account(asset.0)
+        }
+    }
+
+    /// Move every asset still held by the global escrow into its class escrow
+    fn migrate_to_class_escrow() -> Weight {
+        let global_escrow = Self::get_escrow_account();
+        let listed = Self::all_listings();
+        let mut moved: Weight = 0;
+
+        for asset in listed.iter() {
+            if AssetModule::<T>::is_owner(&global_escrow, *asset) {
+                let class_escrow = Self::get_class_escrow_account(asset.0);
+                if AssetModule::<T>::transfer(&global_escrow, &class_escrow, *asset).is_ok() {
+                    moved += 1;
+                }
+            }
+        }
+
+        StorageVersion::put(Releases::V2_0_0);
+
+        // version + listings + ownership of each listed asset,
+        // each move rewrites the token and both owner entries
+        T::DbWeight::get().reads_writes(2 + listed.len() as Weight, 1 + moved * 3)
+    }
+
+    /// Split each owner's vector of listing ids into one ListingsByOwner entry per listing
+    fn migrate_listings_by_owner() -> Weight {
+        let old_entries: Vec<(T::AccountId, Vec<ListingId>)> =
+            StorageKeyIterator::<T::AccountId, Vec<ListingId>, Blake2_128Concat>::new(
+                b"GamePowerMarket",
+                b"ListingsByOwner",
+            )
+            .drain()
+            .collect();
+
+        let mut migrated: Weight = 0;
+        for (owner, listing_ids) in old_entries.iter() {
+            for listing_id in listing_ids {
+                ListingsByOwner::<T>::insert(owner, listing_id, ());
+                migrated += 1;
+            }
+        }
+
+        StorageVersion::put(Releases::V3_0_0);
+
+        T::DbWeight::get().reads_writes(
+            old_entries.len() as Weight,
+            1 + old_entries.len() as Weight + migrated,
+        )
+    }
+
+    /// Index every asset in an open claim
+    fn migrate_claim_index() -> Weight {
+        let claimed = Self::all_claims();
+        for asset in claimed.iter() {
+            ClaimIndex::<T>::insert(asset, true);
+        }
+
+        StorageVersion::put(Releases::V4_0_0);
+
+        T::DbWeight::get().reads_writes(2, 1 + claimed.len() as Weight)
+    }
+
+    /// Add an empty expiry block to the listings created before listings could expire
+    fn migrate_listing_expiry() -> Weight {
+        let mut translated: Weight = 0;
+        Listings::<T>::translate::<
+            (
+                ListingId,
+                T::AccountId,
+                (ClassIdOf<T>, TokenIdOf<T>),
+                BalanceOf<T>,
+            ),
+            _,
+        >(|_, (id, seller, asset, price)| {
+            translated += 1;
+            Some(Listing {
+                id,
+                seller,
+                asset,
+                price,
+                expiry_block: None,
+                currency_id: T::NativeCurrencyId::get(),
+            })
+        });
+
+        StorageVersion::put(Releases::V5_0_0);
+
+        T::DbWeight::get().reads_writes(1 + translated, 1 + translated)
+    }
+
+    /// Record the class owner as the creator of the claims made before claims had
+    /// a creator. Only class owners can create claims, so this is who made them.
+    /// Claims on a class that no longer exists fall back to their receiver, so the
+    /// claim is kept and can still be claimed.
+    fn migrate_claim_deadline() -> Weight {
+        let mut translated: Weight = 0;
+        OpenClaims::<T>::translate::<(T::AccountId, (ClassIdOf<T>, TokenIdOf<T>)), _>(
+            |_, claim_id, (receiver, asset)| {
+                translated += 1;
+                ClaimReceivers::<T>::insert(claim_id, &receiver);
+                let creator = AssetModule::<T>::classes(asset.0)
+                    .map(|class_info| class_info.owner)
+                    .unwrap_or_else(|| receiver.clone());
+                Some(Claim {
+                    receiver,
+                    asset,
+                    creator,
+                    deadline: None,
+                })
+            },
+        );
+
+        StorageVersion::put(Releases::V6_0_0);
+
+        T::DbWeight::get().reads_writes(1 + 2 * translated, 1 + 2 * translated)
+    }
+
+    /// Price the listings made before listings had a currency in the native currency.
+    /// Listings migrated from V4 already carry it, decoding them with the old layout
+    /// ignores it and writes back the same value.
+    fn migrate_listing_currency() -> Weight {
+        let mut translated: Weight = 0;
+        Listings::<T>::translate::<
+            (
+                ListingId,
+                T::AccountId,
+                (ClassIdOf<T>, TokenIdOf<T>),
+                BalanceOf<T>,
+                Option<T::BlockNumber>,
+            ),
+            _,
+        >(|_, (id, seller, asset, price, expiry_block)| {
+            translated += 1;
+            Some(Listing {
+                id,
+                seller,
+                asset,
+                price,
+                expiry_block,
+                currency_id: T::NativeCurrencyId::get(),
+            })
+        });
+
+        StorageVersion::put(Releases::V7_0_0);
+
+        T::DbWeight::get().reads_writes(1 + translated, 1 + translated)
+    }
+
+    /// Index the asset of every open listing
+    fn migrate_listings_by_asset() -> Weight {
+        let mut indexed: Weight = 0;
+        for listing in Listings::<T>::iter_values() {
+            ListingsByAsset::<T>::insert(listing.asset, listing.id);
+            indexed += 1;
+        }
+
+        StorageVersion::put(Releases::V8_0_0);
+
+        T::DbWeight::get().reads_writes(1 + indexed, 1 + indexed)
+    }
+
+    /// Index every open listing by its class, oldest listing first
+    fn migrate_listings_by_class() -> Weight {
+        let mut listings: Vec<(ClassIdOf<T>, ListingId)> = Listings::<T>::iter_values()
+            .map(|listing| (listing.asset.0, listing.id))
+            .collect();
+        listings.sort_by_key(|(_, listing_id)| *listing_id);
+
+        for (class_id, listing_id) in listings.iter() {
+            ListingsByClass::<T>::append(class_id, listing_id);
+        }
+
+        StorageVersion::put(Releases::V9_0_0);
+
+        let indexed = listings.len() as Weight;
+        T::DbWeight::get().reads_writes(1 + indexed, 1 + indexed)
+    }
+
+    /// Record the sale price as the paid amount of the orders made before orders had one.
+    /// Every sale so far charged the buyer exactly the sale price.
+    fn migrate_order_paid_amount() -> Weight {
+        let mut translated: Weight = 0;
+        OrderHistory::<T>::translate::<
+            (
+                OrderId,
+                T::AccountId,
+                T::AccountId,
+                (ClassIdOf<T>, TokenIdOf<T>),
+                BalanceOf<T>,
+                T::BlockNumber,
+            ),
+            _,
+        >(|_, _, (id, seller, buyer, asset, price, block_number)| {
+            translated += 1;
+            Some(Order {
+                id,
+                seller,
+                buyer,
+                asset,
+                price,
+                paid_amount: price,
+                block_number,
+            })
+        });
+
+        StorageVersion::put(Releases::V10_0_0);
+
+        T::DbWeight::get().reads_writes(1 + translated, 1 + translated)
+    }
+
+    /// Count the claims that were open before claims were counted
+    fn migrate_claim_count() -> Weight {
+        ClaimCount::put(AllClaims::<T>::decode_len().unwrap_or_default() as u64);
+
+        StorageVersion::put(Releases::V11_0_0);
+
+        T::DbWeight::get().reads_writes(2, 2)
+    }
+
+    /// Rewrite the orml_nft classes and tokens stored before ClassData and AssetData
+    /// grew past their properties, keeping the properties and defaulting every other field.
+    /// Entries that already decode in the current layout are left as they are, and so are
+    /// entries that decode in neither layout, which are logged rather than dropped.
+    /// The version is bumped by `on_runtime_upgrade` once the other steps have run.
+    fn migrate_asset_data() -> Weight {
+        let classes = Self::migrate_raw_values(orml_nft::Classes::<T>::final_prefix(), |bytes| {
+            if ClassInfoOf::<T>::decode_all(bytes).is_ok() {
+                return Some(None);
+            }
+            let (metadata, total_issuance, owner, properties) =
+                <(Vec<u8>, TokenIdOf<T>, T::AccountId, Vec<u8>)>::decode_all(bytes).ok()?;
+            let class_info: ClassInfoOf<T> = ClassInfo {
+                metadata,
+                total_issuance,
+                owner,
+                data: ClassData {
+                    properties,
+                    ..Default::default()
+                },
+            };
+            Some(Some(class_info.encode()))
+        });
+        let tokens = Self::migrate_raw_values(orml_nft::Tokens::<T>::final_prefix(), |bytes| {
+            if TokenInfoOf::<T>::decode_all(bytes).is_ok() {
+                return Some(None);
+            }
+            let (metadata, owner, properties) =
+                <(Vec<u8>, T::AccountId, Vec<u8>)>::decode_all(bytes).ok()?;
+            let token_info: TokenInfoOf<T> = TokenInfo {
+                metadata,
+                owner,
+                data: AssetData {
+                    properties,
+                    ..Default::default()
+                },
+            };
+            Some(Some(token_info.encode()))
+        });
+
+        classes.saturating_add(tokens)
+    }
+
+    /// Walk every value stored under `prefix` and rewrite the ones `upgrade` returns new
+    /// bytes for. `upgrade` returns `Some(None)` for values already in the current layout
+    /// and `None` for values it can't read, which keep their bytes and are logged.
+    fn migrate_raw_values(
+        prefix: [u8; 32],
+        upgrade: impl Fn(&[u8]) -> Option<Option<Vec<u8>>>,
+    ) -> Weight {
+        let mut reads: Weight = 0;
+        let mut writes: Weight = 0;
+        let mut unreadable: u32 = 0;
+
+        let mut key = prefix.to_vec();
+        while let Some(next) = sp_io::storage::next_key(&key).filter(|k| k.starts_with(&prefix)) {
+            key = next;
+            reads += 1;
+            let bytes = match unhashed::get_raw(&key) {
+                Some(bytes) => bytes,
+                None => continue,
+            };
+            match upgrade(&bytes) {
+                Some(Some(upgraded)) => {
+                    unhashed::put_raw(&key, &upgraded);
+                    writes += 1;
+                }
+                Some(None) => {}
+                None => unreadable += 1,
+            }
+        }
+        if unreadable > 0 {
+            frame_support::debug::warn!(
+                "market: {} entries decode in no known layout and were left as they are",
+                unreadable
+            );
+        }
+
+        T::DbWeight::get().reads_writes(reads, writes)
+    }
+
+    /// Mark the offers made before offers had a currency as made in the native
+    /// currency, which is the one their amount was reserved in.
+    fn migrate_offer_currency() -> Weight {
+        let mut translated: Weight = 0;
+        Offers::<T>::translate::<
+            (
+                OfferId,
+                T::AccountId,
+                (ClassIdOf<T>, TokenIdOf<T>),
+                BalanceOf<T>,
+                T::BlockNumber,
+            ),
+            _,
+        >(|_, (offer_id, buyer, asset, amount, expiry_block)| {
+            translated += 1;
+            Some(Offer {
+                offer_id,
+                buyer,
+                asset,
+                amount,
+                expiry_block,
+                currency_id: T::NativeCurrencyId::get(),
+            })
+        });
+
+        StorageVersion::put(Releases::V13_0_0);
+
+        T::DbWeight::get().reads_writes(1 + translated, 1 + translated)
+    }
+
+    /// Record the upgrade block as the proposal block of the swaps made before swaps
+    /// recorded it, so their dispute timeout starts counting from the upgrade.
+    fn migrate_swap_proposed_block() -> Weight {
+        let now = <system::Module<T>>::block_number();
+        let mut translated: Weight = 0;
+        SwapProposals::<T>::translate::<
+            (
+                SwapId,
+                T::AccountId,
+                T::AccountId,
+                (ClassIdOf<T>, TokenIdOf<T>),
+                (ClassIdOf<T>, TokenIdOf<T>),
+                T::BlockNumber,
+            ),
+            _,
+        >(
+            |_, (swap_id, initiator, counterparty, offered_asset, requested_asset, expiry_block)| {
+                translated += 1;
+                Some(SwapProposal {
+                    swap_id,
+                    initiator,
+                    counterparty,
+                    offered_asset,
+                    requested_asset,
+                    expiry_block,
+                    proposed_block: now,
+                })
+            },
+        );
+
+        StorageVersion::put(Releases::V14_0_0);
+
+        T::DbWeight::get().reads_writes(1 + translated, 1 + translated)
+    }
+
+    /// Index every open secret listing by its asset and class, oldest listing first
+    fn migrate_secret_listing_indexes() -> Weight {
+        let mut listings: Vec<SecretListingOf<T>> = SecretListings::<T>::iter_values().collect();
+        listings.sort_by_key(|listing| listing.id);
+
+        for listing in listings.iter() {
+            SecretListingsByAsset::<T>::insert(listing.asset, listing.id);
+            SecretListingsByClass::<T>::append(listing.asset.0, listing.id);
+        }
+
+        StorageVersion::put(Releases::V15_0_0);
+
+        let indexed = listings.len() as Weight;
+        T::DbWeight::get().reads_writes(1 + indexed, 1 + 2 * indexed)
+    }
+
+    /// Snapshot the market counters before a runtime upgrade.
+    /// frame-support 3.0 has no try-runtime hooks, so the upgrade harness calls this directly.
+    #[cfg(any(feature = "try-runtime", test))]
+    pub fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+        Ok((
+            Self::listing_count(),
+            Self::order_count(),
+            Self::next_listing_id(),
+        )
+            .encode())
+    }
+
+    /// Check the market counters against the snapshot taken by `pre_upgrade`
+    #[cfg(any(feature = "try-runtime", test))]
+    pub fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+        let (listing_count, order_count, next_listing_id): (u64, u64, ListingId) =
+            Decode::decode(&mut &state[..]).map_err(|_| "invalid pre_upgrade state")?;
+
+        ensure!(
+            Self::listing_count() == listing_count,
+            "ListingCount changed during upgrade"
+        );
+        ensure!(
+            Self::order_count() == order_count,
+            "OrderCount changed during upgrade"
+        );
+        ensure!(
+            Self::next_listing_id() == next_listing_id,
+            "NextListingId changed during upgrade"
+        );
+
+        Ok(())
+    }
+
+    pub fn is_locked(asset: &(ClassIdOf<T>, TokenIdOf<T>)) -> bool {
+        Self::is_listed(asset)
+            || Self::is_claiming(asset)
+            || Self::is_equipped(asset)
+            || Self::is_in_auction(asset)
+    }
+
+    /// Check if an asset is held by an auction
+    pub fn is_in_auction(asset: &(ClassIdOf<T>, TokenIdOf<T>)) -> bool {
+        Self::all_auctions().contains(asset)
+    }
+
+    /// Check if an item is equipped to a character
+    pub fn is_equipped(asset: &(ClassIdOf<T>, TokenIdOf<T>)) -> bool {
+        AssetModule::<T>::tokens(asset.0, asset.1)
+            .and_then(|token| token.data.equipped_to)
+            .is_some()
+    }
+
+    /// Get the owner of an asset and the storage key holding its ownership record.
+    /// Clients pass the key to the node's `state_getReadProof` RPC to get a proof
+    /// that can be checked against the state root in a block header.
+    pub fn get_ownership_storage_key(
+        asset: (ClassIdOf<T>, TokenIdOf<T>),
+    ) -> Option<(T::AccountId, Vec<u8>)> {
+        let token_info = AssetModule::<T>::tokens(asset.0, asset.1)?;
+        let storage_key = orml_nft::Tokens::<T>::hashed_key_for(asset.0, asset.1);
+
+        Some((token_info.owner, storage_key))
+    }
+
+    /// Get the serial number of an asset minted through the market
+    pub fn get_serial_number(asset: (ClassIdOf<T>, TokenIdOf<T>)) -> Option<u64> {
+        let serial_number = AssetModule::<T>::tokens(asset.0, asset.1)?
+            .data
+            .serial_number;
+        if Self::serial_number_registry(asset.0, serial_number) == Some(asset.1) {
+            Some(serial_number)
+        } else {
+            None
+        }
+    }
+
+    /// Get the asset minted with a serial number in a class
+    pub fn get_token_by_serial(class_id: ClassIdOf<T>, serial_number: u64) -> Option<TokenIdOf<T>> {
+        Self::serial_number_registry(class_id, serial_number)
+    }
+
+    /// Get the number of tokens minted in a class
+    pub fn total_minted(class_id: ClassIdOf<T>) -> u64 {
+        Self::total_minted_by_class(class_id)
+    }
+
+    /// Check if an account has posted an emote on an asset.
+    /// `emote` can be the emote name or the emoji itself.
+    pub fn has_emoted(
+        asset: (ClassIdOf<T>, TokenIdOf<T>),
+        account: &T::AccountId,
+        emote: &[u8],
+    ) -> bool {
+        let emoji = Self::lookup_emoji(emote).unwrap_or_else(|| emote.to_vec());
+        Self::emotes(asset, account).contains(&emoji)
+    }
+
+    /// Get all emotes an account has posted on an asset
+    pub fn user_emotes_for_asset(
+        asset: (ClassIdOf<T>, TokenIdOf<T>),
+        account: &T::AccountId,
+    ) -> Vec<Vec<u8>> {
+        Self::emotes(asset, account)
+    }
+
+    /// Get the number of times an emote, by name or emoji, has been posted on an asset
+    pub fn get_emote_count(class_id: ClassIdOf<T>, token_id: TokenIdOf<T>, emote: &[u8]) -> u64 {
+        let emoji = Self::lookup_emoji(emote).unwrap_or_else(|| emote.to_vec());
+        Self::emote_count_by_asset((class_id, token_id), emoji)
+    }
+
+    /// Get the value of a game attribute of an asset
+    pub fn get_token_attribute(
+        class_id: ClassIdOf<T>,
+        token_id: TokenIdOf<T>,
+        key: &[u8],
+    ) -> Option<Vec<u8>> {
+        AssetModule::<T>::tokens(class_id, token_id)?
+            .data
+            .game_attributes
+            .into_iter()
+            .find(|(existing, _)| existing.as_slice() == key)
+            .map(|(_, value)| value)
+    }
+
+    /// Get the creator address linked to a class, if one is set
+    pub fn get_class_creator_address(class_id: ClassIdOf<T>) -> Option<Vec<u8>> {
+        AssetModule::<T>::classes(class_id)
+            .map(|class_info| class_info.data.creator_address)
+            .filter(|address| !address.is_empty())
+    }
+
+    /// Get everything the market knows about an asset in one read
+    pub fn get_token_detail(asset: (ClassIdOf<T>, TokenIdOf<T>)) -> Option<TokenDetailOf<T>> {
+        let token_info = AssetModule::<T>::tokens(asset.0, asset.1)?;
+
+        let lock_reason = if Self::is_listed(&asset) {
+            Some(LockReason::Listed)
+        } else if Self::is_claiming(&asset) {
+            Some(LockReason::Claiming)
+        } else if token_info.data.equipped_to.is_some() {
+            Some(LockReason::Equipped)
+        } else if Self::is_in_auction(&asset) {
+            Some(LockReason::Auction)
+        } else {
+            None
+        };
+
+        let mut emote_counts: BTreeMap<Vec<u8>, u64> = BTreeMap::new();
+        for emoji in Emotes::<T>::iter_prefix_values(asset).flatten() {
+            let count = emote_counts.entry(emoji).or_insert(0);
+            *count = count.saturating_add(1);
+        }
+
+        Some(TokenDetail {
+            owner: token_info.owner,
+            class_id: asset.0,
+            token_id: asset.1,
+            metadata: token_info.metadata,
+            data: token_info.data,
+            lock_reason,
+            emote_counts: emote_counts.into_iter().collect(),
+            last_sale_price: Self::token_price_history(asset)
+                .last()
+                .map(|(price, _)| *price),
+            mint_block: Self::get_mint_block(asset),
+            serial_number: Self::get_serial_number(asset),
+        })
+    }
+
+    /// Get the block an asset was minted in
+    pub fn get_mint_block(asset: (ClassIdOf<T>, TokenIdOf<T>)) -> Option<T::BlockNumber> {
+        Self::token_minted_at(asset)
+    }
+
+    /// Get up to `limit` assets in open claims, starting after `cursor`.
+    /// Also returns the cursor for the next page, or None on the last page.
+    #[allow(clippy::type_complexity)]
+    pub fn get_all_claims_page(
+        cursor: Option<(ClassIdOf<T>, TokenIdOf<T>)>,
+        limit: u32,
+    ) -> (
+        Vec<(ClassIdOf<T>, TokenIdOf<T>)>,
+        Option<(ClassIdOf<T>, TokenIdOf<T>)>,
+    ) {
+        let mut assets = ClaimIndex::<T>::iter().map(|(asset, _)| asset);
+
+        // Skip past the last asset of the previous page
+        if let Some(cursor) = cursor {
+            if assets.by_ref().find(|asset| *asset == cursor).is_none() {
+                return (Vec::new(), None);
+            }
+        }
+
+        let page: Vec<_> = assets.by_ref().take(limit as usize).collect();
+        let next_cursor = match (page.last(), assets.next()) {
+            (Some(last), Some(_)) => Some(*last),
+            _ => None,
+        };
+
+        (page, next_cursor)
+    }
+
+    /// Get the ids of an account's listings in ascending order
+    pub fn listings_by_owner(owner: &T::AccountId) -> Vec<ListingId> {
+        let mut listing_ids: Vec<ListingId> = ListingsByOwner::<T>::iter_prefix(owner)
+            .map(|(listing_id, _)| listing_id)
+            .collect();
+        listing_ids.sort_unstable();
+        listing_ids
+    }
+
+    /// Get a page of the sales of an asset, oldest first
+    pub fn get_order_history(
+        asset: (ClassIdOf<T>, TokenIdOf<T>),
+        offset: u32,
+        limit: u32,
+    ) -> Vec<OrderOf<T>> {
+        Self::orders_by_asset(asset)
+            .into_iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .filter_map(|order_id| Self::order_history(asset, order_id))
+            .collect()
+    }
+
+    /// Get every sale of an asset, oldest first
+    pub fn get_order_history_for_asset(
+        class_id: ClassIdOf<T>,
+        token_id: TokenIdOf<T>,
+    ) -> Vec<OrderOf<T>> {
+        let asset = (class_id, token_id);
+        Self::orders_by_asset(asset)
+            .into_iter()
+            .filter_map(|order_id| Self::order_history(asset, order_id))
+            .collect()
+    }
+
+    /// Get a page of the open listings in the order their assets were listed
+    pub fn get_listings(page: u32, page_size: u32) -> Vec<ListingOf<T>> {
+        let assets: Vec<(ClassIdOf<T>, TokenIdOf<T>)> = Self::all_listings()
+            .into_iter()
+            .skip((page as usize).saturating_mul(page_size as usize))
+            .take(page_size as usize)
+            .collect();
+        if assets.is_empty() {
+            return Vec::new();
+        }
+
+        // Resolve the whole page in a single pass over the listings
+        let wanted: BTreeSet<_> = assets.iter().copied().collect();
+        let mut by_asset: BTreeMap<_, ListingOf<T>> = Listings::<T>::iter_values()
+            .filter(|listing| wanted.contains(&listing.asset))
+            .map(|listing| (listing.asset, listing))
+            .collect();
+
+        assets
+            .into_iter()
+            .filter_map(|asset| by_asset.remove(&asset))
+            .collect()
+    }
+
+    /// Get a listing by its id
+    pub fn get_listing(listing_id: ListingId) -> Option<ListingOf<T>> {
+        Self::listings(listing_id)
+    }
+
+    /// Get a page of the open listings in a class in the order they were listed
+    pub fn get_listings_by_class(
+        class_id: ClassIdOf<T>,
+        page: u32,
+        page_size: u32,
+    ) -> Vec<ListingOf<T>> {
+        Self::listings_by_class(class_id)
+            .into_iter()
+            .skip((page as usize).saturating_mul(page_size as usize))
+            .take(page_size as usize)
+            .filter_map(Self::listings)
+            .collect()
+    }
+
+    /// Get the open listing of an asset
+    pub fn get_listing_by_asset(
+        class_id: ClassIdOf<T>,
+        token_id: TokenIdOf<T>,
+    ) -> Option<ListingOf<T>> {
+        Self::listings_by_asset((class_id, token_id)).and_then(Self::listings)
+    }
+
+    /// Get a page of an account's listings ordered by listing id,
+    /// together with the account's total number of listings
+    pub fn get_listings_by_owner(
+        owner: &T::AccountId,
+        offset: u32,
+        limit: u32,
+    ) -> (Vec<ListingOf<T>>, u32) {
+        let listing_ids = Self::listings_by_owner(owner);
+        let total_count = listing_ids.len() as u32;
+
+        let listings = listing_ids
+            .into_iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .filter_map(Self::listings)
+            .collect();
+
+        (listings, total_count)
+    }
+
+    /// Get a page of the open offers made by an account ordered by offer id
+    pub fn get_offers_by_buyer(
+        buyer: &T::AccountId,
+        page: u32,
+        limit: u32,
+    ) -> Vec<(OfferId, OfferOf<T>)> {
+        let mut offer_ids: Vec<OfferId> = OffersByBuyer::<T>::iter_prefix(buyer)
+            .map(|(offer_id, _)| offer_id)
+            .collect();
+        offer_ids.sort_unstable();
+
+        offer_ids
+            .into_iter()
+            .skip((page as usize).saturating_mul(limit as usize))
+            .take(limit as usize)
+            .filter_map(|offer_id| Self::offers(offer_id).map(|offer| (offer_id, offer)))
+            .collect()
+    }
+
+    /// Get the data of a class, or the defaults if the class doesn't exist
+    fn class_data(class_id: ClassIdOf<T>) -> ClassData<T::AccountId, T::BlockNumber> {
+        AssetModule::<T>::classes(class_id)
+            .map(|class_info| class_info.data)
+            .unwrap_or_default()
+    }
+
+    /// Get the royalty rate and recipient of a class, if it has one
+    pub fn get_royalty(class_id: ClassIdOf<T>) -> Option<(Perbill, T::AccountId)> {
+        let class_data = AssetModule::<T>::classes(class_id)?.data;
+        class_data.royalty_rate.zip(class_data.royalty_recipient)
+    }
+
+    /// Get the name and version of the pallet
+    pub fn get_pallet_metadata() -> Option<MarketPalletInfo> {
+        Self::pallet_metadata()
+    }
+
+    /// Get every asset an account owns, ordered by class and token id.
+    /// Read from orml_nft's owner index, so assets moved outside the market are included.
+    pub fn get_owned_assets(account: &T::AccountId) -> Vec<(ClassIdOf<T>, TokenIdOf<T>)> {
+        let mut assets: Vec<(ClassIdOf<T>, TokenIdOf<T>)> =
+            orml_nft::TokensByOwner::<T>::iter_prefix(account)
+                .map(|(asset, _)| asset)
+                .collect();
+        assets.sort();
+        assets
+    }
+
+    /// Get the assets an account owns in a class, ordered by token id
+    pub fn get_owned_assets_by_class(
+        account: &T::AccountId,
+        class_id: ClassIdOf<T>,
+    ) -> Vec<(ClassIdOf<T>, TokenIdOf<T>)> {
+        let mut assets: Vec<(ClassIdOf<T>, TokenIdOf<T>)> =
+            orml_nft::TokensByOwner::<T>::iter_prefix(account)
+                .map(|(asset, _)| asset)
+                .filter(|asset| asset.0 == class_id)
+                .collect();
+        assets.sort();
+        assets
+    }
+
+    /// Get the estimated value of an account's assets. Each asset counts at the
+    /// price it was last listed at, or 0 if it was never listed.
+    pub fn estimate_portfolio_value(account: &T::AccountId) -> BalanceOf<T> {
+        Self::account_nft_value(account)
+    }
+
+    /// Get a page of the assets carrying a tag, in any case, ordered by asset
+    pub fn search_tokens_by_tag(
+        tag: Vec<u8>,
+        page: u32,
+        limit: u32,
+    ) -> Vec<(ClassIdOf<T>, TokenIdOf<T>)> {
+        let mut assets: Vec<(ClassIdOf<T>, TokenIdOf<T>)> =
+            TokenTags::<T>::iter_prefix(tag.to_ascii_lowercase())
+                .map(|(asset, _)| asset)
+                .collect();
+        assets.sort();
+
+        assets
+            .into_iter()
+            .skip((page as usize).saturating_mul(limit as usize))
+            .take(limit as usize)
+            .collect()
+    }
+
+    /// Get a page of a game's classes ordered by class id
+    pub fn get_classes_by_game(game_id: u32, page: u32, limit: u32) -> Vec<ClassIdOf<T>> {
+        let mut class_ids: Vec<ClassIdOf<T>> = ClassesByGame::<T>::iter_prefix(game_id)
+            .map(|(class_id, _)| class_id)
+            .collect();
+        class_ids.sort();
+
+        class_ids
+            .into_iter()
+            .skip((page as usize).saturating_mul(limit as usize))
+            .take(limit as usize)
+            .collect()
+    }
+
+    /// Get a page of the open listings in a game's classes ordered by listing id
+    pub fn get_active_listings_by_game(game_id: u32, page: u32, limit: u32) -> Vec<ListingOf<T>> {
+        let mut listings: Vec<ListingOf<T>> = Listings::<T>::iter_values()
+            .filter(|listing| ClassesByGame::<T>::contains_key(game_id, listing.asset.0))
+            .collect();
+        listings.sort_by_key(|listing| listing.id);
+
+        listings
+            .into_iter()
+            .skip((page as usize).saturating_mul(limit as usize))
+            .take(limit as usize)
+            .collect()
+    }
+
+    /// Get the gift message attached to an asset
+    pub fn get_gift_message(asset: (ClassIdOf<T>, TokenIdOf<T>)) -> Option<Vec<u8>> {
+        Self::gift_messages(asset)
+    }
+
+    /// Get the recorded sale prices of an asset, oldest first
+    pub fn get_price_history(
+        asset: (ClassIdOf<T>, TokenIdOf<T>),
+    ) -> Vec<(BalanceOf<T>, T::BlockNumber)> {
+        Self::token_price_history(asset)
     }
 
-    pub fn is_locked(asset: &(ClassIdOf<T>, TokenIdOf<T>)) -> bool {
-        Self::is_listed(asset) || Self::is_claiming(asset)
+    /// Get `points` entries of the price history evenly spaced over block numbers.
+    /// Prices between two sales are linearly interpolated.
+    pub fn get_price_chart_data(
+        asset: (ClassIdOf<T>, TokenIdOf<T>),
+        points: u32,
+    ) -> Vec<(BalanceOf<T>, T::BlockNumber)> {
+        let history = Self::token_price_history(asset);
+        let points = points as usize;
+
+        if points == 0 || history.is_empty() {
+            return Vec::new();
+        }
+        if points >= history.len() {
+            return history;
+        }
+        if points == 1 {
+            return history[history.len() - 1..].to_vec();
+        }
+
+        let to_u128 = |(price, block): &(BalanceOf<T>, T::BlockNumber)| -> (u128, u128) {
+            ((*price).saturated_into(), (*block).saturated_into())
+        };
+        let first_block = to_u128(&history[0]).1;
+        let span = to_u128(&history[history.len() - 1]).1 - first_block;
+        let mut segment = 0;
+
+        (0..points)
+            .map(|i| {
+                let block = first_block
+                    + multiply_by_rational(span, i as u128, (points - 1) as u128).unwrap_or(span);
+
+                // Move to the pair of sales surrounding this block
+                while segment + 2 < history.len() && to_u128(&history[segment + 1]).1 < block {
+                    segment += 1;
+                }
+                let (start_price, start_block) = to_u128(&history[segment]);
+                let (end_price, end_block) = to_u128(&history[segment + 1]);
+
+                let price = if end_block <= start_block {
+                    end_price
+                } else {
+                    let offset = block.saturating_sub(start_block);
+                    let length = end_block - start_block;
+                    if end_price >= start_price {
+                        start_price
+                            + multiply_by_rational(end_price - start_price, offset, length)
+                                .unwrap_or_default()
+                    } else {
+                        start_price
+                            - multiply_by_rational(start_price - end_price, offset, length)
+                                .unwrap_or_default()
+                    }
+                };
+
+                (price.saturated_into(), block.saturated_into())
+            })
+            .collect()
+    }
+
+    /// Get sale and listing figures for a class.
+    /// Daily volume is summed from the price history of each asset.
+    pub fn get_class_market_data(
+        class_id: ClassIdOf<T>,
+    ) -> ClassMarketData<BalanceOf<T>, T::BlockNumber> {
+        let floor_price = Self::listings_by_class(class_id)
+            .into_iter()
+            .filter_map(Self::listings)
+            .map(|listing| listing.price)
+            .min();
+
+        ClassMarketData {
+            last_sale_block: Self::class_last_sale_block(class_id),
+            last_sale_price: Self::class_last_sale_price(class_id),
+            floor_price,
+            volume_24h: Self::class_volume_24h(class_id),
+        }
+    }
+
+    /// Get listing and sale figures for a class
+    pub fn get_class_listing_stats(class_id: ClassIdOf<T>) -> ClassListingStats<BalanceOf<T>> {
+        let mut active_listing_count: u32 = 0;
+        let mut floor_price: Option<BalanceOf<T>> = None;
+        let mut sellers = BTreeSet::new();
+
+        for listing in Self::listings_by_class(class_id)
+            .into_iter()
+            .filter_map(Self::listings)
+        {
+            active_listing_count = active_listing_count.saturating_add(1);
+            floor_price = Some(floor_price.map_or(listing.price, |floor| floor.min(listing.price)));
+            sellers.insert(listing.seller);
+        }
+
+        ClassListingStats {
+            active_listing_count,
+            floor_price,
+            highest_listing_price: Self::highest_listing_price(class_id),
+            volume_24h: Self::class_volume_24h(class_id),
+            sales_count: Self::class_sales_count(class_id),
+            unique_sellers: sellers.len().saturated_into::<u32>(),
+        }
+    }
+
+    /// Amount paid for assets of a class over the last day
+    fn class_volume_24h(class_id: ClassIdOf<T>) -> BalanceOf<T> {
+        let day_start = <system::Module<T>>::block_number().saturating_sub(T::BlocksPerDay::get());
+
+        TokenPriceHistory::<T>::iter()
+            .filter(|(asset, _)| asset.0 == class_id)
+            .flat_map(|(_, history)| history)
+            .filter(|(_, block)| *block > day_start)
+            .fold(Zero::zero(), |volume: BalanceOf<T>, (price, _)| {
+                volume.saturating_add(price)
+            })
+    }
+
+    /// Check if an account is currently banned from the market
+    pub fn is_blacklisted(who: &T::AccountId) -> bool {
+        match Self::blacklisted_accounts(who) {
+            Some(until_block) => <system::Module<T>>::block_number() <= until_block,
+            None => false,
+        }
+    }
+
+    /// Get the number of times an asset has been listed
+    pub fn get_relist_count(asset: (ClassIdOf<T>, TokenIdOf<T>)) -> u32 {
+        AssetModule::<T>::tokens(asset.0, asset.1)
+            .map(|info| info.data.mint_count)
+            .unwrap_or_default()
+    }
+
+    /// Check if a collection has been verified
+    pub fn is_verified_collection(class_id: ClassIdOf<T>) -> bool {
+        Self::verified_collections(class_id) == Some(VerificationStatus::Verified)
+    }
+
+    /// Value an asset held by `owner` at a new listing price
+    fn do_revalue_asset(
+        owner: &T::AccountId,
+        asset: (ClassIdOf<T>, TokenIdOf<T>),
+        price: BalanceOf<T>,
+    ) {
+        let previous = LastListingPrice::<T>::mutate(asset, |value| value.replace(price));
+        AccountNFTValue::<T>::mutate(owner, |total| {
+            *total = total
+                .saturating_sub(previous.unwrap_or_else(Zero::zero))
+                .saturating_add(price)
+        });
+    }
+
+    fn do_record_sale(
+        seller: &T::AccountId,
+        buyer: &T::AccountId,
+        asset: (ClassIdOf<T>, TokenIdOf<T>),
+        price: BalanceOf<T>,
+    ) -> DispatchResult {
+        // Every sale path records its sale here, so this is where the buy hook runs
+        T::Buy::on_buy(seller, buyer, asset, price)?;
+
+        let order_id = NextOrderId::try_mutate(|id| -> Result<OrderId, DispatchError> {
+            let current_id = *id;
+            *id = id
+                .checked_add(One::one())
+                .ok_or(Error::<T>::NoAvailableOrderId)?;
+
+            Ok(current_id)
+        })?;
+        OrderHistory::<T>::insert(
+            asset,
+            order_id,
+            Order {
+                id: order_id,
+                seller: seller.clone(),
+                buyer: buyer.clone(),
+                asset,
+                price,
+                // Nothing is discounted yet, buyers pay the sale price in full
+                paid_amount: price,
+                block_number: <system::Module<T>>::block_number(),
+            },
+        );
+        OrdersByAsset::<T>::append(asset, order_id);
+        orml_nft::Tokens::<T>::mutate(asset.0, asset.1, |token_info| {
+            if let Some(info) = token_info {
+                info.data.last_purchased_at = Some(<system::Module<T>>::block_number());
+            }
+        });
+
+        OrderCount::mutate(|count| *count = count.saturating_add(1));
+        TotalVolume::<T>::mutate(|volume| *volume = volume.saturating_add(price));
+        ClassLastSaleBlock::<T>::insert(asset.0, <system::Module<T>>::block_number());
+        ClassLastSalePrice::<T>::insert(asset.0, price);
+        ClassSalesCount::<T>::mutate(asset.0, |count| *count = count.saturating_add(1));
+        ClassTotalVolume::<T>::mutate(asset.0, |volume| *volume = volume.saturating_add(price));
+
+        let max_depth = T::MaxPriceHistoryDepth::get() as usize;
+        if max_depth == 0 {
+            return Ok(());
+        }
+
+        TokenPriceHistory::<T>::mutate(asset, |history| {
+            // Drop the oldest entries to make room
+            if history.len() >= max_depth {
+                history.drain(..=history.len() - max_depth);
+            }
+            history.push((price, <system::Module<T>>::block_number()));
+        });
+
+        Ok(())
     }
 
     fn do_unlist(
@@ -564,60 +5275,711 @@ impl<T: Config> Module<T> {
         listing_data: ListingOf<T>,
         is_buy: bool,
     ) -> Result<bool, DispatchError> {
-        //Escrow Account
-        let escrow_account: T::AccountId = Self::get_escrow_account();
+        // Roll back the escrow transfer if the listing can't be cleared or the
+        // unlist handler fails, but keep the recovery failure event
+        let result =
+            with_transaction(
+                || match Self::do_clear_listing(sender, &listing_data, is_buy) {
+                    Ok(()) => TransactionOutcome::Commit(Ok(true)),
+                    Err(e) => TransactionOutcome::Rollback(Err(e)),
+                },
+            );
+
+        if result == Err(Error::<T>::InsufficientEscrowBalance.into()) {
+            Self::deposit_event(RawEvent::MarketEscrowRecoveryFailed(listing_data.id));
+        }
+
+        result
+    }
 
-        // Transfer out of escrow
-        if !is_buy {
-            Self::do_transfer(&escrow_account, sender, listing_data.asset).ok();
+    fn do_clear_listing(
+        sender: &T::AccountId,
+        listing_data: &ListingOf<T>,
+        is_buy: bool,
+    ) -> DispatchResult {
+        //Escrow Account
+        let escrow_account: T::AccountId = Self::escrow_account_of(&listing_data.asset);
+
+        // Transfer out of escrow. `escrow_account_of` already falls back to the
+        // global escrow, so a failure here means no escrow holds the asset and
+        // the treasury is the last place an emergency refund can come from.
+        if !is_buy
+            && Self::do_transfer(&escrow_account, sender, listing_data.asset).is_err()
+            && Self::do_transfer(&Self::get_treasury_account(), sender, listing_data.asset).is_err()
+        {
+            return Err(Error::<T>::InsufficientEscrowBalance.into());
         }
 
         // Remove the asset from all listings
+        Self::remove_from_all_listings(&listing_data.asset)?;
+        Self::do_unreserve_listing(listing_data.id);
+
+        // Remove listing from owner, asset and class
+        ListingsByOwner::<T>::remove(&listing_data.seller, listing_data.id);
+        ListingsByAsset::<T>::remove(listing_data.asset);
+        ListingsByClass::<T>::mutate_exists(listing_data.asset.0, |maybe_ids| {
+            if let Some(ids) = maybe_ids {
+                ids.retain(|id| *id != listing_data.id);
+                if ids.is_empty() {
+                    *maybe_ids = None;
+                }
+            }
+        });
+
+        // The listing is still in storage, so leave it out of the new highest price
+        Self::refresh_highest_listing_price(listing_data.asset.0, Some(listing_data.id));
+
+        // Offers invalidated by a price change of this listing are valid again
+        for (offer_id, _) in OffersByAsset::<T>::iter_prefix(listing_data.asset) {
+            InvalidatedOffers::remove(offer_id);
+        }
+
+        Self::check_escrow_balance();
+
+        T::Unlist::on_unlist(&listing_data.seller, listing_data.asset, is_buy)
+    }
+
+    /// Recompute the highest listing price of a class, ignoring `excluded`
+    fn refresh_highest_listing_price(class_id: ClassIdOf<T>, excluded: Option<ListingId>) {
+        let highest_price = Self::listings_by_class(class_id)
+            .into_iter()
+            .filter(|listing_id| Some(*listing_id) != excluded)
+            .filter_map(Self::listings)
+            .map(|listing| listing.price)
+            .max();
+        HighestListingPrice::<T>::mutate_exists(class_id, |highest| *highest = highest_price);
+    }
+
+    /// Warn when the escrow free balance is below the threshold, at most once per cooldown
+    fn check_escrow_balance() {
+        let free_balance = T::Currency::free_balance(&Self::get_escrow_account());
+        if free_balance >= T::EscrowWarningThreshold::get() {
+            return;
+        }
+
+        let now = <system::Module<T>>::block_number();
+        if let Some(last_alert) = Self::last_escrow_alert() {
+            if now < last_alert.saturating_add(T::EscrowAlertCooldown::get()) {
+                return;
+            }
+        }
+
+        LastEscrowAlert::<T>::put(now);
+        Self::deposit_event(RawEvent::MarketEscrowBalanceLow(free_balance));
+    }
+
+    /// Transfer a payment in the given currency. Only the native currency honours
+    /// the existence requirement.
+    fn do_pay(
+        currency_id: T::CurrencyId,
+        from: &T::AccountId,
+        to: &T::AccountId,
+        amount: BalanceOf<T>,
+        existence_requirement: ExistenceRequirement,
+    ) -> DispatchResult {
+        if currency_id == T::NativeCurrencyId::get() {
+            <T as Config>::Currency::transfer(from, to, amount, existence_requirement)
+        } else {
+            T::MultiCurrency::transfer(currency_id, from, to, amount)
+        }
+    }
+
+    /// Reserve an amount in the native currency or one of the accepted currencies
+    fn do_reserve(
+        currency_id: T::CurrencyId,
+        who: &T::AccountId,
+        amount: BalanceOf<T>,
+    ) -> DispatchResult {
+        if currency_id == T::NativeCurrencyId::get() {
+            <T as Config>::Currency::reserve(who, amount)
+        } else {
+            T::MultiCurrency::reserve(currency_id, who, amount)
+        }
+    }
+
+    /// Unreserve an amount reserved with `do_reserve`
+    fn do_unreserve(currency_id: T::CurrencyId, who: &T::AccountId, amount: BalanceOf<T>) {
+        if currency_id == T::NativeCurrencyId::get() {
+            <T as Config>::Currency::unreserve(who, amount);
+        } else {
+            T::MultiCurrency::unreserve(currency_id, who, amount);
+        }
+    }
+
+    /// Pay for a sale, sending the marketplace fee, each fee split its share and
+    /// the class royalties out of what is left, and the rest to the seller
+    fn do_pay_sale(
+        buyer: &T::AccountId,
+        seller: &T::AccountId,
+        class_id: ClassIdOf<T>,
+        price: BalanceOf<T>,
+        currency_id: T::CurrencyId,
+    ) -> DispatchResult {
+        Self::do_pay_sale_with(
+            buyer,
+            seller,
+            class_id,
+            price,
+            currency_id,
+            ExistenceRequirement::KeepAlive,
+        )
+    }
+
+    /// Pay for a sale like `do_pay_sale`, keeping the buyer's account alive only
+    /// if `existence_requirement` asks for it
+    fn do_pay_sale_with(
+        buyer: &T::AccountId,
+        seller: &T::AccountId,
+        class_id: ClassIdOf<T>,
+        price: BalanceOf<T>,
+        currency_id: T::CurrencyId,
+        existence_requirement: ExistenceRequirement,
+    ) -> DispatchResult {
+        let mut remainder = price;
+
+        let marketplace_fee = T::MarketplaceFeeRate::get().mul_floor(price);
+        if !marketplace_fee.is_zero() {
+            let recipient = T::FeeRecipient::get();
+            Self::do_pay(
+                currency_id,
+                buyer,
+                &recipient,
+                marketplace_fee,
+                ExistenceRequirement::AllowDeath,
+            )?;
+            remainder = remainder.saturating_sub(marketplace_fee);
+            Self::deposit_event(RawEvent::MarketFeeCollected(marketplace_fee, recipient));
+        }
+
+        for (recipient, share) in Self::fee_splits() {
+            let fee = share.mul_floor(price).min(remainder);
+            if fee.is_zero() {
+                continue;
+            }
+            Self::do_pay(currency_id, buyer, &recipient, fee, existence_requirement)?;
+            remainder = remainder.saturating_sub(fee);
+        }
+
+        let class_data = Self::class_data(class_id);
+        let royalties = class_data
+            .royalty_rate
+            .zip(class_data.royalty_recipient)
+            .map(|(rate, recipient)| (recipient, rate))
+            .into_iter()
+            .chain(class_data.royalty_recipients);
+
+        let mut total_royalty: BalanceOf<T> = Zero::zero();
+        for (recipient, rate) in royalties {
+            // Never pay out more than the fee splits left over
+            let royalty = rate.mul_floor(price).min(remainder);
+            if royalty.is_zero() {
+                continue;
+            }
+            Self::do_pay(
+                currency_id,
+                buyer,
+                &recipient,
+                royalty,
+                existence_requirement,
+            )?;
+            remainder = remainder.saturating_sub(royalty);
+            total_royalty = total_royalty.saturating_add(royalty);
+        }
+
+        if !total_royalty.is_zero() {
+            Self::deposit_event(RawEvent::MarketRoyaltiesDistributed(
+                class_id,
+                total_royalty,
+            ));
+        }
+
+        Self::do_pay(currency_id, buyer, seller, remainder, existence_requirement)
+    }
+
+    fn do_set_equipped_to(
+        asset: (ClassIdOf<T>, TokenIdOf<T>),
+        equipped_to: Option<(ClassIdOf<T>, TokenIdOf<T>)>,
+    ) -> DispatchResult {
+        orml_nft::Tokens::<T>::try_mutate(asset.0, asset.1, |token| -> DispatchResult {
+            let info = token.as_mut().ok_or(Error::<T>::AssetDataUpdateFailed)?;
+            info.data.equipped_to = equipped_to;
+            Ok(())
+        })
+    }
+
+    fn do_increment_relist_count(asset: (ClassIdOf<T>, TokenIdOf<T>)) -> DispatchResult {
+        orml_nft::Tokens::<T>::try_mutate(asset.0, asset.1, |token| -> DispatchResult {
+            let info = token.as_mut().ok_or(Error::<T>::AssetDataUpdateFailed)?;
+            info.data.mint_count = info.data.mint_count.saturating_add(1);
+            Ok(())
+        })
+    }
+
+    fn add_to_all_listings(asset: (ClassIdOf<T>, TokenIdOf<T>)) {
+        AllListings::<T>::append(asset);
+        ListingCount::mutate(|count| *count = count.saturating_add(1));
+    }
+
+    fn remove_bundle_listing(bundle: &BundleListingOf<T>) -> DispatchResult {
+        for asset in bundle.assets.iter() {
+            Self::remove_from_all_listings(asset)?;
+        }
+        BundleListings::<T>::remove(bundle.id);
+
+        Ok(())
+    }
+
+    fn is_listing_expired(listing: &ListingOf<T>, now: T::BlockNumber) -> bool {
+        matches!(listing.expiry_block, Some(expiry) if now > expiry)
+    }
+
+    fn remove_offer(offer: &OfferOf<T>) {
+        Offers::<T>::remove(offer.offer_id);
+        OffersByAsset::<T>::remove(offer.asset, offer.offer_id);
+        OffersByBuyer::<T>::remove(&offer.buyer, offer.offer_id);
+        OffersGiven::<T>::mutate_exists(&offer.buyer, |count| {
+            *count = count
+                .map(|count| count.saturating_sub(1))
+                .filter(|count| *count > 0);
+        });
+        InvalidatedOffers::remove(offer.offer_id);
+        OfferExpiryQueue::<T>::remove(
+            offer.expiry_block.saturating_add(One::one()),
+            offer.offer_id,
+        );
+    }
+
+    /// Remove an offer without a sale and give the buyer their amount back
+    fn do_release_offer(offer: &OfferOf<T>) {
+        Self::remove_offer(offer);
+        Self::do_unreserve(offer.currency_id, &offer.buyer, offer.amount);
+    }
+
+    fn remove_auction(auction: &AuctionOf<T>) {
+        Auctions::<T>::remove(auction.id);
+        AuctionBidHistory::<T>::remove_prefix(auction.id);
+        AuctionBidCount::remove(auction.id);
+        AuctionDeposits::<T>::remove(auction.id);
+        AllAuctions::<T>::mutate(|assets| assets.retain(|asset| *asset != auction.asset));
+    }
+
+    fn remove_secret_listing(data: &SecretListingOf<T>) {
+        SecretListings::<T>::remove(data.id);
+        SecretListingsByAsset::<T>::remove(data.asset);
+        SecretListingsByClass::<T>::mutate_exists(data.asset.0, |maybe_ids| {
+            if let Some(ids) = maybe_ids {
+                ids.retain(|id| *id != data.id);
+                if ids.is_empty() {
+                    *maybe_ids = None;
+                }
+            }
+        });
+    }
+
+    fn remove_dutch_auction(auction: &DutchAuctionOf<T>) {
+        DutchAuctions::<T>::remove(auction.id);
+        AllAuctions::<T>::mutate(|assets| assets.retain(|asset| *asset != auction.asset));
+    }
+
+    /// Get the bids placed on an open auction, oldest first
+    pub fn get_auction_bid_history(
+        auction_id: AuctionId,
+    ) -> Vec<(T::AccountId, BalanceOf<T>, T::BlockNumber)> {
+        (0..Self::auction_bid_count(auction_id))
+            .filter_map(|bid_index| Self::auction_bid_history(auction_id, bid_index))
+            .collect()
+    }
+
+    /// Get the price of a dutch auction at the given block
+    pub fn get_dutch_auction_price(
+        auction: &DutchAuctionOf<T>,
+        now: T::BlockNumber,
+    ) -> BalanceOf<T> {
+        if now >= auction.end_block {
+            return auction.floor_price;
+        }
+
+        let elapsed = now
+            .saturating_sub(auction.start_block)
+            .saturated_into::<u128>();
+        let duration = auction
+            .end_block
+            .saturating_sub(auction.start_block)
+            .saturated_into::<u128>();
+        let total_drop = auction
+            .start_price
+            .saturating_sub(auction.floor_price)
+            .saturated_into::<u128>();
+        let drop = multiply_by_rational(total_drop, elapsed, duration).unwrap_or(total_drop);
+
+        auction.start_price.saturating_sub(drop.saturated_into())
+    }
+
+    fn remove_from_all_listings(asset: &(ClassIdOf<T>, TokenIdOf<T>)) -> DispatchResult {
         AllListings::<T>::try_mutate(|asset_ids| -> DispatchResult {
             let asset_index = asset_ids
                 .iter()
-                .position(|x| *x == listing_data.asset)
-                .unwrap();
+                .position(|x| x == asset)
+                .ok_or(Error::<T>::ListingNotFound)?;
             asset_ids.remove(asset_index);
 
             Ok(())
         })?;
+        ListingCount::mutate(|count| *count = count.saturating_sub(1));
+
+        Ok(())
+    }
 
-        // Remove listing from owner
-        // Get owner listing data
-        ListingsByOwner::<T>::try_mutate(
-            listing_data.clone().seller,
-            |owner_data| -> DispatchResult {
-                let data = owner_data.as_mut().ok_or(Error::<T>::ListingNotFound)?;
+    /// Remove a listing reservation and its expiry entry. Returns false if it wasn't reserved.
+    fn do_unreserve_listing(listing_id: ListingId) -> bool {
+        match ReservedListings::<T>::take(listing_id) {
+            Some((_, expiry)) => {
+                ReservationExpiries::<T>::mutate_exists(expiry, |ids| {
+                    if let Some(listing_ids) = ids {
+                        listing_ids.retain(|id| *id != listing_id);
+                        if listing_ids.is_empty() {
+                            *ids = None;
+                        }
+                    }
+                });
+                true
+            }
+            None => false,
+        }
+    }
 
-                // Remove the old listing id
-                data.retain(|&x| x != listing_data.id);
+    /// Release the listings whose reservation ends at `now`
+    fn expire_reservations(now: T::BlockNumber) -> Weight {
+        let expired = ReservationExpiries::<T>::take(now);
 
-                // Update owner listings
-                ListingsByOwner::<T>::insert(listing_data.seller, data);
+        for listing_id in expired.iter() {
+            ReservedListings::<T>::remove(listing_id);
+            Self::deposit_event(RawEvent::MarketListingUnreserved(*listing_id));
+        }
 
-                Ok(())
-            },
-        )?;
+        T::DbWeight::get().reads_writes(1, 1 + expired.len() as Weight)
+    }
 
-        Ok(true)
+    /// Release the offers whose expiry block has passed. Queued offers are
+    /// drained, so running this twice for a block releases nothing more.
+    fn expire_offers(now: T::BlockNumber, limit: u32) -> Weight {
+        let mut expired: Weight = 0;
+
+        for (offer_id, _) in OfferExpiryQueue::<T>::drain_prefix(now).take(limit as usize) {
+            expired += 1;
+            if let Some(offer) = Self::offers(offer_id) {
+                Self::do_release_offer(&offer);
+                Self::deposit_event(RawEvent::MarketOfferExpired(
+                    offer.buyer,
+                    offer_id,
+                    offer.amount,
+                ));
+            }
+        }
+
+        T::DbWeight::get().reads_writes(1 + expired, 3 * expired)
     }
 
-    fn do_create_claim(
+    /// Weight of the `expire_offers` run in on_finalize, for the offers still queued at `now`
+    fn offer_expiries_left_weight(now: T::BlockNumber) -> Weight {
+        let left = OfferExpiryQueue::<T>::iter_prefix(now).count() as Weight;
+        // Counting reads each entry once, releasing costs what `expire_offers` declares
+        T::DbWeight::get().reads_writes(1 + 2 * left, 3 * left)
+    }
+
+    /// Drop the listing counters of the previous block
+    fn clear_listing_rate_limits() -> Weight {
+        let cleared = ListingsCreatedThisBlock::<T>::drain().count() as Weight;
+        T::DbWeight::get().reads_writes(cleared, cleared)
+    }
+
+    /// Count a new listing by an account, failing once it reached
+    /// MaxListingsPerAccountPerBlock in the current block
+    fn do_count_listing_this_block(account: &T::AccountId) -> DispatchResult {
+        let key = (account.clone(), <system::Module<T>>::block_number());
+        ListingsCreatedThisBlock::<T>::try_mutate(key, |count| -> DispatchResult {
+            ensure!(
+                *count < T::MaxListingsPerAccountPerBlock::get(),
+                Error::<T>::RateLimitExceeded
+            );
+            *count = count.saturating_add(1);
+            Ok(())
+        })
+    }
+
+    /// Ensure an asset exists and the sender owns its class
+    fn ensure_class_owner_of_asset(
+        sender: &T::AccountId,
+        asset: &(ClassIdOf<T>, TokenIdOf<T>),
+    ) -> DispatchResult {
+        ensure!(
+            AssetModule::<T>::tokens(asset.0, asset.1).is_some(),
+            Error::<T>::AssetNotFound
+        );
+        let class_info = AssetModule::<T>::classes(asset.0).ok_or(Error::<T>::AssetNotFound)?;
+        ensure!(*sender == class_info.owner, Error::<T>::NoPermission);
+        Ok(())
+    }
+
+    /// Move the owner role of a class from `from` to `to`
+    fn do_transfer_class_ownership(
+        from: &T::AccountId,
+        to: &T::AccountId,
+        class_id: ClassIdOf<T>,
+    ) -> DispatchResult {
+        orml_nft::Classes::<T>::try_mutate(class_id, |class_info| -> DispatchResult {
+            let info = class_info.as_mut().ok_or(Error::<T>::AssetNotFound)?;
+
+            // Ensure that the sender is the owner of this class
+            ensure!(*from == info.owner, Error::<T>::NoPermission);
+
+            info.owner = to.clone();
+            Ok(())
+        })
+    }
+
+    /// Ensure a bought asset has been held for at least MinHoldingPeriod blocks
+    fn ensure_holding_period_met(asset: &(ClassIdOf<T>, TokenIdOf<T>)) -> DispatchResult {
+        let last_purchased_at = AssetModule::<T>::tokens(asset.0, asset.1)
+            .and_then(|token_info| token_info.data.last_purchased_at);
+        if let Some(purchased_at) = last_purchased_at {
+            ensure!(
+                <system::Module<T>>::block_number()
+                    >= purchased_at.saturating_add(T::MinHoldingPeriod::get()),
+                Error::<T>::HoldingPeriodNotMet
+            );
+        }
+        Ok(())
+    }
+
+    /// Ensure the assets of a class are past its transferable after block
+    fn ensure_class_transferable(class_id: ClassIdOf<T>) -> DispatchResult {
+        if let Some(block) = Self::class_data(class_id).transferable_after {
+            ensure!(
+                <system::Module<T>>::block_number() >= block,
+                Error::<T>::AssetNotYetTransferable
+            );
+        }
+        Ok(())
+    }
+
+    fn ensure_valid_creator_address(address: &[u8]) -> DispatchResult {
+        ensure!(
+            address.len() <= T::MaxCreatorAddressLength::get() as usize,
+            Error::<T>::CreatorAddressTooLong
+        );
+        ensure!(
+            str::from_utf8(address).is_ok(),
+            Error::<T>::InvalidCreatorAddress
+        );
+        Ok(())
+    }
+
+    /// Ensure a royalty split has at most MaxRoyaltyRecipients collaborators
+    /// and its shares never exceed the sale price
+    fn ensure_valid_royalty_split(splits: &[(T::AccountId, Perbill)]) -> DispatchResult {
+        ensure!(
+            splits.len() <= T::MaxRoyaltyRecipients::get() as usize,
+            Error::<T>::TooManyRoyaltyRecipients
+        );
+        let total = splits
+            .iter()
+            .map(|(_, share)| share.deconstruct() as u64)
+            .sum::<u64>();
+        ensure!(
+            total <= Perbill::one().deconstruct() as u64,
+            Error::<T>::InvalidRoyaltySplit
+        );
+        Ok(())
+    }
+
+    fn ensure_can_mint(
+        sender: &T::AccountId,
+        class_id: ClassIdOf<T>,
+        quantity: u32,
+    ) -> DispatchResult {
+        // Ensure that the sender is the owner of this class
+        let class_info = AssetModule::<T>::classes(class_id).ok_or(Error::<T>::AssetNotFound)?;
+        ensure!(*sender == class_info.owner, Error::<T>::NoPermission);
+
+        // Both caps count every asset ever minted, including those minted outside the market
+        let minted = Self::total_minted(class_id).saturating_add(quantity as u64);
+
+        // Limited editions can never mint past their cap
+        if let Some(cap) = Self::limited_edition_cap(class_id) {
+            ensure!(minted <= cap as u64, Error::<T>::LimitedEditionCapReached);
+        }
+
+        // Nor can classes with a max supply
+        if let Some(max_supply) = class_info.data.max_supply {
+            ensure!(minted <= max_supply as u64, Error::<T>::MaxSupplyExceeded);
+        }
+
+        Ok(())
+    }
+
+    /// Mint an asset numbered with the class's next serial number
+    fn do_mint(
         owner: &T::AccountId,
+        class_id: ClassIdOf<T>,
+        metadata: Vec<u8>,
+        mut data: T::TokenData,
+    ) -> Result<TokenIdOf<T>, DispatchError> {
+        ensure!(
+            data.game_attributes.len() <= T::MaxAttributeCount::get() as usize,
+            Error::<T>::TooManyAttributes
+        );
+        let serial_number = Self::class_minted_count(class_id) as u64;
+        data.serial_number = serial_number;
+        let class_data = Self::class_data(class_id);
+        data.game_id = class_data.game_id;
+        data.schema_version = class_data.schema_version;
+
+        let token_id = AssetModule::<T>::mint(owner, class_id, metadata, data)?;
+        ClassMintedCount::<T>::mutate(class_id, |minted| *minted = minted.saturating_add(1));
+        SerialNumberRegistry::<T>::insert(class_id, serial_number, token_id);
+        Self::on_mint(owner, (class_id, token_id))?;
+        T::Mint::on_mint(owner, (class_id, token_id))?;
+
+        Ok(token_id)
+    }
+
+    fn do_execute_claim(receiver: &T::AccountId, claim_id: ClaimId) -> DispatchResult {
+        // Ensure the claim is for this receiver
+        let data = Self::open_claims(receiver, claim_id).ok_or(Error::<T>::ClaimNotFound)?;
+        ensure!(
+            !Self::non_tradeable(data.asset.0),
+            Error::<T>::ClassNonTradeable
+        );
+        ensure!(
+            !Self::is_claim_expired(&data, <system::Module<T>>::block_number()),
+            Error::<T>::ClaimExpired
+        );
+
+        // Perform any domain related tasks to claiming
+        ensure!(
+            T::Claim::claim(receiver, data.asset).is_ok(),
+            Error::<T>::ClaimCancelled
+        );
+
+        // Claim Account
+        let claim_account: T::AccountId = Self::get_claim_account();
+
+        // Transfer asset into the reciever's account
+        Self::do_transfer(&claim_account, receiver, data.asset)?;
+
+        Self::remove_open_claim(receiver, claim_id, data.asset)?;
+
+        // Pay out any reward reserved by the claim issuer
+        if let Some((issuer, reward)) = ClaimRewards::<T>::take(claim_id) {
+            <T as Config>::Currency::repatriate_reserved(
+                &issuer,
+                receiver,
+                reward,
+                BalanceStatus::Free,
+            )?;
+            Self::deposit_event(RawEvent::MarketClaimRewardPaid(
+                receiver.clone(),
+                claim_id,
+                reward,
+            ));
+        }
+
+        Self::deposit_event(RawEvent::MarketAssetClaimed(
+            receiver.clone(),
+            data.asset.0,
+            data.asset.1,
+        ));
+
+        Ok(())
+    }
+
+    /// Return the asset of an open claim to its creator and refund any reward
+    fn do_return_claim(
+        receiver: &T::AccountId,
+        claim_id: ClaimId,
+        claim: &ClaimOf<T>,
+    ) -> DispatchResult {
+        // Return the asset from the claim account
+        let claim_account: T::AccountId = Self::get_claim_account();
+        Self::do_transfer(&claim_account, &claim.creator, claim.asset)?;
+
+        Self::remove_open_claim(receiver, claim_id, claim.asset)?;
+
+        // Refund any reward reserved for the receiver
+        if let Some((issuer, reward)) = ClaimRewards::<T>::take(claim_id) {
+            <T as Config>::Currency::unreserve(&issuer, reward);
+        }
+
+        Ok(())
+    }
+
+    fn remove_open_claim(
         receiver: &T::AccountId,
+        claim_id: ClaimId,
         asset: (ClassIdOf<T>, TokenIdOf<T>),
-    ) -> Result<bool, DispatchError> {
+    ) -> DispatchResult {
+        AllClaims::<T>::try_mutate(|asset_ids| -> DispatchResult {
+            let asset_index = asset_ids
+                .iter()
+                .position(|x| *x == asset)
+                .ok_or(Error::<T>::ClaimNotFound)?;
+            asset_ids.remove(asset_index);
+
+            Ok(())
+        })?;
+        ClaimIndex::<T>::remove(asset);
+        OpenClaims::<T>::remove(receiver, claim_id);
+        ClaimReceivers::<T>::remove(claim_id);
+        ClaimCount::mutate(|count| *count = count.saturating_sub(1));
+
+        Ok(())
+    }
+
+    fn is_claim_expired(claim: &ClaimOf<T>, now: T::BlockNumber) -> bool {
+        matches!(claim.deadline, Some(deadline) if now > deadline)
+    }
+
+    fn do_create_claim(
+        sender: &T::AccountId,
+        receiver: &T::AccountId,
+        asset: (ClassIdOf<T>, TokenIdOf<T>),
+        deadline: Option<T::BlockNumber>,
+    ) -> Result<ClaimId, DispatchError> {
+        ensure!(!Self::non_tradeable(asset.0), Error::<T>::ClassNonTradeable);
+        Self::ensure_class_transferable(asset.0)?;
+        ensure!(
+            !matches!(deadline, Some(deadline) if deadline < <system::Module<T>>::block_number()),
+            Error::<T>::InvalidClaimDeadline
+        );
+
+        // Check that the sender owns this asset or operates it for the owner
+        let owner = &Self::ensure_owner_or_operator(sender, &asset)?;
+
+        // Ensure that the owner is the owner of this class
+        let class_info = AssetModule::<T>::classes(asset.0).ok_or(Error::<T>::AssetNotFound)?;
+        ensure!(*owner == class_info.owner, Error::<T>::NoPermission);
+
+        // Ensure that the asset is not locked in Escrow, Claims or equipped
+        ensure!(!Self::is_locked(&asset), Error::<T>::AssetLocked);
+        ensure!(!Self::is_soulbound(&asset), Error::<T>::SoulboundAsset);
+        ensure!(
+            Self::claim_count() < T::MaxClaims::get(),
+            Error::<T>::NoAvailableClaimId
+        );
+
         // Get claim account
         let claim_account: T::AccountId = Self::get_claim_account();
 
         // Transfer asset into the claim account
-        Self::do_transfer(owner, &claim_account, asset).ok();
+        Self::do_transfer(owner, &claim_account, asset)?;
 
         // Create claim data
         let claim = Claim {
             receiver: receiver.clone(),
             asset,
+            creator: owner.clone(),
+            deadline,
         };
 
         // Add the new claim id to storage
@@ -632,13 +5994,38 @@ impl<T: Config> Module<T> {
 
         // Add claim to storage
         OpenClaims::<T>::insert(receiver, claim_id, claim);
-        AllClaims::<T>::append(&asset);
+        ClaimReceivers::<T>::insert(claim_id, receiver);
+        AllClaims::<T>::append(asset);
+        ClaimIndex::<T>::insert(asset, true);
+        ClaimCount::mutate(|count| *count = count.saturating_add(1));
 
-        Ok(true)
+        Ok(claim_id)
     }
 }
 
 // Implement OnTransferHandler
+impl<T: Config> frame_support::unsigned::ValidateUnsigned for Module<T> {
+    type Call = Call<T>;
+
+    fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+        if let Call::expire_listing(listing_id) = call {
+            let listing = Self::listings(listing_id).ok_or(InvalidTransaction::Stale)?;
+            if !Self::is_listing_expired(&listing, <system::Module<T>>::block_number()) {
+                return InvalidTransaction::Future.into();
+            }
+
+            ValidTransaction::with_tag_prefix("GamePowerMarketExpireListing")
+                .priority(T::UnsignedPriority::get())
+                .and_provides(listing_id)
+                .longevity(64)
+                .propagate(true)
+                .build()
+        } else {
+            InvalidTransaction::Call.into()
+        }
+    }
+}
+
 impl<T: Config> OnTransferHandler<T::AccountId, T::ClassId, T::TokenId> for Module<T> {
     fn transfer(
         from: &T::AccountId,
@@ -665,6 +6052,26 @@ impl<T: Config> OnBurnHandler<T::AccountId, T::ClassId, T::TokenId> for Module<T
     }
 }
 
+// Implement OnMintHandler
+impl<T: Config> OnMintHandler<T::AccountId, T::ClassId, T::TokenId> for Module<T> {
+    fn on_mint(owner: &T::AccountId, asset: (T::ClassId, T::TokenId)) -> DispatchResult {
+        TotalMintedByClass::<T>::mutate(asset.0, |minted| *minted = minted.saturating_add(1));
+        let now = <system::Module<T>>::block_number();
+        TokenMintedAt::<T>::insert(asset, now);
+        if !ClassFirstMintBlock::<T>::contains_key(asset.0) {
+            ClassFirstMintBlock::<T>::insert(asset.0, now);
+        }
+        let game_id = AssetModule::<T>::tokens(asset.0, asset.1)
+            .map(|token_info| token_info.data.game_id)
+            .unwrap_or_default();
+        if game_id != 0 {
+            AssetsByGame::<T>::insert(game_id, asset, ());
+        }
+        Module::<T>::deposit_event(RawEvent::MarketAssetMinted(owner.clone(), asset.0, asset.1));
+        Ok(())
+    }
+}
+
 // Implement OnClaimHandler
 impl<T: Config> OnClaimHandler<T::AccountId, T::ClassId, T::TokenId> for Module<T> {
     fn claim(_owner: &T::AccountId, _asset: (T::ClassId, T::TokenId)) -> DispatchResult {
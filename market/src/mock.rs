@@ -3,11 +3,12 @@
 use super::*;
 
 use crate as gamepower_market;
-use balances;
 use frame_support::{
     parameter_types,
 };
 use frame_system as system;
+use frame_system::EnsureRoot;
+use gamepower_primitives::{AssetData, ClassData};
 use sp_core::H256;
 use sp_runtime::ModuleId;
 use sp_runtime::{
@@ -28,7 +29,7 @@ frame_support::construct_runtime!(
       System: frame_system::{Module, Call, Config, Storage, Event<T>},
       Balances: balances::{Module, Call, Storage, Config<T>, Event<T>},
       OrmlNFT: orml_nft::{Module ,Storage},
-      GamePowerMarket: gamepower_market::{Module, Call, Storage, Event<T>},
+      GamePowerMarket: gamepower_market::{Module, Call, Storage, Config, Event<T>, ValidateUnsigned},
     }
 );
 
@@ -89,6 +90,140 @@ parameter_types! {
     pub AllowEscrow: bool = true;
     pub AllowClaim: bool = true;
     pub const MarketModuleId: ModuleId = ModuleId(*b"gpmarket");
+    pub const MaxBatchSize: u32 = 3;
+    pub const MaxMessageLength: u32 = 16;
+    pub const MaxMemoLength: u32 = 16;
+    pub const MaxFeeSplitRecipients: u32 = 2;
+    pub const MaxRoyaltyRecipients: u32 = 2;
+    pub const MaxPriceHistoryDepth: u32 = 3;
+    pub const StatsEmitInterval: u64 = 10;
+    pub const BlocksPerDay: u64 = 100;
+    pub const MaxAirdropRecipients: u32 = 3;
+    pub const EscrowWarningThreshold: u64 = 1000;
+    pub const EscrowAlertCooldown: u64 = 10;
+    pub const MaxCreatorAddressLength: u32 = 16;
+    pub const MaxTagsPerToken: u32 = 2;
+    pub const MaxTagLength: u32 = 8;
+    pub const MaxAttributeCount: u32 = 2;
+    pub const MaxBidsPerAuction: u32 = 3;
+    pub const AuctionDeposit: u64 = 1000;
+    pub const EarlyUnlistPenalty: Permill = Permill::from_percent(10);
+    pub const SwapTimeoutBlocks: u64 = 10;
+    pub const MaxEmotesPerAccountPerAsset: u32 = 3;
+    pub const TransferCooldownPeriod: u64 = 5;
+    pub const UnsignedPriority: u64 = 1 << 20;
+    pub const FeeRecipient: AccountId = 5;
+    pub const NativeCurrencyId: u32 = NATIVE_CURRENCY_ID;
+    pub AcceptedCurrencies: Vec<u32> = vec![OTHER_CURRENCY_ID];
+}
+
+parameter_types! {
+    pub static MarketplaceFeeRate: Perbill = Perbill::zero();
+    pub static ListHookCalls: u32 = 0;
+    pub static BuyHookCalls: u32 = 0;
+    pub static RejectMint: bool = false;
+    pub static RejectUnlist: bool = false;
+    pub static MinListingPrice: u64 = 0;
+    pub static MaxClaims: u64 = 100;
+    pub static MinHoldingPeriod: u64 = 0;
+    pub static MaxListingsPerAccountPerBlock: u32 = 100;
+    pub static MaxOfferExpiriesOnInitialize: u32 = 100;
+    pub static TokenBalances: BTreeMap<(u32, AccountId), u64> = BTreeMap::new();
+    pub static ReservedTokenBalances: BTreeMap<(u32, AccountId), u64> = BTreeMap::new();
+}
+
+pub struct MockCurrencies;
+
+impl MultiCurrency<AccountId, u32, u64> for MockCurrencies {
+    fn transfer(currency_id: u32, from: &AccountId, to: &AccountId, amount: u64) -> DispatchResult {
+        let mut balances = TokenBalances::get();
+        let from_balance = balances.get(&(currency_id, *from)).copied().unwrap_or_default();
+        let remaining = from_balance
+            .checked_sub(amount)
+            .ok_or(DispatchError::Other("insufficient token balance"))?;
+        balances.insert((currency_id, *from), remaining);
+        *balances.entry((currency_id, *to)).or_default() += amount;
+        TokenBalances::set(balances);
+        Ok(())
+    }
+
+    fn reserve(currency_id: u32, who: &AccountId, amount: u64) -> DispatchResult {
+        let free = token_balance(currency_id, *who)
+            .checked_sub(amount)
+            .ok_or(DispatchError::Other("insufficient token balance"))?;
+        set_token_balance(currency_id, *who, free);
+        let mut reserved = ReservedTokenBalances::get();
+        *reserved.entry((currency_id, *who)).or_default() += amount;
+        ReservedTokenBalances::set(reserved);
+        Ok(())
+    }
+
+    fn unreserve(currency_id: u32, who: &AccountId, amount: u64) -> u64 {
+        let mut reserved = ReservedTokenBalances::get();
+        let held = reserved.entry((currency_id, *who)).or_default();
+        let actual = amount.min(*held);
+        *held -= actual;
+        ReservedTokenBalances::set(reserved);
+        set_token_balance(currency_id, *who, token_balance(currency_id, *who) + actual);
+        amount - actual
+    }
+}
+
+pub fn token_balance(currency_id: u32, who: AccountId) -> u64 {
+    TokenBalances::get().get(&(currency_id, who)).copied().unwrap_or_default()
+}
+
+pub fn reserved_token_balance(currency_id: u32, who: AccountId) -> u64 {
+    ReservedTokenBalances::get().get(&(currency_id, who)).copied().unwrap_or_default()
+}
+
+pub fn set_token_balance(currency_id: u32, who: AccountId, amount: u64) {
+    let mut balances = TokenBalances::get();
+    balances.insert((currency_id, who), amount);
+    TokenBalances::set(balances);
+}
+
+pub struct MarketHooks;
+
+impl OnListHandler<AccountId, u32, u64, u64> for MarketHooks {
+    fn on_list(_seller: &AccountId, _asset: (u32, u64), _price: u64) -> DispatchResult {
+        ListHookCalls::set(ListHookCalls::get() + 1);
+        Ok(())
+    }
+}
+
+impl OnUnlistHandler<AccountId, u32, u64> for MarketHooks {
+    fn on_unlist(_seller: &AccountId, _asset: (u32, u64), _was_sold: bool) -> DispatchResult {
+        ensure!(!RejectUnlist::get(), DispatchError::Other("unlist rejected"));
+        Ok(())
+    }
+}
+
+impl OnMintHandler<AccountId, u32, u64> for MarketHooks {
+    fn on_mint(_owner: &AccountId, _asset: (u32, u64)) -> DispatchResult {
+        ensure!(!RejectMint::get(), DispatchError::Other("mint rejected"));
+        Ok(())
+    }
+}
+
+impl OnBuyHandler<AccountId, u32, u64, u64> for MarketHooks {
+    fn on_buy(
+        _seller: &AccountId,
+        _buyer: &AccountId,
+        _asset: (u32, u64),
+        _price: u64,
+    ) -> DispatchResult {
+        BuyHookCalls::set(BuyHookCalls::get() + 1);
+        Ok(())
+    }
+}
+
+impl<C> system::offchain::SendTransactionTypes<C> for Test
+where
+    Call: From<C>,
+{
+    type OverarchingCall = Call;
+    type Extrinsic = UncheckedExtrinsic;
 }
 
 impl gamepower_market::Config for Test {
@@ -96,19 +231,62 @@ impl gamepower_market::Config for Test {
     type Transfer = GamePowerMarket;
     type Burn = GamePowerMarket;
     type Claim = GamePowerMarket;
+    type Mint = MarketHooks;
+    type List = MarketHooks;
+    type Unlist = MarketHooks;
+    type Buy = MarketHooks;
     type AllowTransfer = AllowTransfer;
     type AllowBurn = AllowBurn;
     type AllowEscrow = AllowEscrow;
     type AllowClaim = AllowClaim;
     type Currency = Balances;
+    type CurrencyId = u32;
+    type MultiCurrency = MockCurrencies;
+    type NativeCurrencyId = NativeCurrencyId;
+    type AcceptedCurrencies = AcceptedCurrencies;
     type ModuleId = MarketModuleId;
+    type MaxBatchSize = MaxBatchSize;
+    type MaxMessageLength = MaxMessageLength;
+    type MaxMemoLength = MaxMemoLength;
+    type MaxFeeSplitRecipients = MaxFeeSplitRecipients;
+    type MaxRoyaltyRecipients = MaxRoyaltyRecipients;
+    type MaxPriceHistoryDepth = MaxPriceHistoryDepth;
+    type StatsEmitInterval = StatsEmitInterval;
+    type BlocksPerDay = BlocksPerDay;
+    type MaxAirdropRecipients = MaxAirdropRecipients;
+    type MaxClaims = MaxClaims;
+    type EscrowWarningThreshold = EscrowWarningThreshold;
+    type MinListingPrice = MinListingPrice;
+    type EscrowAlertCooldown = EscrowAlertCooldown;
+    type MaxCreatorAddressLength = MaxCreatorAddressLength;
+    type MaxTagsPerToken = MaxTagsPerToken;
+    type MaxTagLength = MaxTagLength;
+    type MaxAttributeCount = MaxAttributeCount;
+    type MaxBidsPerAuction = MaxBidsPerAuction;
+    type AuctionDeposit = AuctionDeposit;
+    type EarlyUnlistPenalty = EarlyUnlistPenalty;
+    type MaxEmotesPerAccountPerAsset = MaxEmotesPerAccountPerAsset;
+    type TransferCooldownPeriod = TransferCooldownPeriod;
+    type MinHoldingPeriod = MinHoldingPeriod;
+    type MaxListingsPerAccountPerBlock = MaxListingsPerAccountPerBlock;
+    type MaxOfferExpiriesOnInitialize = MaxOfferExpiriesOnInitialize;
+    type UnsignedPriority = UnsignedPriority;
+    type MarketplaceFeeRate = MarketplaceFeeRate;
+    type FeeRecipient = FeeRecipient;
+    type GovernanceOrigin = EnsureRoot<AccountId>;
+    type VerificationOrigin = EnsureRoot<AccountId>;
+    type ForceOrigin = EnsureRoot<AccountId>;
+    type PauseOrigin = EnsureRoot<AccountId>;
+    type SwapTimeoutBlocks = SwapTimeoutBlocks;
+    type ArbitrationOrigin = EnsureRoot<AccountId>;
+    type WeightInfo = ();
 }
 
 impl orml_nft::Config for Test {
     type ClassId = u32;
     type TokenId = u64;
-    type ClassData = ();
-    type TokenData = ();
+    type ClassData = ClassData<AccountId, u64>;
+    type TokenData = AssetData<u32, u64, u64>;
 }
 
 pub const ALICE: AccountId = 1;
@@ -119,6 +297,8 @@ pub const TOKEN_ID: <Test as orml_nft::Config>::TokenId = 0;
 pub const TOKEN_ID_NOT_EXIST: <Test as orml_nft::Config>::TokenId = 1;
 pub const LISTING_ID: u64 = 0;
 pub const LISTING_ID_NOT_EXIST: u64 = 1;
+pub const NATIVE_CURRENCY_ID: u32 = 0;
+pub const OTHER_CURRENCY_ID: u32 = 1;
 
 /// Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
@@ -133,10 +313,20 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
             (3, 1000000),
             (4, 1000000),
             (5, 1000000),
+            (MarketModuleId::get().into_account(), 2000),
         ],
     }
     .assimilate_storage(&mut t)
     .unwrap();
+    gamepower_market::GenesisConfig {
+        pallet_metadata: Some(MarketPalletInfo {
+            name: b"gamepower-market".to_vec(),
+            version: 1,
+            description: b"NFT wallet and marketplace".to_vec(),
+        }),
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
     let mut ext: sp_io::TestExternalities = t.into();
     ext.execute_with(|| System::set_block_number(1));
     ext
@@ -0,0 +1,228 @@
+#![cfg(test)]
+
+use super::*;
+
+use crate as gamepower_market;
+use frame_support::parameter_types;
+use frame_system as system;
+use frame_system::EnsureRoot;
+use gamepower_primitives::{AssetData, ClassData};
+use sp_core::H256;
+use sp_runtime::ModuleId;
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, IdentityLookup},
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime with u128 asset ids and accounts to test the pallet.
+// u128 accounts are wide enough to tell the escrow sub-accounts apart.
+frame_support::construct_runtime!(
+    pub enum Test where
+      Block = Block,
+      NodeBlock = Block,
+      UncheckedExtrinsic = UncheckedExtrinsic,
+    {
+      System: frame_system::{Module, Call, Config, Storage, Event<T>},
+      Balances: balances::{Module, Call, Storage, Config<T>, Event<T>},
+      OrmlNFT: orml_nft::{Module ,Storage},
+      GamePowerMarket: gamepower_market::{Module, Call, Storage, Event<T>, ValidateUnsigned},
+    }
+);
+
+parameter_types! {
+  pub const BlockHashCount: u64 = 250;
+}
+
+/*
+impl_outer_origin! {
+  pub enum Origin for Test {}
+}
+*/
+
+pub type AccountId = u128;
+
+impl system::Config for Test {
+    type BaseCallFilter = ();
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type Origin = Origin;
+    type Call = Call;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = AccountId;
+    type AccountData = balances::AccountData<u64>;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type Event = Event;
+    type BlockHashCount = BlockHashCount;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+}
+
+parameter_types! {
+  pub const ExistentialDeposit: u64 = 500;
+  pub const MaxLocks: u32 = 50;
+}
+impl balances::Config for Test {
+    type MaxLocks = ();
+    type Balance = u64;
+    type Event = Event;
+    type DustRemoval = ();
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = System;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+      pub AllowTransfer: bool = true;
+    pub AllowBurn: bool = true;
+    pub AllowEscrow: bool = true;
+    pub AllowClaim: bool = true;
+    pub const MarketModuleId: ModuleId = ModuleId(*b"gpmarket");
+    pub const MaxBatchSize: u32 = 3;
+    pub const MaxMessageLength: u32 = 16;
+    pub const MaxMemoLength: u32 = 16;
+    pub const MaxFeeSplitRecipients: u32 = 2;
+    pub const MaxRoyaltyRecipients: u32 = 2;
+    pub const MaxPriceHistoryDepth: u32 = 3;
+    pub const StatsEmitInterval: u64 = 10;
+    pub const BlocksPerDay: u64 = 100;
+    pub const MaxAirdropRecipients: u32 = 3;
+    pub const MaxClaims: u64 = 100;
+    pub const EscrowWarningThreshold: u64 = 1000;
+    pub const MinListingPrice: u64 = 0;
+    pub const EscrowAlertCooldown: u64 = 10;
+    pub const MaxCreatorAddressLength: u32 = 16;
+    pub const MaxTagsPerToken: u32 = 2;
+    pub const MaxTagLength: u32 = 8;
+    pub const MaxAttributeCount: u32 = 2;
+    pub const MaxBidsPerAuction: u32 = 3;
+    pub const AuctionDeposit: u64 = 1000;
+    pub const EarlyUnlistPenalty: Permill = Permill::from_percent(10);
+    pub const SwapTimeoutBlocks: u64 = 10;
+    pub const MaxEmotesPerAccountPerAsset: u32 = 3;
+    pub const TransferCooldownPeriod: u64 = 5;
+    pub const MinHoldingPeriod: u64 = 0;
+    pub const MaxListingsPerAccountPerBlock: u32 = 100;
+    pub const MaxOfferExpiriesOnInitialize: u32 = 100;
+    pub const UnsignedPriority: u64 = 1 << 20;
+    pub const FeeRecipient: AccountId = 5;
+}
+
+parameter_types! {
+    pub static MarketplaceFeeRate: Perbill = Perbill::zero();
+}
+
+impl<C> system::offchain::SendTransactionTypes<C> for Test
+where
+    Call: From<C>,
+{
+    type OverarchingCall = Call;
+    type Extrinsic = UncheckedExtrinsic;
+}
+
+impl gamepower_market::Config for Test {
+    type Event = Event;
+    type Transfer = GamePowerMarket;
+    type Burn = GamePowerMarket;
+    type Claim = GamePowerMarket;
+    type Mint = ();
+    type List = ();
+    type Unlist = ();
+    type Buy = ();
+    type AllowTransfer = AllowTransfer;
+    type AllowBurn = AllowBurn;
+    type AllowEscrow = AllowEscrow;
+    type AllowClaim = AllowClaim;
+    type Currency = Balances;
+    type CurrencyId = u32;
+    type MultiCurrency = ();
+    type NativeCurrencyId = ();
+    type AcceptedCurrencies = ();
+    type ModuleId = MarketModuleId;
+    type MaxBatchSize = MaxBatchSize;
+    type MaxMessageLength = MaxMessageLength;
+    type MaxMemoLength = MaxMemoLength;
+    type MaxFeeSplitRecipients = MaxFeeSplitRecipients;
+    type MaxRoyaltyRecipients = MaxRoyaltyRecipients;
+    type MaxPriceHistoryDepth = MaxPriceHistoryDepth;
+    type StatsEmitInterval = StatsEmitInterval;
+    type BlocksPerDay = BlocksPerDay;
+    type MaxAirdropRecipients = MaxAirdropRecipients;
+    type MaxClaims = MaxClaims;
+    type EscrowWarningThreshold = EscrowWarningThreshold;
+    type MinListingPrice = MinListingPrice;
+    type EscrowAlertCooldown = EscrowAlertCooldown;
+    type MaxCreatorAddressLength = MaxCreatorAddressLength;
+    type MaxTagsPerToken = MaxTagsPerToken;
+    type MaxTagLength = MaxTagLength;
+    type MaxAttributeCount = MaxAttributeCount;
+    type MaxBidsPerAuction = MaxBidsPerAuction;
+    type AuctionDeposit = AuctionDeposit;
+    type EarlyUnlistPenalty = EarlyUnlistPenalty;
+    type MaxEmotesPerAccountPerAsset = MaxEmotesPerAccountPerAsset;
+    type TransferCooldownPeriod = TransferCooldownPeriod;
+    type MinHoldingPeriod = MinHoldingPeriod;
+    type MaxListingsPerAccountPerBlock = MaxListingsPerAccountPerBlock;
+    type MaxOfferExpiriesOnInitialize = MaxOfferExpiriesOnInitialize;
+    type UnsignedPriority = UnsignedPriority;
+    type MarketplaceFeeRate = MarketplaceFeeRate;
+    type FeeRecipient = FeeRecipient;
+    type GovernanceOrigin = EnsureRoot<AccountId>;
+    type VerificationOrigin = EnsureRoot<AccountId>;
+    type ForceOrigin = EnsureRoot<AccountId>;
+    type PauseOrigin = EnsureRoot<AccountId>;
+    type SwapTimeoutBlocks = SwapTimeoutBlocks;
+    type ArbitrationOrigin = EnsureRoot<AccountId>;
+    type WeightInfo = ();
+}
+
+impl orml_nft::Config for Test {
+    type ClassId = u128;
+    type TokenId = u128;
+    type ClassData = ClassData<AccountId, u64>;
+    type TokenData = AssetData<u128, u128, u64>;
+}
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+// Ids above u64::MAX catch any narrowing cast in the pallet
+pub const CLASS_ID: <Test as orml_nft::Config>::ClassId = u64::MAX as u128 + 1;
+pub const TOKEN_ID: <Test as orml_nft::Config>::TokenId = u64::MAX as u128 + 1;
+
+/// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut t = system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .unwrap();
+    balances::GenesisConfig::<Test> {
+        // Provide some initial balances
+        balances: vec![
+            (1, 1000000),
+            (2, 1000000),
+            (3, 1000000),
+            (4, 1000000),
+            (5, 1000000),
+            (MarketModuleId::get().into_account(), 2000),
+        ],
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+    let mut ext: sp_io::TestExternalities = t.into();
+    ext.execute_with(|| {
+        System::set_block_number(1);
+        orml_nft::NextClassId::<Test>::put(CLASS_ID);
+        orml_nft::NextTokenId::<Test>::insert(CLASS_ID, TOKEN_ID);
+    });
+    ext
+}
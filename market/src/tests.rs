@@ -1,13 +1,38 @@
 use crate::mock::*;
-use crate::Error;
-use frame_support::{assert_noop, assert_ok};
+use crate::{
+    AllClaims, AssetsByGame, ClassLastSaleBlock, ClassLastSalePrice, ClassSalesCount,
+    ClassTotalVolume, DisputeStatus, Error, InvalidatedOffers, ListingsCreatedThisBlock,
+    OfferExpiryQueue, OffersByAsset, Order, RawEvent, Releases, TokenPriceHistory,
+    TotalMintedByClass, VerificationStatus, WeightInfo,
+};
+use codec::Decode;
+use frame_support::{
+    assert_noop, assert_ok,
+    traits::{
+        Currency, Get, OffchainWorker, OnFinalize, OnInitialize, OnRuntimeUpgrade,
+        ReservableCurrency,
+    },
+    unsigned::ValidateUnsigned,
+    weights::RuntimeDbWeight,
+    IterableStorageDoubleMap, StorageDoubleMap, StorageMap, StorageValue,
+};
+use gamepower_primitives::{
+    AssetData, ClassData, ClassListingStats, ClassMarketData, GameInfo, ListingId, LockReason,
+    MarketPalletInfo,
+};
+use gamepower_traits::OnMintHandler;
+use sp_core::offchain::{testing::TestTransactionPoolExt, TransactionPoolExt};
+use sp_runtime::{
+    transaction_validity::{InvalidTransaction, TransactionSource},
+    DispatchError, Perbill,
+};
 
 #[test]
 fn transfer_should_work() {
     new_test_ext().execute_with(|| {
         // Create NFT
-        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], ()));
-        assert_ok!(OrmlNFT::mint(&BOB, CLASS_ID, vec![1], ()));
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&BOB, CLASS_ID, vec![1], Default::default()));
 
         // Make a valid transfer
         assert_ok!(GamePowerMarket::transfer(
@@ -23,8 +48,8 @@ fn transfer_should_work() {
 fn transfer_should_fail() {
     new_test_ext().execute_with(|| {
         // Create NFT
-        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], ()));
-        assert_ok!(OrmlNFT::mint(&BOB, CLASS_ID, vec![1], ()));
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&BOB, CLASS_ID, vec![1], Default::default()));
 
         // Try to transfer a token for a class that doesn't exist
         assert_noop!(
@@ -42,8 +67,8 @@ fn transfer_should_fail() {
 fn burn_should_work() {
     new_test_ext().execute_with(|| {
         // Create NFT
-        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], ()));
-        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], ()));
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
 
         // Make a valid burn
         assert_ok!(GamePowerMarket::burn(
@@ -57,8 +82,8 @@ fn burn_should_work() {
 fn burn_should_fail() {
     new_test_ext().execute_with(|| {
         // Create NFT
-        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], ()));
-        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], ()));
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
 
         // Try to burn a token for a class that doesn't exist
         assert_noop!(
@@ -72,14 +97,16 @@ fn burn_should_fail() {
 fn create_listing_should_work() {
     new_test_ext().execute_with(|| {
         // Create NFT
-        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], ()));
-        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], ()));
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
 
         // Create a valid listing
         assert_ok!(GamePowerMarket::list(
             Origin::signed(1),
             (CLASS_ID, TOKEN_ID),
-            100
+            100,
+            None,
+            NATIVE_CURRENCY_ID
         ));
 
         assert_eq!(
@@ -93,15 +120,20 @@ fn create_listing_should_work() {
             "Listing not added to all"
         );
         assert_eq!(
-            GamePowerMarket::listings_by_owner(1),
-            Some(vec![0]),
+            GamePowerMarket::listings_by_owner(&1),
+            vec![0],
             "Listing by owner not added"
         );
         assert_eq!(
-            GamePowerMarket::listings(0).is_some(),
-            true,
-            "Listing not added"
+            GamePowerMarket::listings_by_asset((CLASS_ID, TOKEN_ID)),
+            Some(0),
+            "Listing by asset not added"
+        );
+        assert_eq!(
+            GamePowerMarket::get_listing_by_asset(CLASS_ID, TOKEN_ID),
+            GamePowerMarket::listings(0)
         );
+        assert!(GamePowerMarket::listings(0).is_some(), "Listing not added");
     });
 }
 
@@ -109,12 +141,18 @@ fn create_listing_should_work() {
 fn create_listing_should_fail() {
     new_test_ext().execute_with(|| {
         // Create NFT
-        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], ()));
-        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], ()));
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
 
         // Try to create a listing for a class that doesn't exist
         assert_noop!(
-            GamePowerMarket::list(Origin::signed(1), (CLASS_ID_NOT_EXIST, TOKEN_ID), 100),
+            GamePowerMarket::list(
+                Origin::signed(1),
+                (CLASS_ID_NOT_EXIST, TOKEN_ID),
+                100,
+                None,
+                NATIVE_CURRENCY_ID
+            ),
             Error::<Test>::NoPermission
         );
 
@@ -130,14 +168,16 @@ fn create_listing_should_fail() {
 fn unlisting_should_work() {
     new_test_ext().execute_with(|| {
         // Create NFT
-        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], ()));
-        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], ()));
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
 
         // Make a valid listing
         assert_ok!(GamePowerMarket::list(
             Origin::signed(1),
             (CLASS_ID, TOKEN_ID),
-            100
+            100,
+            None,
+            NATIVE_CURRENCY_ID
         ));
 
         // Properly unlist
@@ -147,11 +187,15 @@ fn unlisting_should_work() {
             0,
             "Listing not removed from all"
         );
-        assert_eq!(
-            GamePowerMarket::listings_by_owner(1),
-            Some(vec![]),
+        assert!(
+            GamePowerMarket::listings_by_owner(&1).is_empty(),
             "Listing by owner not removed"
         );
+        assert_eq!(
+            GamePowerMarket::listings_by_asset((CLASS_ID, TOKEN_ID)),
+            None,
+            "Listing by asset not removed"
+        );
         assert_eq!(GamePowerMarket::listings(0), None, "Listing not removed");
     });
 }
@@ -160,14 +204,16 @@ fn unlisting_should_work() {
 fn unlisting_should_fail() {
     new_test_ext().execute_with(|| {
         // Create NFT
-        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], ()));
-        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], ()));
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
 
         // Make a valid listing
         assert_ok!(GamePowerMarket::list(
             Origin::signed(1),
             (CLASS_ID, TOKEN_ID),
-            100
+            100,
+            None,
+            NATIVE_CURRENCY_ID
         ));
 
         // Try to unlist a listing that doesn't belong to the original signer
@@ -182,30 +228,77 @@ fn unlisting_should_fail() {
             "The number of all listings is incorrect"
         );
         assert_eq!(
-            GamePowerMarket::listings_by_owner(1),
-            Some(vec![0]),
+            GamePowerMarket::listings_by_owner(&1),
+            vec![0],
             "Listing by owner should have a value"
         );
-        assert_eq!(
+        assert!(
             GamePowerMarket::listings(0).is_some(),
-            true,
             "Listing should not be removed"
         );
     });
 }
 
+#[test]
+fn runtime_upgrade_should_keep_genesis_data() {
+    new_test_ext().execute_with(|| {
+        // A new chain starts at the latest release
+        assert_eq!(GamePowerMarket::storage_version(), Releases::V15_0_0);
+
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        for _ in 0..3 {
+            assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        }
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, 0),
+            1000,
+            Some(50),
+            OTHER_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::create_claim(
+            Origin::signed(ALICE),
+            BOB,
+            (CLASS_ID, 1),
+            Some(50)
+        ));
+        set_token_balance(OTHER_CURRENCY_ID, BOB, 1000);
+        assert_ok!(GamePowerMarket::make_offer(
+            Origin::signed(BOB),
+            (CLASS_ID, 2),
+            500,
+            50,
+            OTHER_CURRENCY_ID
+        ));
+
+        let listing = GamePowerMarket::listings(LISTING_ID);
+        let claim = GamePowerMarket::open_claims(BOB, 0);
+        let offer = GamePowerMarket::offers(0);
+        assert!(listing.is_some() && claim.is_some() && offer.is_some());
+
+        // No migration runs over data already in the current layout
+        GamePowerMarket::on_runtime_upgrade();
+        assert_eq!(GamePowerMarket::storage_version(), Releases::V15_0_0);
+        assert_eq!(GamePowerMarket::listings(LISTING_ID), listing);
+        assert_eq!(GamePowerMarket::listings_by_owner(&ALICE), vec![LISTING_ID]);
+        assert_eq!(GamePowerMarket::open_claims(BOB, 0), claim);
+        assert_eq!(GamePowerMarket::offers(0), offer);
+    });
+}
+
 #[test]
 fn create_claim_should_work() {
     new_test_ext().execute_with(|| {
         // Create NFT
-        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], ()));
-        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], ()));
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
 
         // Create a valid claim
         assert_ok!(GamePowerMarket::create_claim(
             Origin::signed(1),
             BOB,
-            (CLASS_ID, TOKEN_ID)
+            (CLASS_ID, TOKEN_ID),
+            None
         ));
 
         assert_eq!(
@@ -218,9 +311,8 @@ fn create_claim_should_work() {
             1,
             "Claim not added to all"
         );
-        assert_eq!(
+        assert!(
             GamePowerMarket::open_claims(BOB, 0).is_some(),
-            true,
             "Claim not added"
         );
     });
@@ -230,12 +322,12 @@ fn create_claim_should_work() {
 fn create_claim_should_fail() {
     new_test_ext().execute_with(|| {
         // Create NFT
-        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], ()));
-        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], ()));
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
 
         // Try to create a claim for a token you don't own
         assert_noop!(
-            GamePowerMarket::create_claim(Origin::signed(2), BOB, (CLASS_ID, TOKEN_ID)),
+            GamePowerMarket::create_claim(Origin::signed(2), BOB, (CLASS_ID, TOKEN_ID), None),
             Error::<Test>::NoPermission
         );
 
@@ -249,9 +341,8 @@ fn create_claim_should_fail() {
             0,
             "Claim not added to all"
         );
-        assert_eq!(
-            GamePowerMarket::open_claims(BOB, 0).is_some(),
-            false,
+        assert!(
+            GamePowerMarket::open_claims(BOB, 0).is_none(),
             "Claim should not be added"
         );
     });
@@ -261,8 +352,8 @@ fn create_claim_should_fail() {
 fn buy_should_work() {
     new_test_ext().execute_with(|| {
         // Create NFT
-        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], ()));
-        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], ()));
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
 
         // There should be no listing
         assert_eq!(GamePowerMarket::listings(0), None, "Listing was not empty");
@@ -271,7 +362,9 @@ fn buy_should_work() {
         assert_ok!(GamePowerMarket::list(
             Origin::signed(1),
             (CLASS_ID, TOKEN_ID),
-            100
+            100,
+            None,
+            NATIVE_CURRENCY_ID
         ));
 
         assert_eq!(
@@ -286,11 +379,15 @@ fn buy_should_work() {
             0,
             "Listing not removed from all!"
         );
-        assert_eq!(
-            GamePowerMarket::listings_by_owner(1),
-            Some(vec![]),
+        assert!(
+            GamePowerMarket::listings_by_owner(&1).is_empty(),
             "Listing by owner not removed"
         );
+        assert_eq!(
+            GamePowerMarket::get_listing_by_asset(CLASS_ID, TOKEN_ID),
+            None,
+            "Listing by asset not removed"
+        );
         assert_eq!(GamePowerMarket::listings(0), None, "Listing not removed");
 
         // Check Balances
@@ -303,14 +400,16 @@ fn buy_should_work() {
 fn buy_should_fail() {
     new_test_ext().execute_with(|| {
         // Create NFT
-        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], ()));
-        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], ()));
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
 
         // Create a valid listing
         assert_ok!(GamePowerMarket::list(
             Origin::signed(1),
             (CLASS_ID, TOKEN_ID),
-            100
+            100,
+            None,
+            NATIVE_CURRENCY_ID
         ));
 
         // Try to buy a listing not being sold
@@ -325,13 +424,12 @@ fn buy_should_fail() {
             "Listing should not be removed from all!"
         );
         assert_eq!(
-            GamePowerMarket::listings_by_owner(ALICE),
-            Some(vec![0]),
+            GamePowerMarket::listings_by_owner(&ALICE),
+            vec![0],
             "Listing by owner should not be removed"
         );
-        assert_eq!(
+        assert!(
             GamePowerMarket::listings(0).is_some(),
-            true,
             "Listing should not be removed"
         );
 
@@ -345,8 +443,8 @@ fn buy_should_fail() {
 fn emote_should_work() {
     new_test_ext().execute_with(|| {
         // Create NFT
-        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], ()));
-        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], ()));
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
 
         // Post a valid emote
         assert_ok!(GamePowerMarket::emote(
@@ -363,12 +461,43 @@ fn emote_should_work() {
     });
 }
 
+#[test]
+fn has_emoted_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        let asset = (CLASS_ID, TOKEN_ID);
+
+        // No emotes yet
+        assert!(!GamePowerMarket::has_emoted(asset, &BOB, b"fish"));
+        assert!(GamePowerMarket::user_emotes_for_asset(asset, &BOB).is_empty());
+
+        assert_ok!(GamePowerMarket::emote(
+            Origin::signed(BOB),
+            asset,
+            b"fish".to_vec()
+        ));
+
+        // Matches by name or emoji, only for that emote and account
+        let fish = emojis::lookup("fish").unwrap().as_str().as_bytes().to_vec();
+        assert!(GamePowerMarket::has_emoted(asset, &BOB, b"fish"));
+        assert!(GamePowerMarket::has_emoted(asset, &BOB, &fish));
+        assert!(!GamePowerMarket::has_emoted(asset, &BOB, b"smile"));
+        assert!(!GamePowerMarket::has_emoted(asset, &ALICE, b"fish"));
+        assert_eq!(
+            GamePowerMarket::user_emotes_for_asset(asset, &BOB),
+            vec![fish]
+        );
+    });
+}
+
 #[test]
 fn emote_should_fail() {
     new_test_ext().execute_with(|| {
         // Create NFT
-        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], ()));
-        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], ()));
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
 
         // Post an invalid emote for a class that doesn't exist
         assert_noop!(
@@ -392,8 +521,8 @@ fn emote_should_fail() {
 fn emote_should_fail_for_invalid_token() {
     new_test_ext().execute_with(|| {
         // Create NFT
-        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], ()));
-        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], ()));
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
 
         // Post an invalid emote for a class that doesn't exist
         assert_noop!(
@@ -411,14 +540,15 @@ fn emote_should_fail_for_invalid_token() {
 fn locked_asset_should_fail() {
     new_test_ext().execute_with(|| {
         // Create NFT
-        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], ()));
-        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], ()));
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
 
         // Make a valid claim
         assert_ok!(GamePowerMarket::create_claim(
             Origin::signed(1),
             BOB,
-            (CLASS_ID, TOKEN_ID)
+            (CLASS_ID, TOKEN_ID),
+            None
         ));
 
         // All calls that require an unlocked token should give no permission error
@@ -431,8 +561,7286 @@ fn locked_asset_should_fail() {
             Error::<Test>::NoPermission
         );
         assert_noop!(
-            GamePowerMarket::list(Origin::signed(1), (CLASS_ID, TOKEN_ID), 100),
+            GamePowerMarket::list(
+                Origin::signed(1),
+                (CLASS_ID, TOKEN_ID),
+                100,
+                None,
+                NATIVE_CURRENCY_ID
+            ),
+            Error::<Test>::NoPermission
+        );
+    });
+}
+
+#[test]
+fn approved_spender_can_transfer_once() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        // Approve Bob to move the asset
+        assert_ok!(GamePowerMarket::approve_token(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            BOB
+        ));
+        assert_eq!(
+            GamePowerMarket::token_approvals((CLASS_ID, TOKEN_ID)),
+            Some(BOB)
+        );
+
+        // Bob moves the asset to himself
+        assert_ok!(GamePowerMarket::transfer(
+            Origin::signed(BOB),
+            BOB,
+            (CLASS_ID, TOKEN_ID)
+        ));
+        assert!(OrmlNFT::is_owner(&BOB, (CLASS_ID, TOKEN_ID)));
+        assert_eq!(
+            GamePowerMarket::token_approvals((CLASS_ID, TOKEN_ID)),
+            None,
+            "Approval should be consumed"
+        );
+    });
+}
+
+#[test]
+fn approval_cannot_be_used_twice() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        assert_ok!(GamePowerMarket::approve_token(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            BOB
+        ));
+
+        // Bob sends the asset to a third account
+        assert_ok!(GamePowerMarket::transfer(
+            Origin::signed(BOB),
+            3,
+            (CLASS_ID, TOKEN_ID)
+        ));
+        assert!(OrmlNFT::is_owner(&3, (CLASS_ID, TOKEN_ID)));
+
+        // The approval is gone so Bob cannot move it again
+        assert_noop!(
+            GamePowerMarket::transfer(Origin::signed(BOB), BOB, (CLASS_ID, TOKEN_ID)),
+            Error::<Test>::NoPermission
+        );
+    });
+}
+
+#[test]
+fn approval_cleared_when_owner_transfers() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        assert_ok!(GamePowerMarket::approve_token(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            BOB
+        ));
+
+        // Alice moves the asset herself
+        assert_ok!(GamePowerMarket::transfer(
+            Origin::signed(ALICE),
+            3,
+            (CLASS_ID, TOKEN_ID)
+        ));
+        assert_eq!(GamePowerMarket::token_approvals((CLASS_ID, TOKEN_ID)), None);
+
+        // Bob's old approval does not carry over to the new owner
+        assert_noop!(
+            GamePowerMarket::transfer(Origin::signed(BOB), BOB, (CLASS_ID, TOKEN_ID)),
+            Error::<Test>::NoPermission
+        );
+    });
+}
+
+#[test]
+fn approved_spender_can_list_for_owner() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        assert_ok!(GamePowerMarket::approve_token(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            BOB
+        ));
+
+        // Bob lists on behalf of Alice
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(BOB),
+            (CLASS_ID, TOKEN_ID),
+            100,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+
+        let listing = GamePowerMarket::listings(LISTING_ID).unwrap();
+        assert_eq!(listing.seller, ALICE, "Listing should belong to the owner");
+        assert_eq!(GamePowerMarket::listings_by_owner(&ALICE), vec![0]);
+        assert!(GamePowerMarket::listings_by_owner(&BOB).is_empty());
+        assert_eq!(GamePowerMarket::token_approvals((CLASS_ID, TOKEN_ID)), None);
+
+        // Only the owner can unlist
+        assert_noop!(
+            GamePowerMarket::unlist(Origin::signed(BOB), LISTING_ID),
+            Error::<Test>::NoPermission
+        );
+        assert_ok!(GamePowerMarket::unlist(Origin::signed(ALICE), LISTING_ID));
+        assert!(OrmlNFT::is_owner(&ALICE, (CLASS_ID, TOKEN_ID)));
+    });
+}
+
+#[test]
+fn revoke_token_approval_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        assert_ok!(GamePowerMarket::approve_token(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            BOB
+        ));
+        assert_ok!(GamePowerMarket::revoke_token_approval(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID)
+        ));
+        assert_eq!(GamePowerMarket::token_approvals((CLASS_ID, TOKEN_ID)), None);
+
+        // Bob can no longer move the asset
+        assert_noop!(
+            GamePowerMarket::transfer(Origin::signed(BOB), BOB, (CLASS_ID, TOKEN_ID)),
+            Error::<Test>::NoPermission
+        );
+
+        // Nothing left to revoke
+        assert_noop!(
+            GamePowerMarket::revoke_token_approval(Origin::signed(ALICE), (CLASS_ID, TOKEN_ID)),
+            Error::<Test>::ApprovalNotFound
+        );
+    });
+}
+
+#[test]
+fn approve_token_should_fail() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        // Only the owner can approve or revoke
+        assert_noop!(
+            GamePowerMarket::approve_token(Origin::signed(BOB), (CLASS_ID, TOKEN_ID), BOB),
+            Error::<Test>::NoPermission
+        );
+        assert_ok!(GamePowerMarket::approve_token(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            BOB
+        ));
+        assert_noop!(
+            GamePowerMarket::revoke_token_approval(Origin::signed(BOB), (CLASS_ID, TOKEN_ID)),
+            Error::<Test>::NoPermission
+        );
+    });
+}
+
+#[test]
+fn batch_approve_tokens_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFTs
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        assert_ok!(GamePowerMarket::batch_approve_tokens(
+            Origin::signed(ALICE),
+            vec![(CLASS_ID, 0), (CLASS_ID, 1)],
+            BOB
+        ));
+
+        // Each asset can be moved exactly once by the spender
+        for token_id in 0..2 {
+            assert_ok!(GamePowerMarket::transfer(
+                Origin::signed(BOB),
+                3,
+                (CLASS_ID, token_id)
+            ));
+            assert!(OrmlNFT::is_owner(&3, (CLASS_ID, token_id)));
+        }
+        assert_ok!(GamePowerMarket::transfer(
+            Origin::signed(3),
+            ALICE,
+            (CLASS_ID, 0)
+        ));
+        assert_noop!(
+            GamePowerMarket::transfer(Origin::signed(BOB), BOB, (CLASS_ID, 0)),
+            Error::<Test>::NoPermission
+        );
+    });
+}
+
+#[test]
+fn batch_approve_tokens_should_fail() {
+    new_test_ext().execute_with(|| {
+        // Create NFTs, one of them owned by Bob
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&BOB, CLASS_ID, vec![1], Default::default()));
+
+        // A single asset not owned by the sender fails the whole batch
+        assert_noop!(
+            GamePowerMarket::batch_approve_tokens(
+                Origin::signed(ALICE),
+                vec![(CLASS_ID, 0), (CLASS_ID, 1)],
+                3
+            ),
+            Error::<Test>::NoPermission
+        );
+        assert_eq!(GamePowerMarket::token_approvals((CLASS_ID, 0)), None);
+
+        // Batches are capped by MaxBatchSize
+        assert_noop!(
+            GamePowerMarket::batch_approve_tokens(Origin::signed(ALICE), vec![(CLASS_ID, 0); 4], 3),
+            Error::<Test>::BatchTooLarge
+        );
+    });
+}
+
+#[test]
+fn batch_revoke_token_approvals_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFTs
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        assert_ok!(GamePowerMarket::batch_approve_tokens(
+            Origin::signed(ALICE),
+            vec![(CLASS_ID, 0), (CLASS_ID, 1)],
+            BOB
+        ));
+
+        // Only the owner can revoke
+        assert_noop!(
+            GamePowerMarket::batch_revoke_token_approvals(
+                Origin::signed(BOB),
+                vec![(CLASS_ID, 0), (CLASS_ID, 1)]
+            ),
+            Error::<Test>::NoPermission
+        );
+
+        assert_ok!(GamePowerMarket::batch_revoke_token_approvals(
+            Origin::signed(ALICE),
+            vec![(CLASS_ID, 0), (CLASS_ID, 1)]
+        ));
+        assert_eq!(GamePowerMarket::token_approvals((CLASS_ID, 0)), None);
+        assert_eq!(GamePowerMarket::token_approvals((CLASS_ID, 1)), None);
+    });
+}
+
+#[test]
+fn class_operator_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFTs
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        assert_ok!(GamePowerMarket::approve_for_class(
+            Origin::signed(ALICE),
+            CLASS_ID,
+            BOB
+        ));
+        assert!(GamePowerMarket::class_approvals(ALICE, (CLASS_ID, BOB)));
+
+        // Bob transfers a token he does not own
+        assert_ok!(GamePowerMarket::transfer(
+            Origin::signed(BOB),
+            3,
+            (CLASS_ID, 0)
+        ));
+        assert!(OrmlNFT::is_owner(&3, (CLASS_ID, 0)));
+
+        // Bob lists a token on behalf of Alice
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(BOB),
+            (CLASS_ID, 1),
+            100,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_eq!(GamePowerMarket::listings(LISTING_ID).unwrap().seller, ALICE);
+
+        // Bob burns a token on behalf of Alice
+        assert_ok!(GamePowerMarket::burn(Origin::signed(BOB), (CLASS_ID, 2)));
+        assert!(OrmlNFT::tokens(CLASS_ID, 2).is_none());
+
+        // Tokens minted later are covered as well
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(GamePowerMarket::transfer(
+            Origin::signed(BOB),
+            BOB,
+            (CLASS_ID, 3)
+        ));
+
+        // The approval does not cover tokens of another owner
+        assert_noop!(
+            GamePowerMarket::transfer(Origin::signed(BOB), BOB, (CLASS_ID, 0)),
+            Error::<Test>::NoPermission
+        );
+    });
+}
+
+#[test]
+fn revoke_class_approval_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        // Unknown classes cannot be approved
+        assert_noop!(
+            GamePowerMarket::approve_for_class(Origin::signed(ALICE), CLASS_ID_NOT_EXIST, BOB),
+            Error::<Test>::AssetNotFound
+        );
+
+        assert_ok!(GamePowerMarket::approve_for_class(
+            Origin::signed(ALICE),
+            CLASS_ID,
+            BOB
+        ));
+        assert_ok!(GamePowerMarket::revoke_class_approval(
+            Origin::signed(ALICE),
+            CLASS_ID,
+            BOB
+        ));
+        assert!(!GamePowerMarket::class_approvals(ALICE, (CLASS_ID, BOB)));
+
+        assert_noop!(
+            GamePowerMarket::transfer(Origin::signed(BOB), BOB, (CLASS_ID, TOKEN_ID)),
             Error::<Test>::NoPermission
         );
+        assert_noop!(
+            GamePowerMarket::revoke_class_approval(Origin::signed(ALICE), CLASS_ID, BOB),
+            Error::<Test>::ApprovalNotFound
+        );
+    });
+}
+
+#[test]
+fn gift_with_message_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        assert_ok!(GamePowerMarket::gift_with_message(
+            Origin::signed(ALICE),
+            BOB,
+            (CLASS_ID, TOKEN_ID),
+            "gift".as_bytes().to_vec(),
+            "happy birthday".as_bytes().to_vec()
+        ));
+        assert!(OrmlNFT::is_owner(&BOB, (CLASS_ID, TOKEN_ID)));
+        assert_eq!(
+            GamePowerMarket::emotes((CLASS_ID, TOKEN_ID), ALICE).len(),
+            1,
+            "Emote should be added"
+        );
+        assert_eq!(
+            GamePowerMarket::get_gift_message((CLASS_ID, TOKEN_ID)),
+            Some("happy birthday".as_bytes().to_vec())
+        );
+
+        // The message persists after the asset moves on
+        assert_ok!(GamePowerMarket::transfer(
+            Origin::signed(BOB),
+            3,
+            (CLASS_ID, TOKEN_ID)
+        ));
+        assert_eq!(
+            GamePowerMarket::get_gift_message((CLASS_ID, TOKEN_ID)),
+            Some("happy birthday".as_bytes().to_vec())
+        );
+
+        // Burning the asset clears the message
+        assert_ok!(GamePowerMarket::burn(
+            Origin::signed(3),
+            (CLASS_ID, TOKEN_ID)
+        ));
+        assert_eq!(
+            GamePowerMarket::get_gift_message((CLASS_ID, TOKEN_ID)),
+            None
+        );
+    });
+}
+
+#[test]
+fn gift_with_message_should_fail() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        // Message longer than MaxMessageLength
+        assert_noop!(
+            GamePowerMarket::gift_with_message(
+                Origin::signed(ALICE),
+                BOB,
+                (CLASS_ID, TOKEN_ID),
+                "gift".as_bytes().to_vec(),
+                vec![b'a'; 17]
+            ),
+            Error::<Test>::MessageTooLong
+        );
+
+        // Invalid emote
+        assert_noop!(
+            GamePowerMarket::gift_with_message(
+                Origin::signed(ALICE),
+                BOB,
+                (CLASS_ID, TOKEN_ID),
+                "fasdfasdfaish".as_bytes().to_vec(),
+                "hi".as_bytes().to_vec()
+            ),
+            Error::<Test>::InvalidEmote
+        );
+
+        // Failed transfer leaves no emote or message behind
+        assert_noop!(
+            GamePowerMarket::gift_with_message(
+                Origin::signed(BOB),
+                ALICE,
+                (CLASS_ID, TOKEN_ID),
+                "gift".as_bytes().to_vec(),
+                "hi".as_bytes().to_vec()
+            ),
+            Error::<Test>::NoPermission
+        );
+    });
+}
+
+#[test]
+fn buy_secret_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        // Create a secret listing
+        assert_ok!(GamePowerMarket::list_secret(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            100,
+            42
+        ));
+        assert!(GamePowerMarket::secret_listings(0).is_some());
+        assert!(GamePowerMarket::is_locked(&(CLASS_ID, TOKEN_ID)));
+        assert_eq!(
+            GamePowerMarket::secret_listings_by_asset((CLASS_ID, TOKEN_ID)),
+            Some(0)
+        );
+        assert_eq!(GamePowerMarket::secret_listings_by_class(CLASS_ID), vec![0]);
+
+        // Buy with the correct reveal
+        assert_ok!(GamePowerMarket::buy_secret(Origin::signed(BOB), 0, 100, 42));
+        assert!(OrmlNFT::is_owner(&BOB, (CLASS_ID, TOKEN_ID)));
+        assert!(GamePowerMarket::secret_listings(0).is_none());
+        assert_eq!(
+            GamePowerMarket::secret_listings_by_asset((CLASS_ID, TOKEN_ID)),
+            None
+        );
+        assert!(GamePowerMarket::secret_listings_by_class(CLASS_ID).is_empty());
+        assert_eq!(GamePowerMarket::all_listings().len(), 0);
+
+        // Check Balances
+        assert_eq!(Balances::free_balance(ALICE), 1000000 + 100);
+        assert_eq!(Balances::free_balance(BOB), 1000000 - 100);
+    });
+}
+
+#[test]
+fn buy_secret_should_fail() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        assert_ok!(GamePowerMarket::list_secret(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            100,
+            42
+        ));
+
+        // Wrong price
+        assert_noop!(
+            GamePowerMarket::buy_secret(Origin::signed(BOB), 0, 1, 42),
+            Error::<Test>::InvalidPriceReveal
+        );
+        // Wrong nonce
+        assert_noop!(
+            GamePowerMarket::buy_secret(Origin::signed(BOB), 0, 100, 7),
+            Error::<Test>::InvalidPriceReveal
+        );
+        // Unknown listing
+        assert_noop!(
+            GamePowerMarket::buy_secret(Origin::signed(BOB), 1, 100, 42),
+            Error::<Test>::ListingNotFound
+        );
+
+        assert!(GamePowerMarket::secret_listings(0).is_some());
+        assert_eq!(Balances::free_balance(BOB), 1000000);
+    });
+}
+
+#[test]
+fn unlist_secret_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        assert_ok!(GamePowerMarket::list_secret(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            100,
+            42
+        ));
+
+        // Only the seller can unlist
+        assert_noop!(
+            GamePowerMarket::unlist_secret(Origin::signed(BOB), 0),
+            Error::<Test>::NoPermission
+        );
+
+        assert_ok!(GamePowerMarket::unlist_secret(Origin::signed(ALICE), 0));
+        assert!(OrmlNFT::is_owner(&ALICE, (CLASS_ID, TOKEN_ID)));
+        assert!(!GamePowerMarket::is_locked(&(CLASS_ID, TOKEN_ID)));
+        assert!(GamePowerMarket::secret_listings(0).is_none());
+        assert_eq!(
+            GamePowerMarket::secret_listings_by_asset((CLASS_ID, TOKEN_ID)),
+            None
+        );
+        assert!(GamePowerMarket::secret_listings_by_class(CLASS_ID).is_empty());
+    });
+}
+
+#[test]
+fn buy_with_fee_splits_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        // 10% to account 3 and 2.5% to account 4
+        assert_ok!(GamePowerMarket::set_fee_splits(
+            Origin::root(),
+            vec![
+                (3, Perbill::from_percent(10)),
+                (4, Perbill::from_perthousand(25))
+            ]
+        ));
+
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            1000,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::buy(Origin::signed(BOB), LISTING_ID));
+
+        // Check Balances
+        assert_eq!(Balances::free_balance(BOB), 1000000 - 1000);
+        assert_eq!(Balances::free_balance(3), 1000000 + 100);
+        assert_eq!(Balances::free_balance(4), 1000000 + 25);
+        assert_eq!(Balances::free_balance(ALICE), 1000000 + 875);
+    });
+}
+
+#[test]
+fn fee_splits_should_support_fine_shares() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        <Balances as Currency<AccountId>>::make_free_balance_be(&BOB, 2000000);
+
+        // 0.1% to account 3 and nothing to account 4
+        assert_ok!(GamePowerMarket::set_fee_splits(
+            Origin::root(),
+            vec![(3, Perbill::from_parts(1_000_000)), (4, Perbill::zero())]
+        ));
+
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            1000000,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::buy(Origin::signed(BOB), LISTING_ID));
+
+        // Check Balances
+        assert_eq!(Balances::free_balance(3), 1000000 + 1000);
+        assert_eq!(Balances::free_balance(4), 1000000);
+        assert_eq!(Balances::free_balance(ALICE), 1000000 + 999000);
+    });
+}
+
+#[test]
+fn set_fee_splits_should_fail() {
+    new_test_ext().execute_with(|| {
+        // Only the governance origin can update splits
+        assert_noop!(
+            GamePowerMarket::set_fee_splits(
+                Origin::signed(ALICE),
+                vec![(3, Perbill::from_percent(10))]
+            ),
+            DispatchError::BadOrigin
+        );
+
+        // Shares cannot add up to more than the price
+        assert_noop!(
+            GamePowerMarket::set_fee_splits(
+                Origin::root(),
+                vec![
+                    (3, Perbill::from_percent(60)),
+                    (4, Perbill::from_percent(50))
+                ]
+            ),
+            Error::<Test>::InvalidFeeSplits
+        );
+
+        // Recipients are capped by MaxFeeSplitRecipients
+        assert_noop!(
+            GamePowerMarket::set_fee_splits(
+                Origin::root(),
+                vec![
+                    (3, Perbill::from_percent(1)),
+                    (4, Perbill::from_percent(1)),
+                    (5, Perbill::from_percent(1))
+                ]
+            ),
+            Error::<Test>::TooManyFeeSplitRecipients
+        );
+
+        // Clearing the splits is allowed
+        assert_ok!(GamePowerMarket::set_fee_splits(Origin::root(), vec![]));
+        assert_eq!(GamePowerMarket::fee_splits(), vec![]);
+    });
+}
+
+#[test]
+fn claim_with_reward_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        // Create a claim with a reward
+        assert_ok!(GamePowerMarket::create_claim_with_reward(
+            Origin::signed(ALICE),
+            BOB,
+            (CLASS_ID, TOKEN_ID),
+            1000
+        ));
+        assert_eq!(GamePowerMarket::claim_rewards(0), Some((ALICE, 1000)));
+        assert_eq!(Balances::reserved_balance(ALICE), 1000);
+
+        // Claim the asset and the reward
+        assert_ok!(GamePowerMarket::claim(Origin::signed(BOB), 0));
+        assert!(OrmlNFT::is_owner(&BOB, (CLASS_ID, TOKEN_ID)));
+        assert!(GamePowerMarket::claim_rewards(0).is_none());
+
+        // Check Balances
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        assert_eq!(Balances::free_balance(ALICE), 1000000 - 1000);
+        assert_eq!(Balances::free_balance(BOB), 1000000 + 1000);
+    });
+}
+
+#[test]
+fn cancel_claim_reward_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        // Create a claim with a reward
+        assert_ok!(GamePowerMarket::create_claim_with_reward(
+            Origin::signed(ALICE),
+            BOB,
+            (CLASS_ID, TOKEN_ID),
+            1000
+        ));
+
+        // Only the issuer can cancel the reward
+        assert_noop!(
+            GamePowerMarket::cancel_claim_reward(Origin::signed(BOB), 0),
+            Error::<Test>::NoPermission
+        );
+
+        // Cancel the reward and refund the issuer
+        assert_ok!(GamePowerMarket::cancel_claim_reward(
+            Origin::signed(ALICE),
+            0
+        ));
+        assert!(GamePowerMarket::claim_rewards(0).is_none());
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        assert_eq!(Balances::free_balance(ALICE), 1000000);
+
+        // The claim stays open without the reward
+        assert_ok!(GamePowerMarket::claim(Origin::signed(BOB), 0));
+        assert!(OrmlNFT::is_owner(&BOB, (CLASS_ID, TOKEN_ID)));
+        assert_eq!(Balances::free_balance(BOB), 1000000);
+    });
+}
+
+#[test]
+fn collection_verification_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT class
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert!(!GamePowerMarket::is_verified_collection(CLASS_ID));
+
+        // Only the class owner can apply
+        assert_noop!(
+            GamePowerMarket::apply_for_verification(Origin::signed(BOB), CLASS_ID),
+            Error::<Test>::NoPermission
+        );
+        assert_ok!(GamePowerMarket::apply_for_verification(
+            Origin::signed(ALICE),
+            CLASS_ID
+        ));
+        assert_eq!(
+            GamePowerMarket::verified_collections(CLASS_ID),
+            Some(VerificationStatus::Pending)
+        );
+
+        // Pending collections cannot apply again
+        assert_noop!(
+            GamePowerMarket::apply_for_verification(Origin::signed(ALICE), CLASS_ID),
+            Error::<Test>::InvalidVerificationStatus
+        );
+
+        // Only the verification origin can approve
+        assert_noop!(
+            GamePowerMarket::approve_verification(Origin::signed(ALICE), CLASS_ID),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(GamePowerMarket::approve_verification(
+            Origin::root(),
+            CLASS_ID
+        ));
+        assert!(GamePowerMarket::is_verified_collection(CLASS_ID));
+
+        // Verified collections can be revoked
+        assert_ok!(GamePowerMarket::reject_verification(
+            Origin::root(),
+            CLASS_ID
+        ));
+        assert!(!GamePowerMarket::is_verified_collection(CLASS_ID));
+        assert_eq!(
+            GamePowerMarket::verified_collections(CLASS_ID),
+            Some(VerificationStatus::Rejected)
+        );
+    });
+}
+
+#[test]
+fn collection_verification_should_fail() {
+    new_test_ext().execute_with(|| {
+        // Create NFT class
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+
+        // Classes that don't exist can't apply
+        assert_noop!(
+            GamePowerMarket::apply_for_verification(Origin::signed(ALICE), CLASS_ID_NOT_EXIST),
+            Error::<Test>::AssetNotFound
+        );
+
+        // Nothing to approve or reject before applying
+        assert_noop!(
+            GamePowerMarket::approve_verification(Origin::root(), CLASS_ID),
+            Error::<Test>::InvalidVerificationStatus
+        );
+        assert_noop!(
+            GamePowerMarket::reject_verification(Origin::root(), CLASS_ID),
+            Error::<Test>::InvalidVerificationStatus
+        );
+
+        // Rejected collections can't be approved without applying again
+        assert_ok!(GamePowerMarket::apply_for_verification(
+            Origin::signed(ALICE),
+            CLASS_ID
+        ));
+        assert_ok!(GamePowerMarket::reject_verification(
+            Origin::root(),
+            CLASS_ID
+        ));
+        assert_noop!(
+            GamePowerMarket::approve_verification(Origin::root(), CLASS_ID),
+            Error::<Test>::InvalidVerificationStatus
+        );
+        assert_ok!(GamePowerMarket::apply_for_verification(
+            Origin::signed(ALICE),
+            CLASS_ID
+        ));
+        assert_ok!(GamePowerMarket::approve_verification(
+            Origin::root(),
+            CLASS_ID
+        ));
+        assert!(GamePowerMarket::is_verified_collection(CLASS_ID));
+    });
+}
+
+#[test]
+fn price_history_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        // Trade the asset back and forth, once per block
+        let sales = [(ALICE, BOB), (BOB, ALICE), (ALICE, BOB), (BOB, ALICE)];
+        for (index, (seller, buyer)) in sales.iter().enumerate() {
+            System::set_block_number(index as u64 + 1);
+            assert_ok!(GamePowerMarket::list(
+                Origin::signed(*seller),
+                (CLASS_ID, TOKEN_ID),
+                1000 * (index as u64 + 1),
+                None,
+                NATIVE_CURRENCY_ID
+            ));
+            assert_ok!(GamePowerMarket::buy(Origin::signed(*buyer), index as u64));
+        }
+
+        // The oldest sale is dropped once MaxPriceHistoryDepth is reached
+        assert_eq!(
+            GamePowerMarket::get_price_history((CLASS_ID, TOKEN_ID)),
+            vec![(2000, 2), (3000, 3), (4000, 4)]
+        );
+        assert!(GamePowerMarket::get_price_history((CLASS_ID, TOKEN_ID_NOT_EXIST)).is_empty());
+    });
+}
+
+#[test]
+fn price_chart_data_should_work() {
+    new_test_ext().execute_with(|| {
+        TokenPriceHistory::<Test>::insert(
+            (CLASS_ID, TOKEN_ID),
+            vec![(100, 10), (300, 20), (200, 30), (600, 50), (500, 60)],
+        );
+
+        // Evenly spaced blocks with interpolated prices
+        assert_eq!(
+            GamePowerMarket::get_price_chart_data((CLASS_ID, TOKEN_ID), 3),
+            vec![(100, 10), (300, 35), (500, 60)]
+        );
+        assert_eq!(
+            GamePowerMarket::get_price_chart_data((CLASS_ID, TOKEN_ID), 4),
+            vec![(100, 10), (240, 26), (460, 43), (500, 60)]
+        );
+
+        // Edge cases
+        assert!(GamePowerMarket::get_price_chart_data((CLASS_ID, TOKEN_ID), 0).is_empty());
+        assert_eq!(
+            GamePowerMarket::get_price_chart_data((CLASS_ID, TOKEN_ID), 1),
+            vec![(500, 60)]
+        );
+        assert_eq!(
+            GamePowerMarket::get_price_chart_data((CLASS_ID, TOKEN_ID), 10),
+            GamePowerMarket::get_price_history((CLASS_ID, TOKEN_ID))
+        );
+    });
+}
+
+#[test]
+fn marketplace_stats_should_emit_at_interval() {
+    new_test_ext().execute_with(|| {
+        // Create NFTs
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        // List both assets and sell one
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, 0),
+            1000,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, 1),
+            2000,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::buy(Origin::signed(BOB), 0));
+
+        let stats_events = || {
+            System::events()
+                .into_iter()
+                .filter(|record| {
+                    matches!(
+                        record.event,
+                        Event::gamepower_market(RawEvent::MarketStats(..))
+                    )
+                })
+                .count()
+        };
+
+        // Nothing is emitted between intervals
+        for block in 1..StatsEmitInterval::get() {
+            assert_eq!(GamePowerMarket::on_initialize(block), 0);
+        }
+        assert_eq!(stats_events(), 0);
+
+        // The snapshot carries the current storage values
+        assert_eq!(
+            GamePowerMarket::on_initialize(StatsEmitInterval::get()),
+            <<Test as frame_system::Config>::DbWeight as Get<RuntimeDbWeight>>::get().reads(4)
+        );
+        assert_eq!(stats_events(), 1);
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketStats(
+                1,
+                1,
+                1000,
+                StatsEmitInterval::get()
+            )))
+        );
+        assert_eq!(GamePowerMarket::listing_count(), 1);
+        assert_eq!(GamePowerMarket::order_count(), 1);
+        assert_eq!(GamePowerMarket::total_volume(), 1000);
+
+        // And again on the next boundary only
+        GamePowerMarket::on_initialize(StatsEmitInterval::get() + 1);
+        GamePowerMarket::on_initialize(StatsEmitInterval::get() * 2);
+        assert_eq!(stats_events(), 2);
+    });
+}
+
+#[test]
+fn permanent_blacklist_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&BOB, CLASS_ID, vec![1], Default::default()));
+
+        // Only the force origin can ban accounts
+        assert_noop!(
+            GamePowerMarket::blacklist_account(Origin::signed(ALICE), BOB, u64::MAX),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(GamePowerMarket::blacklist_account(
+            Origin::root(),
+            BOB,
+            u64::MAX
+        ));
+        assert!(GamePowerMarket::is_blacklisted(&BOB));
+
+        // Banned accounts cannot use the market
+        assert_noop!(
+            GamePowerMarket::transfer(Origin::signed(BOB), ALICE, (CLASS_ID, TOKEN_ID)),
+            Error::<Test>::AccountBlacklisted
+        );
+        assert_noop!(
+            GamePowerMarket::list(
+                Origin::signed(BOB),
+                (CLASS_ID, TOKEN_ID),
+                1000,
+                None,
+                NATIVE_CURRENCY_ID
+            ),
+            Error::<Test>::AccountBlacklisted
+        );
+        assert_noop!(
+            GamePowerMarket::burn(Origin::signed(BOB), (CLASS_ID, TOKEN_ID)),
+            Error::<Test>::AccountBlacklisted
+        );
+        assert_noop!(
+            GamePowerMarket::emote(
+                Origin::signed(BOB),
+                (CLASS_ID, TOKEN_ID),
+                "fish".as_bytes().to_vec()
+            ),
+            Error::<Test>::AccountBlacklisted
+        );
+
+        // Lifting the ban restores access
+        assert_ok!(GamePowerMarket::unblacklist_account(Origin::root(), BOB));
+        assert!(!GamePowerMarket::is_blacklisted(&BOB));
+        assert_ok!(GamePowerMarket::transfer(
+            Origin::signed(BOB),
+            ALICE,
+            (CLASS_ID, TOKEN_ID)
+        ));
+
+        assert_noop!(
+            GamePowerMarket::unblacklist_account(Origin::root(), BOB),
+            Error::<Test>::AccountNotFound
+        );
+    });
+}
+
+#[test]
+fn recalculate_class_stats_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        for _ in 0..3 {
+            assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        }
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, 0),
+            100,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::buy(Origin::signed(BOB), 0));
+        System::set_block_number(5);
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, 1),
+            300,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::buy(Origin::signed(BOB), 1));
+        assert_ok!(GamePowerMarket::burn(Origin::signed(ALICE), (CLASS_ID, 2)));
+
+        // Counters left inconsistent by earlier bugs
+        TotalMintedByClass::<Test>::insert(CLASS_ID, 1);
+        ClassSalesCount::<Test>::insert(CLASS_ID, 7);
+        ClassTotalVolume::<Test>::insert(CLASS_ID, 0);
+        ClassLastSalePrice::<Test>::insert(CLASS_ID, 999);
+        ClassLastSaleBlock::<Test>::insert(CLASS_ID, 1);
+
+        assert_noop!(
+            GamePowerMarket::recalculate_class_stats(Origin::signed(ALICE), CLASS_ID),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            GamePowerMarket::recalculate_class_stats(Origin::root(), CLASS_ID_NOT_EXIST),
+            Error::<Test>::AssetNotFound
+        );
+        assert_ok!(GamePowerMarket::recalculate_class_stats(
+            Origin::root(),
+            CLASS_ID
+        ));
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(
+                RawEvent::MarketClassStatsRecalculated(CLASS_ID)
+            ))
+        );
+
+        // The burned token still counts as minted
+        assert_eq!(GamePowerMarket::total_minted(CLASS_ID), 3);
+        assert_eq!(GamePowerMarket::class_sales_count(CLASS_ID), 2);
+        assert_eq!(GamePowerMarket::class_total_volume(CLASS_ID), 400);
+        assert_eq!(GamePowerMarket::class_last_sale_price(CLASS_ID), Some(300));
+        assert_eq!(GamePowerMarket::class_last_sale_block(CLASS_ID), Some(5));
+    });
+}
+
+#[test]
+fn blacklist_should_cover_cancel_paths() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        for _ in 0..4 {
+            assert_ok!(OrmlNFT::mint(&BOB, CLASS_ID, vec![1], Default::default()));
+        }
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        // Open a listing, auctions, an offer and a swap before the ban
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(BOB),
+            (CLASS_ID, 0),
+            1000,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::create_auction(
+            Origin::signed(BOB),
+            (CLASS_ID, 1),
+            100,
+            100,
+            10
+        ));
+        assert_ok!(GamePowerMarket::create_dutch_auction(
+            Origin::signed(BOB),
+            (CLASS_ID, 2),
+            1000,
+            100,
+            1,
+            10
+        ));
+        assert_ok!(GamePowerMarket::make_offer(
+            Origin::signed(BOB),
+            (CLASS_ID, 4),
+            100,
+            10,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::propose_swap(
+            Origin::signed(BOB),
+            (CLASS_ID, 3),
+            (CLASS_ID, 4),
+            ALICE,
+            10
+        ));
+
+        assert_ok!(GamePowerMarket::blacklist_account(
+            Origin::root(),
+            BOB,
+            u64::MAX
+        ));
+
+        // A banned account can neither unlist nor cancel anything it opened
+        assert_noop!(
+            GamePowerMarket::unlist(Origin::signed(BOB), LISTING_ID),
+            Error::<Test>::AccountBlacklisted
+        );
+        assert_noop!(
+            GamePowerMarket::cancel_auction(Origin::signed(BOB), 0),
+            Error::<Test>::AccountBlacklisted
+        );
+        assert_noop!(
+            GamePowerMarket::cancel_dutch_auction(Origin::signed(BOB), 0),
+            Error::<Test>::AccountBlacklisted
+        );
+        assert_noop!(
+            GamePowerMarket::cancel_offer(Origin::signed(BOB), 0),
+            Error::<Test>::AccountBlacklisted
+        );
+        assert_noop!(
+            GamePowerMarket::cancel_swap(Origin::signed(BOB), 0),
+            Error::<Test>::AccountBlacklisted
+        );
+
+        // Nor run the permissionless cleanup calls
+        System::set_block_number(11);
+        assert_noop!(
+            GamePowerMarket::settle_auction(Origin::signed(BOB), 0),
+            Error::<Test>::AccountBlacklisted
+        );
+        assert_noop!(
+            GamePowerMarket::cleanup_offer(Origin::signed(BOB), 0),
+            Error::<Test>::AccountBlacklisted
+        );
+        assert_ok!(GamePowerMarket::settle_auction(Origin::signed(ALICE), 0));
+        assert_ok!(GamePowerMarket::cleanup_offer(Origin::signed(ALICE), 0));
+    });
+}
+
+#[test]
+fn temporary_blacklist_should_expire() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            1000,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+
+        // Ban through block 5
+        assert_ok!(GamePowerMarket::blacklist_account(Origin::root(), BOB, 5));
+
+        System::set_block_number(5);
+        assert_noop!(
+            GamePowerMarket::buy(Origin::signed(BOB), 0),
+            Error::<Test>::AccountBlacklisted
+        );
+
+        // The ban expires after the last block
+        System::set_block_number(6);
+        assert!(!GamePowerMarket::is_blacklisted(&BOB));
+        assert_ok!(GamePowerMarket::buy(Origin::signed(BOB), 0));
+        assert!(OrmlNFT::is_owner(&BOB, (CLASS_ID, TOKEN_ID)));
+    });
+}
+
+#[test]
+fn class_market_data_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFTs
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        // Listing and unlisting don't count as sales
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, 0),
+            3000,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, 1),
+            1000,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::unlist(Origin::signed(ALICE), 1));
+        assert_eq!(
+            GamePowerMarket::get_class_market_data(CLASS_ID),
+            ClassMarketData {
+                last_sale_block: None,
+                last_sale_price: None,
+                floor_price: Some(3000),
+                volume_24h: 0,
+            }
+        );
+
+        // Each buy updates the class figures
+        System::set_block_number(5);
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, 1),
+            1000,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::buy(Origin::signed(BOB), 2));
+        assert_eq!(
+            GamePowerMarket::get_class_market_data(CLASS_ID),
+            ClassMarketData {
+                last_sale_block: Some(5),
+                last_sale_price: Some(1000),
+                floor_price: Some(3000),
+                volume_24h: 1000,
+            }
+        );
+
+        System::set_block_number(8);
+        assert_ok!(GamePowerMarket::buy(Origin::signed(BOB), 0));
+        assert_eq!(
+            GamePowerMarket::get_class_market_data(CLASS_ID),
+            ClassMarketData {
+                last_sale_block: Some(8),
+                last_sale_price: Some(3000),
+                floor_price: None,
+                volume_24h: 4000,
+            }
+        );
+
+        // Sales older than a day drop out of the daily volume
+        System::set_block_number(5 + BlocksPerDay::get());
+        assert_eq!(
+            GamePowerMarket::get_class_market_data(CLASS_ID).volume_24h,
+            3000
+        );
+        assert_eq!(
+            GamePowerMarket::get_class_market_data(CLASS_ID_NOT_EXIST),
+            ClassMarketData::default()
+        );
+    });
+}
+
+#[test]
+fn transfer_via_claim_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFTs
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        // Two-step path
+        assert_ok!(GamePowerMarket::create_claim(
+            Origin::signed(ALICE),
+            BOB,
+            (CLASS_ID, 0),
+            None
+        ));
+        assert_ok!(GamePowerMarket::claim(Origin::signed(BOB), 0));
+        let two_step_events = System::events().len();
+
+        // One-step path ends in the same state
+        assert_ok!(GamePowerMarket::transfer_via_claim(
+            Origin::signed(ALICE),
+            BOB,
+            (CLASS_ID, 1)
+        ));
+        assert!(OrmlNFT::is_owner(&BOB, (CLASS_ID, 0)));
+        assert!(OrmlNFT::is_owner(&BOB, (CLASS_ID, 1)));
+        assert_eq!(GamePowerMarket::next_claim_id(), 2);
+        assert!(GamePowerMarket::open_claims(BOB, 0).is_none());
+        assert!(GamePowerMarket::open_claims(BOB, 1).is_none());
+        assert!(GamePowerMarket::all_claims().is_empty());
+
+        // And emits the same events
+        let events: Vec<_> = System::events()
+            .into_iter()
+            .map(|record| record.event)
+            .collect();
+        assert_eq!(events.len(), two_step_events * 2);
+        assert_eq!(
+            events[two_step_events..].last(),
+            Some(&Event::gamepower_market(RawEvent::MarketAssetClaimed(
+                BOB, CLASS_ID, 1
+            )))
+        );
+        assert!(events[two_step_events..].contains(&Event::gamepower_market(
+            RawEvent::MarketClaimCreated(ALICE, BOB, CLASS_ID, 1)
+        )));
+    });
+}
+
+#[test]
+fn transfer_via_claim_should_fail() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&BOB, CLASS_ID, vec![1], Default::default()));
+
+        // Only the class owner can issue claims
+        assert_noop!(
+            GamePowerMarket::transfer_via_claim(Origin::signed(BOB), ALICE, (CLASS_ID, TOKEN_ID)),
+            Error::<Test>::NoPermission
+        );
+        assert!(OrmlNFT::is_owner(&BOB, (CLASS_ID, TOKEN_ID)));
+    });
+}
+
+#[test]
+fn limited_edition_class_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create a class capped at 3 tokens
+        assert_ok!(GamePowerMarket::create_class(
+            Origin::signed(ALICE),
+            vec![1],
+            Default::default(),
+            Some(3)
+        ));
+        assert_eq!(GamePowerMarket::limited_edition_cap(CLASS_ID), Some(3));
+
+        // Minting exactly at the cap succeeds
+        assert_ok!(GamePowerMarket::mint(
+            Origin::signed(ALICE),
+            CLASS_ID,
+            vec![1],
+            Default::default(),
+            2
+        ));
+        assert_ok!(GamePowerMarket::mint(
+            Origin::signed(ALICE),
+            CLASS_ID,
+            vec![1],
+            Default::default(),
+            1
+        ));
+        assert_eq!(GamePowerMarket::class_minted_count(CLASS_ID), 3);
+
+        // One more fails
+        assert_noop!(
+            GamePowerMarket::mint(
+                Origin::signed(ALICE),
+                CLASS_ID,
+                vec![1],
+                Default::default(),
+                1
+            ),
+            Error::<Test>::LimitedEditionCapReached
+        );
+
+        // Burning doesn't free up a slot
+        assert_ok!(GamePowerMarket::burn(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID)
+        ));
+        assert_noop!(
+            GamePowerMarket::mint(
+                Origin::signed(ALICE),
+                CLASS_ID,
+                vec![1],
+                Default::default(),
+                1
+            ),
+            Error::<Test>::LimitedEditionCapReached
+        );
+
+        // Assets minted outside the market count against the cap once reported
+        assert_ok!(GamePowerMarket::create_class(
+            Origin::signed(ALICE),
+            vec![1],
+            Default::default(),
+            Some(1)
+        ));
+        assert_ok!(OrmlNFT::mint(
+            &ALICE,
+            CLASS_ID + 1,
+            vec![1],
+            Default::default()
+        ));
+        assert_ok!(<GamePowerMarket as OnMintHandler<_, _, _>>::on_mint(
+            &ALICE,
+            (CLASS_ID + 1, TOKEN_ID)
+        ));
+        assert_eq!(GamePowerMarket::class_minted_count(CLASS_ID + 1), 0);
+        assert_noop!(
+            GamePowerMarket::mint(
+                Origin::signed(ALICE),
+                CLASS_ID + 1,
+                vec![1],
+                Default::default(),
+                1
+            ),
+            Error::<Test>::LimitedEditionCapReached
+        );
+    });
+}
+
+#[test]
+fn mint_should_fail() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(GamePowerMarket::create_class(
+            Origin::signed(ALICE),
+            vec![1],
+            Default::default(),
+            Some(3)
+        ));
+
+        // Only the class owner can mint
+        assert_noop!(
+            GamePowerMarket::mint(
+                Origin::signed(BOB),
+                CLASS_ID,
+                vec![1],
+                Default::default(),
+                1
+            ),
+            Error::<Test>::NoPermission
+        );
+        assert_noop!(
+            GamePowerMarket::mint(
+                Origin::signed(ALICE),
+                CLASS_ID,
+                vec![1],
+                Default::default(),
+                0
+            ),
+            Error::<Test>::InvalidQuantity
+        );
+
+        // A batch over the cap mints nothing
+        assert_noop!(
+            GamePowerMarket::mint(
+                Origin::signed(ALICE),
+                CLASS_ID,
+                vec![1],
+                Default::default(),
+                4
+            ),
+            Error::<Test>::LimitedEditionCapReached
+        );
+
+        // Classes without a cap are unlimited
+        assert_ok!(GamePowerMarket::create_class(
+            Origin::signed(ALICE),
+            vec![1],
+            Default::default(),
+            None
+        ));
+        assert_eq!(
+            GamePowerMarket::limited_edition_cap(CLASS_ID_NOT_EXIST),
+            None
+        );
+        assert_ok!(GamePowerMarket::mint(
+            Origin::signed(ALICE),
+            CLASS_ID_NOT_EXIST,
+            vec![1],
+            Default::default(),
+            10
+        ));
+    });
+}
+
+#[test]
+fn airdrop_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+
+        // Mint one asset to each recipient
+        assert_ok!(GamePowerMarket::airdrop(
+            Origin::signed(ALICE),
+            CLASS_ID,
+            vec![BOB, 3, 4],
+            vec![1],
+            Default::default()
+        ));
+        assert!(OrmlNFT::is_owner(&BOB, (CLASS_ID, 0)));
+        assert!(OrmlNFT::is_owner(&3, (CLASS_ID, 1)));
+        assert!(OrmlNFT::is_owner(&4, (CLASS_ID, 2)));
+        assert_eq!(GamePowerMarket::class_minted_count(CLASS_ID), 3);
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketAirdropCompleted(
+                ALICE, CLASS_ID, 3
+            )))
+        );
+    });
+}
+
+#[test]
+fn airdrop_should_fail() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(GamePowerMarket::create_class(
+            Origin::signed(ALICE),
+            vec![1],
+            Default::default(),
+            Some(2)
+        ));
+
+        // Recipients are capped by MaxAirdropRecipients
+        assert_noop!(
+            GamePowerMarket::airdrop(
+                Origin::signed(ALICE),
+                CLASS_ID,
+                vec![BOB, 3, 4, 5],
+                vec![1],
+                Default::default()
+            ),
+            Error::<Test>::TooManyAirdropRecipients
+        );
+
+        // Only the class owner can airdrop
+        assert_noop!(
+            GamePowerMarket::airdrop(
+                Origin::signed(BOB),
+                CLASS_ID,
+                vec![BOB],
+                vec![1],
+                Default::default()
+            ),
+            Error::<Test>::NoPermission
+        );
+
+        // Nothing is minted if the airdrop doesn't fit the limited edition cap
+        assert_noop!(
+            GamePowerMarket::airdrop(
+                Origin::signed(ALICE),
+                CLASS_ID,
+                vec![BOB, 3, 4],
+                vec![1],
+                Default::default()
+            ),
+            Error::<Test>::LimitedEditionCapReached
+        );
+        assert_eq!(GamePowerMarket::class_minted_count(CLASS_ID), 0);
+
+        // A mint failing part way through leaves no assets behind
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        orml_nft::NextTokenId::<Test>::insert(CLASS_ID_NOT_EXIST, u64::MAX - 1);
+        assert!(GamePowerMarket::airdrop(
+            Origin::signed(ALICE),
+            CLASS_ID_NOT_EXIST,
+            vec![BOB, 3],
+            vec![1],
+            Default::default()
+        )
+        .is_err());
+        assert!(OrmlNFT::tokens(CLASS_ID_NOT_EXIST, u64::MAX - 1).is_none());
+        assert_eq!(GamePowerMarket::class_minted_count(CLASS_ID_NOT_EXIST), 0);
+    });
+}
+
+#[test]
+fn reserve_listing_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT and list it
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            1000,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+
+        // Reserve for 5 blocks
+        assert_ok!(GamePowerMarket::reserve_listing(
+            Origin::signed(ALICE),
+            LISTING_ID,
+            5
+        ));
+        assert_eq!(
+            GamePowerMarket::reserved_listings(LISTING_ID),
+            Some((ALICE, 6))
+        );
+        assert_noop!(
+            GamePowerMarket::buy(Origin::signed(BOB), LISTING_ID),
+            Error::<Test>::ListingReserved
+        );
+
+        // The seller can release it early
+        assert_ok!(GamePowerMarket::unreserve_listing(
+            Origin::signed(ALICE),
+            LISTING_ID
+        ));
+        assert!(GamePowerMarket::reserved_listings(LISTING_ID).is_none());
+        assert!(GamePowerMarket::reservation_expiries(6).is_empty());
+        assert_noop!(
+            GamePowerMarket::unreserve_listing(Origin::signed(ALICE), LISTING_ID),
+            Error::<Test>::ListingNotReserved
+        );
+        assert_ok!(GamePowerMarket::buy(Origin::signed(BOB), LISTING_ID));
+    });
+}
+
+#[test]
+fn reserved_listing_should_expire() {
+    new_test_ext().execute_with(|| {
+        // Create NFT and list it
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            1000,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::reserve_listing(
+            Origin::signed(ALICE),
+            LISTING_ID,
+            5
+        ));
+
+        // Still reserved the block before expiry
+        GamePowerMarket::on_initialize(5);
+        assert!(GamePowerMarket::reserved_listings(LISTING_ID).is_some());
+
+        // Released automatically at expiry
+        System::set_block_number(6);
+        GamePowerMarket::on_initialize(6);
+        assert!(GamePowerMarket::reserved_listings(LISTING_ID).is_none());
+        assert!(GamePowerMarket::reservation_expiries(6).is_empty());
+        assert_ok!(GamePowerMarket::buy(Origin::signed(BOB), LISTING_ID));
+    });
+}
+
+#[test]
+fn reserve_listing_should_fail() {
+    new_test_ext().execute_with(|| {
+        // Create NFT and list it
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            1000,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+
+        // Only the seller can reserve or release
+        assert_noop!(
+            GamePowerMarket::reserve_listing(Origin::signed(BOB), LISTING_ID, 5),
+            Error::<Test>::NoPermission
+        );
+        assert_noop!(
+            GamePowerMarket::reserve_listing(Origin::signed(ALICE), LISTING_ID, 0),
+            Error::<Test>::InvalidDuration
+        );
+        assert_ok!(GamePowerMarket::reserve_listing(
+            Origin::signed(ALICE),
+            LISTING_ID,
+            5
+        ));
+        assert_noop!(
+            GamePowerMarket::unreserve_listing(Origin::signed(BOB), LISTING_ID),
+            Error::<Test>::NoPermission
+        );
+
+        // Unlisting clears the reservation
+        assert_ok!(GamePowerMarket::unlist(Origin::signed(ALICE), LISTING_ID));
+        assert!(GamePowerMarket::reserved_listings(LISTING_ID).is_none());
+        assert!(GamePowerMarket::reservation_expiries(6).is_empty());
+    });
+}
+
+#[test]
+fn relist_count_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_eq!(GamePowerMarket::get_relist_count((CLASS_ID, TOKEN_ID)), 0);
+
+        // Listing counts, unlisting doesn't
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            1000,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::unlist(Origin::signed(ALICE), 0));
+        assert_eq!(GamePowerMarket::get_relist_count((CLASS_ID, TOKEN_ID)), 1);
+
+        // Buying doesn't count
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            1000,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::buy(Origin::signed(BOB), 1));
+        assert_eq!(GamePowerMarket::get_relist_count((CLASS_ID, TOKEN_ID)), 2);
+
+        // The count survives transfers between owners
+        assert_ok!(GamePowerMarket::transfer(
+            Origin::signed(BOB),
+            3,
+            (CLASS_ID, TOKEN_ID)
+        ));
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(3),
+            (CLASS_ID, TOKEN_ID),
+            1000,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_eq!(GamePowerMarket::get_relist_count((CLASS_ID, TOKEN_ID)), 3);
+        assert_eq!(
+            GamePowerMarket::get_relist_count((CLASS_ID, TOKEN_ID_NOT_EXIST)),
+            0
+        );
+    });
+}
+
+#[test]
+fn get_listings_by_owner_should_paginate() {
+    new_test_ext().execute_with(|| {
+        // Create and list NFTs
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        for token_id in 0..5 {
+            assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+            assert_ok!(GamePowerMarket::list(
+                Origin::signed(ALICE),
+                (CLASS_ID, token_id),
+                1000 + token_id,
+                None,
+                NATIVE_CURRENCY_ID
+            ));
+        }
+
+        let ids = |listings: Vec<crate::ListingOf<Test>>| -> Vec<u64> {
+            listings.into_iter().map(|listing| listing.id).collect()
+        };
+
+        // First page
+        let (listings, total_count) = GamePowerMarket::get_listings_by_owner(&ALICE, 0, 2);
+        assert_eq!(ids(listings), vec![0, 1]);
+        assert_eq!(total_count, 5);
+
+        // Middle page, with the limit running past the end
+        let (listings, total_count) = GamePowerMarket::get_listings_by_owner(&ALICE, 3, 10);
+        assert_eq!(ids(listings), vec![3, 4]);
+        assert_eq!(total_count, 5);
+
+        // Offset beyond the end
+        let (listings, total_count) = GamePowerMarket::get_listings_by_owner(&ALICE, 5, 2);
+        assert!(listings.is_empty());
+        assert_eq!(total_count, 5);
+
+        // Unlisted listings are dropped
+        assert_ok!(GamePowerMarket::unlist(Origin::signed(ALICE), 1));
+        let (listings, total_count) = GamePowerMarket::get_listings_by_owner(&ALICE, 0, 10);
+        assert_eq!(ids(listings), vec![0, 2, 3, 4]);
+        assert_eq!(total_count, 4);
+        assert_eq!(
+            GamePowerMarket::get_listings_by_owner(&BOB, 0, 10),
+            (vec![], 0)
+        );
+    });
+}
+
+#[test]
+fn set_secondary_market_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFTs
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        // Listed before the class is taken off the market
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, 0),
+            1000,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, 1),
+            1000,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+
+        // Only the class owner can toggle the flag
+        assert_noop!(
+            GamePowerMarket::set_secondary_market(Origin::signed(BOB), CLASS_ID, true),
+            Error::<Test>::NoPermission
+        );
+        assert_ok!(GamePowerMarket::set_secondary_market(
+            Origin::signed(ALICE),
+            CLASS_ID,
+            true
+        ));
+        assert!(GamePowerMarket::secondary_market_disabled(CLASS_ID));
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketSecondaryMarketSet(
+                CLASS_ID, true
+            )))
+        );
+
+        // New listings are blocked
+        assert_noop!(
+            GamePowerMarket::list(
+                Origin::signed(ALICE),
+                (CLASS_ID, 2),
+                1000,
+                None,
+                NATIVE_CURRENCY_ID
+            ),
+            Error::<Test>::SecondaryMarketDisabled
+        );
+        assert_noop!(
+            GamePowerMarket::list_secret(Origin::signed(ALICE), (CLASS_ID, 2), 1000, 1),
+            Error::<Test>::SecondaryMarketDisabled
+        );
+
+        // Existing listings can still be unlisted and bought
+        assert_ok!(GamePowerMarket::unlist(Origin::signed(ALICE), 0));
+        assert!(OrmlNFT::is_owner(&ALICE, (CLASS_ID, 0)));
+        assert_ok!(GamePowerMarket::buy(Origin::signed(BOB), 1));
+        assert!(OrmlNFT::is_owner(&BOB, (CLASS_ID, 1)));
+
+        // Claims and transfers are unaffected
+        assert_ok!(GamePowerMarket::transfer(
+            Origin::signed(ALICE),
+            BOB,
+            (CLASS_ID, 2)
+        ));
+        assert_ok!(GamePowerMarket::create_claim(
+            Origin::signed(ALICE),
+            BOB,
+            (CLASS_ID, 0),
+            None
+        ));
+
+        // Re-enabling allows listing again
+        assert_ok!(GamePowerMarket::set_secondary_market(
+            Origin::signed(ALICE),
+            CLASS_ID,
+            false
+        ));
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(BOB),
+            (CLASS_ID, 2),
+            1000,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+    });
+}
+
+#[test]
+fn get_ownership_storage_key_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        // The key points at the token's ownership record
+        let (owner, storage_key) =
+            GamePowerMarket::get_ownership_storage_key((CLASS_ID, TOKEN_ID)).unwrap();
+        assert_eq!(owner, ALICE);
+        let token_info: orml_nft::TokenInfo<AccountId, AssetData<u32, u64, u64>> =
+            frame_support::storage::unhashed::get(&storage_key).unwrap();
+        assert_eq!(token_info.owner, ALICE);
+
+        assert_eq!(
+            GamePowerMarket::get_ownership_storage_key((CLASS_ID, TOKEN_ID_NOT_EXIST)),
+            None
+        );
+    });
+}
+
+#[test]
+fn set_class_tradeable_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFTs
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        for _ in 0..5 {
+            assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        }
+
+        // Listed and claimable before trading is suspended
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, 0),
+            1000,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::list_secret(
+            Origin::signed(ALICE),
+            (CLASS_ID, 1),
+            1000,
+            1
+        ));
+        assert_ok!(GamePowerMarket::create_claim(
+            Origin::signed(ALICE),
+            BOB,
+            (CLASS_ID, 2),
+            None
+        ));
+
+        // Only the class owner can toggle trading
+        assert_noop!(
+            GamePowerMarket::set_class_tradeable(Origin::signed(BOB), CLASS_ID, false),
+            Error::<Test>::NoPermission
+        );
+        assert_ok!(GamePowerMarket::set_class_tradeable(
+            Origin::signed(ALICE),
+            CLASS_ID,
+            false
+        ));
+        assert!(GamePowerMarket::non_tradeable(CLASS_ID));
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(
+                RawEvent::MarketClassTradeabilityChanged(CLASS_ID, false)
+            ))
+        );
+
+        // Listing, buying and claiming are blocked
+        assert_noop!(
+            GamePowerMarket::list(
+                Origin::signed(ALICE),
+                (CLASS_ID, 3),
+                1000,
+                None,
+                NATIVE_CURRENCY_ID
+            ),
+            Error::<Test>::ClassNonTradeable
+        );
+        assert_noop!(
+            GamePowerMarket::list_secret(Origin::signed(ALICE), (CLASS_ID, 3), 1000, 1),
+            Error::<Test>::ClassNonTradeable
+        );
+        assert_noop!(
+            GamePowerMarket::buy(Origin::signed(BOB), 0),
+            Error::<Test>::ClassNonTradeable
+        );
+        assert_noop!(
+            GamePowerMarket::buy_secret(Origin::signed(BOB), 0, 1000, 1),
+            Error::<Test>::ClassNonTradeable
+        );
+        assert_noop!(
+            GamePowerMarket::create_claim(Origin::signed(ALICE), BOB, (CLASS_ID, 3), None),
+            Error::<Test>::ClassNonTradeable
+        );
+        assert_noop!(
+            GamePowerMarket::claim(Origin::signed(BOB), 0),
+            Error::<Test>::ClassNonTradeable
+        );
+
+        // Transfers, burns and unlisting still work
+        assert_ok!(GamePowerMarket::transfer(
+            Origin::signed(ALICE),
+            BOB,
+            (CLASS_ID, 3)
+        ));
+        assert!(OrmlNFT::is_owner(&BOB, (CLASS_ID, 3)));
+        assert_ok!(GamePowerMarket::burn(Origin::signed(ALICE), (CLASS_ID, 4)));
+        assert!(!OrmlNFT::is_owner(&ALICE, (CLASS_ID, 4)));
+        assert_ok!(GamePowerMarket::unlist(Origin::signed(ALICE), 0));
+
+        // Resuming trading unblocks the claim
+        assert_ok!(GamePowerMarket::set_class_tradeable(
+            Origin::signed(ALICE),
+            CLASS_ID,
+            true
+        ));
+        assert!(!GamePowerMarket::non_tradeable(CLASS_ID));
+        assert_ok!(GamePowerMarket::claim(Origin::signed(BOB), 0));
+        assert!(OrmlNFT::is_owner(&BOB, (CLASS_ID, 2)));
+    });
+}
+
+#[test]
+fn escrow_balance_alert_should_respect_cooldown() {
+    new_test_ext().execute_with(|| {
+        // Create and list NFTs
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        for token_id in 0..4 {
+            assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+            assert_ok!(GamePowerMarket::list(
+                Origin::signed(ALICE),
+                (CLASS_ID, token_id),
+                1000,
+                None,
+                NATIVE_CURRENCY_ID
+            ));
+        }
+
+        let alerts = || {
+            System::events()
+                .into_iter()
+                .filter(|record| {
+                    matches!(
+                        record.event,
+                        Event::gamepower_market(RawEvent::MarketEscrowBalanceLow(..))
+                    )
+                })
+                .count()
+        };
+
+        // No alert while the escrow is funded
+        assert_ok!(GamePowerMarket::unlist(Origin::signed(ALICE), 0));
+        assert_eq!(alerts(), 0);
+
+        // Drain the escrow
+        let escrow_account = GamePowerMarket::get_escrow_account();
+        <Balances as Currency<AccountId>>::make_free_balance_be(&escrow_account, 600);
+
+        // Fires once within the cooldown window
+        assert_ok!(GamePowerMarket::buy(Origin::signed(BOB), 1));
+        assert_eq!(alerts(), 1);
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event
+                == Event::gamepower_market(RawEvent::MarketEscrowBalanceLow(600))));
+        assert_eq!(GamePowerMarket::last_escrow_alert(), Some(1));
+
+        System::set_block_number(1 + EscrowAlertCooldown::get() - 1);
+        assert_ok!(GamePowerMarket::unlist(Origin::signed(ALICE), 2));
+        assert_eq!(alerts(), 1);
+
+        // Fires again once the cooldown has passed
+        System::set_block_number(1 + EscrowAlertCooldown::get());
+        assert_ok!(GamePowerMarket::unlist(Origin::signed(ALICE), 3));
+        assert_eq!(alerts(), 2);
+    });
+}
+
+#[test]
+fn mint_block_should_be_recorded() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(GamePowerMarket::create_class(
+            Origin::signed(ALICE),
+            vec![1],
+            Default::default(),
+            None
+        ));
+
+        // First mint sets the class record
+        System::set_block_number(3);
+        assert_ok!(GamePowerMarket::mint(
+            Origin::signed(ALICE),
+            CLASS_ID,
+            vec![1],
+            Default::default(),
+            2
+        ));
+        assert_eq!(GamePowerMarket::get_mint_block((CLASS_ID, 0)), Some(3));
+        assert_eq!(GamePowerMarket::get_mint_block((CLASS_ID, 1)), Some(3));
+        assert_eq!(GamePowerMarket::class_first_mint_block(CLASS_ID), Some(3));
+
+        // Later mints don't move the class record
+        System::set_block_number(7);
+        assert_ok!(GamePowerMarket::airdrop(
+            Origin::signed(ALICE),
+            CLASS_ID,
+            vec![BOB],
+            vec![1],
+            Default::default()
+        ));
+        assert_eq!(GamePowerMarket::get_mint_block((CLASS_ID, 2)), Some(7));
+        assert_eq!(GamePowerMarket::class_first_mint_block(CLASS_ID), Some(3));
+
+        // Records outlive a burn
+        assert_ok!(GamePowerMarket::burn(Origin::signed(ALICE), (CLASS_ID, 0)));
+        assert_eq!(GamePowerMarket::get_mint_block((CLASS_ID, 0)), Some(3));
+        assert_eq!(GamePowerMarket::get_mint_block((CLASS_ID, 3)), None);
+    });
+}
+
+#[test]
+fn equip_should_lock_item() {
+    new_test_ext().execute_with(|| {
+        // Create NFTs: a character and an item
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&BOB, CLASS_ID, vec![1], Default::default()));
+        let character = (CLASS_ID, 0);
+        let item = (CLASS_ID, 1);
+
+        // Both assets must belong to the sender
+        assert_noop!(
+            GamePowerMarket::equip(Origin::signed(ALICE), (CLASS_ID, 2), character),
+            Error::<Test>::NoPermission
+        );
+        assert_noop!(
+            GamePowerMarket::equip(Origin::signed(ALICE), item, (CLASS_ID, 2)),
+            Error::<Test>::NoPermission
+        );
+        assert_noop!(
+            GamePowerMarket::equip(Origin::signed(ALICE), item, item),
+            Error::<Test>::InvalidEquipTarget
+        );
+
+        assert_ok!(GamePowerMarket::equip(
+            Origin::signed(ALICE),
+            item,
+            character
+        ));
+        assert_eq!(
+            OrmlNFT::tokens(CLASS_ID, 1).unwrap().data.equipped_to,
+            Some(character)
+        );
+        assert!(GamePowerMarket::is_locked(&item));
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketAssetEquipped(
+                ALICE, CLASS_ID, 1, CLASS_ID, 0
+            )))
+        );
+        assert_noop!(
+            GamePowerMarket::equip(Origin::signed(ALICE), item, character),
+            Error::<Test>::AlreadyEquipped
+        );
+
+        // Equipped items can't be moved
+        assert_noop!(
+            GamePowerMarket::transfer(Origin::signed(ALICE), BOB, item),
+            Error::<Test>::AssetLocked
+        );
+        assert_noop!(
+            GamePowerMarket::list(Origin::signed(ALICE), item, 1000, None, NATIVE_CURRENCY_ID),
+            Error::<Test>::AssetLocked
+        );
+        assert_noop!(
+            GamePowerMarket::create_claim(Origin::signed(ALICE), BOB, item, None),
+            Error::<Test>::AssetLocked
+        );
+
+        // Unequipping releases the item
+        assert_noop!(
+            GamePowerMarket::unequip(Origin::signed(BOB), item),
+            Error::<Test>::NoPermission
+        );
+        assert_ok!(GamePowerMarket::unequip(Origin::signed(ALICE), item));
+        assert_eq!(OrmlNFT::tokens(CLASS_ID, 1).unwrap().data.equipped_to, None);
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketAssetUnequipped(
+                ALICE, CLASS_ID, 1
+            )))
+        );
+        assert_noop!(
+            GamePowerMarket::unequip(Origin::signed(ALICE), item),
+            Error::<Test>::NotEquipped
+        );
+        assert_ok!(GamePowerMarket::transfer(Origin::signed(ALICE), BOB, item));
+        assert!(OrmlNFT::is_owner(&BOB, item));
+    });
+}
+
+#[test]
+fn serial_numbers_should_follow_mint_order() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(GamePowerMarket::create_class(
+            Origin::signed(ALICE),
+            vec![1],
+            Default::default(),
+            None
+        ));
+
+        // A token minted outside the market has no serial number
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_eq!(GamePowerMarket::get_serial_number((CLASS_ID, 0)), None);
+
+        assert_ok!(GamePowerMarket::mint(
+            Origin::signed(ALICE),
+            CLASS_ID,
+            vec![1],
+            Default::default(),
+            7
+        ));
+        assert_ok!(GamePowerMarket::airdrop(
+            Origin::signed(ALICE),
+            CLASS_ID,
+            vec![BOB, 3, 4],
+            vec![1],
+            Default::default()
+        ));
+
+        // Serials 0-9 map to tokens 1-10
+        for serial_number in 0..10u64 {
+            let token_id = serial_number + 1;
+            assert_eq!(
+                GamePowerMarket::get_serial_number((CLASS_ID, token_id)),
+                Some(serial_number)
+            );
+            assert_eq!(
+                GamePowerMarket::get_token_by_serial(CLASS_ID, serial_number),
+                Some(token_id)
+            );
+        }
+        assert_eq!(GamePowerMarket::get_token_by_serial(CLASS_ID, 10), None);
+        assert_eq!(GamePowerMarket::class_minted_count(CLASS_ID), 10);
+    });
+}
+
+#[test]
+fn get_all_claims_page_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create 100 claims
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        for token_id in 0..100 {
+            assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+            assert_ok!(GamePowerMarket::create_claim(
+                Origin::signed(ALICE),
+                BOB,
+                (CLASS_ID, token_id),
+                None
+            ));
+        }
+
+        // Walk the pages
+        let mut seen = Vec::new();
+        let mut cursor = None;
+        let mut pages = 0;
+        loop {
+            let (page, next_cursor) = GamePowerMarket::get_all_claims_page(cursor, 30);
+            assert!(page.len() <= 30);
+            for asset in page.iter() {
+                assert!(!seen.contains(asset), "pages overlap");
+            }
+            seen.extend(page);
+            pages += 1;
+            match next_cursor {
+                Some(_) => cursor = next_cursor,
+                None => break,
+            }
+        }
+        assert_eq!(pages, 4);
+
+        // Every claim is covered
+        seen.sort_unstable();
+        assert_eq!(
+            seen,
+            (0..100)
+                .map(|token_id| (CLASS_ID, token_id))
+                .collect::<Vec<_>>()
+        );
+
+        // An exact final page has no next cursor
+        let (page, next_cursor) = GamePowerMarket::get_all_claims_page(None, 100);
+        assert_eq!(page.len(), 100);
+        assert_eq!(next_cursor, None);
+
+        // Claimed assets drop out of the index
+        assert_ok!(GamePowerMarket::claim(Origin::signed(BOB), 0));
+        assert_eq!(GamePowerMarket::get_all_claims_page(None, 100).0.len(), 99);
+        assert!(!GamePowerMarket::claim_index((CLASS_ID, 0)));
+    });
+}
+
+#[test]
+fn creator_address_should_work() {
+    new_test_ext().execute_with(|| {
+        let class_data = |address: &[u8]| ClassData {
+            properties: vec![],
+            creator_address: address.to_vec(),
+            ..Default::default()
+        };
+
+        // Set on creation
+        assert_ok!(GamePowerMarket::create_class(
+            Origin::signed(ALICE),
+            vec![1],
+            class_data(b"gamepower.io"),
+            None
+        ));
+        assert_eq!(
+            GamePowerMarket::get_class_creator_address(CLASS_ID),
+            Some(b"gamepower.io".to_vec())
+        );
+        assert_noop!(
+            GamePowerMarket::create_class(
+                Origin::signed(ALICE),
+                vec![1],
+                class_data(&[0xff, 0xfe]),
+                None
+            ),
+            Error::<Test>::InvalidCreatorAddress
+        );
+        assert_noop!(
+            GamePowerMarket::create_class(
+                Origin::signed(ALICE),
+                vec![1],
+                class_data(&[b'a'; 17]),
+                None
+            ),
+            Error::<Test>::CreatorAddressTooLong
+        );
+
+        // Updated by the class owner only
+        assert_noop!(
+            GamePowerMarket::update_creator_address(
+                Origin::signed(BOB),
+                CLASS_ID,
+                b"bob.io".to_vec()
+            ),
+            Error::<Test>::NoPermission
+        );
+        assert_noop!(
+            GamePowerMarket::update_creator_address(
+                Origin::signed(ALICE),
+                CLASS_ID,
+                vec![b'a'; 17]
+            ),
+            Error::<Test>::CreatorAddressTooLong
+        );
+        assert_noop!(
+            GamePowerMarket::update_creator_address(
+                Origin::signed(ALICE),
+                CLASS_ID_NOT_EXIST,
+                b"a".to_vec()
+            ),
+            Error::<Test>::AssetNotFound
+        );
+        assert_ok!(GamePowerMarket::update_creator_address(
+            Origin::signed(ALICE),
+            CLASS_ID,
+            b"0x1234".to_vec()
+        ));
+        assert_eq!(
+            GamePowerMarket::get_class_creator_address(CLASS_ID),
+            Some(b"0x1234".to_vec())
+        );
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(
+                RawEvent::MarketCreatorAddressUpdated(CLASS_ID, b"0x1234".to_vec())
+            ))
+        );
+
+        // Clearing the address
+        assert_ok!(GamePowerMarket::update_creator_address(
+            Origin::signed(ALICE),
+            CLASS_ID,
+            vec![]
+        ));
+        assert_eq!(GamePowerMarket::get_class_creator_address(CLASS_ID), None);
+        assert_eq!(
+            GamePowerMarket::get_class_creator_address(CLASS_ID_NOT_EXIST),
+            None
+        );
+    });
+}
+
+#[test]
+fn recover_escrow_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create and list NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            1000,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+
+        // The asset goes missing from escrow
+        let escrow_account = GamePowerMarket::get_class_escrow_account(CLASS_ID);
+        assert_ok!(OrmlNFT::transfer(&escrow_account, &3, (CLASS_ID, TOKEN_ID)));
+
+        // Unlisting fails without touching the listing
+        assert_eq!(
+            GamePowerMarket::unlist(Origin::signed(ALICE), LISTING_ID),
+            Err(Error::<Test>::InsufficientEscrowBalance.into())
+        );
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(
+                RawEvent::MarketEscrowRecoveryFailed(LISTING_ID)
+            ))
+        );
+        assert!(GamePowerMarket::listings(LISTING_ID).is_some());
+        assert!(GamePowerMarket::is_locked(&(CLASS_ID, TOKEN_ID)));
+        assert_eq!(
+            GamePowerMarket::recover_escrow(Origin::root(), LISTING_ID),
+            Err(Error::<Test>::InsufficientEscrowBalance.into())
+        );
+
+        // Recovery is root only and needs the escrow replenished
+        assert_ok!(OrmlNFT::transfer(&3, &escrow_account, (CLASS_ID, TOKEN_ID)));
+        assert_noop!(
+            GamePowerMarket::recover_escrow(Origin::signed(ALICE), LISTING_ID),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(GamePowerMarket::recover_escrow(Origin::root(), LISTING_ID));
+        assert!(OrmlNFT::is_owner(&ALICE, (CLASS_ID, TOKEN_ID)));
+        assert!(GamePowerMarket::listings(LISTING_ID).is_none());
+        assert!(!GamePowerMarket::is_locked(&(CLASS_ID, TOKEN_ID)));
+        assert_noop!(
+            GamePowerMarket::recover_escrow(Origin::root(), LISTING_ID),
+            Error::<Test>::ListingNotFound
+        );
+    });
+}
+
+#[test]
+fn unlist_should_refund_from_treasury() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            1000,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+
+        // The asset ends up in the treasury instead of escrow
+        let escrow_account = GamePowerMarket::get_class_escrow_account(CLASS_ID);
+        let treasury_account = GamePowerMarket::get_treasury_account();
+        assert_ok!(OrmlNFT::transfer(
+            &escrow_account,
+            &treasury_account,
+            (CLASS_ID, TOKEN_ID)
+        ));
+
+        assert_ok!(GamePowerMarket::unlist(Origin::signed(ALICE), LISTING_ID));
+        assert!(OrmlNFT::is_owner(&ALICE, (CLASS_ID, TOKEN_ID)));
+        assert!(GamePowerMarket::listings(LISTING_ID).is_none());
+        assert!(!GamePowerMarket::is_locked(&(CLASS_ID, TOKEN_ID)));
+    });
+}
+
+#[test]
+fn unlist_secret_should_fail_without_escrow() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(GamePowerMarket::list_secret(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            100,
+            42
+        ));
+
+        // The asset goes missing from escrow
+        let escrow_account = GamePowerMarket::get_class_escrow_account(CLASS_ID);
+        assert_ok!(OrmlNFT::transfer(&escrow_account, &3, (CLASS_ID, TOKEN_ID)));
+
+        // The secret listing stays in place instead of being dropped
+        assert!(GamePowerMarket::unlist_secret(Origin::signed(ALICE), 0).is_err());
+        assert!(GamePowerMarket::secret_listings(0).is_some());
+        assert!(GamePowerMarket::is_locked(&(CLASS_ID, TOKEN_ID)));
+    });
+}
+
+#[test]
+fn get_token_detail_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(
+            GamePowerMarket::get_token_detail((CLASS_ID, TOKEN_ID)),
+            None
+        );
+
+        assert_ok!(GamePowerMarket::create_class(
+            Origin::signed(ALICE),
+            vec![1],
+            Default::default(),
+            None
+        ));
+        System::set_block_number(3);
+        assert_ok!(GamePowerMarket::mint(
+            Origin::signed(ALICE),
+            CLASS_ID,
+            vec![7],
+            Default::default(),
+            4
+        ));
+        let asset = (CLASS_ID, 1);
+
+        // Freshly minted
+        let detail = GamePowerMarket::get_token_detail(asset).unwrap();
+        assert_eq!(detail.owner, ALICE);
+        assert_eq!((detail.class_id, detail.token_id), asset);
+        assert_eq!(detail.metadata, vec![7]);
+        assert_eq!(detail.lock_reason, None);
+        assert!(detail.emote_counts.is_empty());
+        assert_eq!(detail.last_sale_price, None);
+        assert_eq!(detail.mint_block, Some(3));
+        assert_eq!(detail.serial_number, Some(1));
+
+        // Emotes are counted across accounts
+        assert_ok!(GamePowerMarket::emote(
+            Origin::signed(ALICE),
+            asset,
+            b"fish".to_vec()
+        ));
+        assert_ok!(GamePowerMarket::emote(
+            Origin::signed(BOB),
+            asset,
+            b"fish".to_vec()
+        ));
+        let fish = emojis::lookup("fish").unwrap().as_str().as_bytes().to_vec();
+        assert_eq!(
+            GamePowerMarket::get_token_detail(asset)
+                .unwrap()
+                .emote_counts,
+            vec![(fish, 2)]
+        );
+
+        // Listed
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            asset,
+            100,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_eq!(
+            GamePowerMarket::get_token_detail(asset)
+                .unwrap()
+                .lock_reason,
+            Some(LockReason::Listed)
+        );
+
+        // Sold
+        assert_ok!(GamePowerMarket::buy(Origin::signed(BOB), LISTING_ID));
+        let detail = GamePowerMarket::get_token_detail(asset).unwrap();
+        assert_eq!(detail.owner, BOB);
+        assert_eq!(detail.lock_reason, None);
+        assert_eq!(detail.last_sale_price, Some(100));
+        assert_eq!(detail.data.mint_count, 1);
+
+        // Claiming
+        assert_ok!(GamePowerMarket::create_claim(
+            Origin::signed(ALICE),
+            BOB,
+            (CLASS_ID, 0),
+            None
+        ));
+        assert_eq!(
+            GamePowerMarket::get_token_detail((CLASS_ID, 0))
+                .unwrap()
+                .lock_reason,
+            Some(LockReason::Claiming)
+        );
+
+        // Equipped
+        assert_ok!(GamePowerMarket::equip(
+            Origin::signed(ALICE),
+            (CLASS_ID, 2),
+            (CLASS_ID, 3)
+        ));
+        let detail = GamePowerMarket::get_token_detail((CLASS_ID, 2)).unwrap();
+        assert_eq!(detail.lock_reason, Some(LockReason::Equipped));
+        assert_eq!(detail.data.equipped_to, Some((CLASS_ID, 3)));
+    });
+}
+
+#[test]
+fn class_listing_stats_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFTs
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&BOB, CLASS_ID, vec![1], Default::default()));
+        assert_eq!(
+            GamePowerMarket::get_class_listing_stats(CLASS_ID),
+            Default::default()
+        );
+
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, 0),
+            100,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, 1),
+            300,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(BOB),
+            (CLASS_ID, 2),
+            200,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_eq!(
+            GamePowerMarket::get_class_listing_stats(CLASS_ID),
+            ClassListingStats {
+                active_listing_count: 3,
+                floor_price: Some(100),
+                highest_listing_price: Some(300),
+                volume_24h: 0,
+                sales_count: 0,
+                unique_sellers: 2,
+            }
+        );
+
+        // Unlisting the highest listing lowers the highest price
+        assert_ok!(GamePowerMarket::unlist(Origin::signed(ALICE), 1));
+        assert_eq!(GamePowerMarket::highest_listing_price(CLASS_ID), Some(200));
+
+        // A sale counts towards volume and sales
+        System::set_block_number(5);
+        assert_ok!(GamePowerMarket::buy(Origin::signed(BOB), 0));
+        assert_eq!(
+            GamePowerMarket::get_class_listing_stats(CLASS_ID),
+            ClassListingStats {
+                active_listing_count: 1,
+                floor_price: Some(200),
+                highest_listing_price: Some(200),
+                volume_24h: 100,
+                sales_count: 1,
+                unique_sellers: 1,
+            }
+        );
+
+        // Volume falls out of the window, sales don't
+        System::set_block_number(5 + BlocksPerDay::get());
+        assert_ok!(GamePowerMarket::unlist(Origin::signed(BOB), 2));
+        assert_eq!(
+            GamePowerMarket::get_class_listing_stats(CLASS_ID),
+            ClassListingStats {
+                active_listing_count: 0,
+                floor_price: None,
+                highest_listing_price: None,
+                volume_24h: 0,
+                sales_count: 1,
+                unique_sellers: 0,
+            }
+        );
+    });
+}
+
+#[test]
+fn games_should_work() {
+    new_test_ext().execute_with(|| {
+        let game_class = || ClassData {
+            game_id: 7,
+            ..Default::default()
+        };
+
+        // Classes can't join an unregistered game
+        assert_noop!(
+            GamePowerMarket::create_class(Origin::signed(ALICE), vec![1], game_class(), None),
+            Error::<Test>::GameNotRegistered
+        );
+
+        // Only governance registers games, and only once
+        let info = GameInfo {
+            name: b"Quest".to_vec(),
+            metadata: vec![1],
+        };
+        assert_noop!(
+            GamePowerMarket::register_game(Origin::signed(ALICE), 7, info.clone()),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            GamePowerMarket::register_game(Origin::root(), 0, info.clone()),
+            Error::<Test>::GameAlreadyRegistered
+        );
+        assert_ok!(GamePowerMarket::register_game(
+            Origin::root(),
+            7,
+            info.clone()
+        ));
+        assert_noop!(
+            GamePowerMarket::register_game(Origin::root(), 7, info.clone()),
+            Error::<Test>::GameAlreadyRegistered
+        );
+        assert_eq!(GamePowerMarket::registered_games(7), Some(info));
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketGameRegistered(7)))
+        );
+
+        // Classes 0 and 2 belong to the game, class 1 doesn't
+        assert_ok!(GamePowerMarket::create_class(
+            Origin::signed(ALICE),
+            vec![1],
+            game_class(),
+            None
+        ));
+        assert_ok!(GamePowerMarket::create_class(
+            Origin::signed(ALICE),
+            vec![1],
+            Default::default(),
+            None
+        ));
+        assert_ok!(GamePowerMarket::create_class(
+            Origin::signed(ALICE),
+            vec![1],
+            game_class(),
+            None
+        ));
+        assert_eq!(GamePowerMarket::get_classes_by_game(7, 0, 10), vec![0, 2]);
+        assert_eq!(GamePowerMarket::get_classes_by_game(7, 1, 1), vec![2]);
+        assert!(GamePowerMarket::get_classes_by_game(7, 2, 1).is_empty());
+
+        // Assets take the game of their class
+        assert_ok!(GamePowerMarket::mint(
+            Origin::signed(ALICE),
+            0,
+            vec![1],
+            Default::default(),
+            2
+        ));
+        assert_ok!(GamePowerMarket::mint(
+            Origin::signed(ALICE),
+            1,
+            vec![1],
+            Default::default(),
+            1
+        ));
+        assert_eq!(OrmlNFT::tokens(0, 0).unwrap().data.game_id, 7);
+        assert_eq!(OrmlNFT::tokens(1, 0).unwrap().data.game_id, 0);
+        assert!(AssetsByGame::<Test>::contains_key(7, (0, 0)));
+        assert!(AssetsByGame::<Test>::contains_key(7, (0, 1)));
+        assert!(!AssetsByGame::<Test>::contains_key(0, (1, 0)));
+
+        // Only listings in the game's classes are returned
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (1, 0),
+            100,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (0, 0),
+            100,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (0, 1),
+            200,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        let listing_ids = |page, limit| {
+            GamePowerMarket::get_active_listings_by_game(7, page, limit)
+                .into_iter()
+                .map(|listing| listing.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(listing_ids(0, 10), vec![1, 2]);
+        assert_eq!(listing_ids(1, 1), vec![2]);
+
+        // Burning removes the asset from the game
+        assert_ok!(GamePowerMarket::unlist(Origin::signed(ALICE), 2));
+        assert_ok!(GamePowerMarket::burn(Origin::signed(ALICE), (0, 1)));
+        assert!(!AssetsByGame::<Test>::contains_key(7, (0, 1)));
+        assert_eq!(listing_ids(0, 10), vec![1]);
+    });
+}
+
+#[test]
+fn token_tags_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFTs
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&BOB, CLASS_ID, vec![1], Default::default()));
+
+        // Tags are stored in lowercase
+        assert_ok!(GamePowerMarket::add_token_tag(
+            Origin::signed(ALICE),
+            (CLASS_ID, 0),
+            b"Sword".to_vec()
+        ));
+        assert_eq!(
+            GamePowerMarket::tags_by_token((CLASS_ID, 0)),
+            vec![b"sword".to_vec()]
+        );
+
+        // The class owner can tag assets it doesn't hold
+        assert_ok!(GamePowerMarket::add_token_tag(
+            Origin::signed(ALICE),
+            (CLASS_ID, 1),
+            b"sword".to_vec()
+        ));
+
+        // Searches match in any case
+        assert_eq!(
+            GamePowerMarket::search_tokens_by_tag(b"SWORD".to_vec(), 0, 10),
+            vec![(CLASS_ID, 0), (CLASS_ID, 1)]
+        );
+        assert_eq!(
+            GamePowerMarket::search_tokens_by_tag(b"sword".to_vec(), 1, 1),
+            vec![(CLASS_ID, 1)]
+        );
+        assert!(GamePowerMarket::search_tokens_by_tag(b"shield".to_vec(), 0, 10).is_empty());
+
+        // Removing takes the asset out of the search
+        assert_ok!(GamePowerMarket::remove_token_tag(
+            Origin::signed(ALICE),
+            (CLASS_ID, 1),
+            b"SWORD".to_vec()
+        ));
+        assert_eq!(
+            GamePowerMarket::search_tokens_by_tag(b"sword".to_vec(), 0, 10),
+            vec![(CLASS_ID, 0)]
+        );
+        assert!(GamePowerMarket::tags_by_token((CLASS_ID, 1)).is_empty());
+
+        // Burning drops the asset's tags
+        assert_ok!(GamePowerMarket::burn(Origin::signed(ALICE), (CLASS_ID, 0)));
+        assert!(GamePowerMarket::search_tokens_by_tag(b"sword".to_vec(), 0, 10).is_empty());
+    });
+}
+
+#[test]
+fn token_tags_should_fail() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        let asset = (CLASS_ID, TOKEN_ID);
+
+        // Only the class owner can tag
+        assert_noop!(
+            GamePowerMarket::add_token_tag(Origin::signed(BOB), asset, b"sword".to_vec()),
+            Error::<Test>::NoPermission
+        );
+        assert_noop!(
+            GamePowerMarket::add_token_tag(
+                Origin::signed(ALICE),
+                (CLASS_ID, TOKEN_ID_NOT_EXIST),
+                b"sword".to_vec()
+            ),
+            Error::<Test>::AssetNotFound
+        );
+
+        // Tag length
+        assert_noop!(
+            GamePowerMarket::add_token_tag(Origin::signed(ALICE), asset, vec![]),
+            Error::<Test>::InvalidTag
+        );
+        assert_noop!(
+            GamePowerMarket::add_token_tag(Origin::signed(ALICE), asset, b"longsword".to_vec()),
+            Error::<Test>::TagTooLong
+        );
+
+        // Duplicates are caught after lowercasing
+        assert_ok!(GamePowerMarket::add_token_tag(
+            Origin::signed(ALICE),
+            asset,
+            b"sword".to_vec()
+        ));
+        assert_noop!(
+            GamePowerMarket::add_token_tag(Origin::signed(ALICE), asset, b"SWORD".to_vec()),
+            Error::<Test>::TagAlreadyAdded
+        );
+
+        // Tag count
+        assert_ok!(GamePowerMarket::add_token_tag(
+            Origin::signed(ALICE),
+            asset,
+            b"rare".to_vec()
+        ));
+        assert_noop!(
+            GamePowerMarket::add_token_tag(Origin::signed(ALICE), asset, b"fire".to_vec()),
+            Error::<Test>::TooManyTags
+        );
+
+        // Removing
+        assert_noop!(
+            GamePowerMarket::remove_token_tag(Origin::signed(BOB), asset, b"sword".to_vec()),
+            Error::<Test>::NoPermission
+        );
+        assert_noop!(
+            GamePowerMarket::remove_token_tag(Origin::signed(ALICE), asset, b"fire".to_vec()),
+            Error::<Test>::TagNotFound
+        );
+    });
+}
+
+#[test]
+fn portfolio_value_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFTs
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        // Unlisted assets count as 0
+        assert_eq!(GamePowerMarket::estimate_portfolio_value(&ALICE), 0);
+
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, 0),
+            100,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, 1),
+            300,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_eq!(GamePowerMarket::estimate_portfolio_value(&ALICE), 400);
+
+        // Unlisting keeps the latest listing price
+        assert_ok!(GamePowerMarket::unlist(Origin::signed(ALICE), 1));
+        assert_eq!(GamePowerMarket::estimate_portfolio_value(&ALICE), 400);
+
+        // A sale moves the value to the buyer
+        assert_ok!(GamePowerMarket::buy(Origin::signed(BOB), 0));
+        assert_eq!(GamePowerMarket::estimate_portfolio_value(&ALICE), 300);
+        assert_eq!(GamePowerMarket::estimate_portfolio_value(&BOB), 100);
+
+        // Relisting replaces the old price
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(BOB),
+            (CLASS_ID, 0),
+            250,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_eq!(GamePowerMarket::estimate_portfolio_value(&BOB), 250);
+        assert_ok!(GamePowerMarket::buy(Origin::signed(ALICE), 2));
+        assert_eq!(GamePowerMarket::estimate_portfolio_value(&ALICE), 550);
+        assert_eq!(GamePowerMarket::estimate_portfolio_value(&BOB), 0);
+
+        // Burning removes the asset's value
+        assert_ok!(GamePowerMarket::burn(Origin::signed(ALICE), (CLASS_ID, 1)));
+        assert_eq!(GamePowerMarket::estimate_portfolio_value(&ALICE), 250);
+    });
+}
+
+#[test]
+fn portfolio_value_should_saturate() {
+    new_test_ext().execute_with(|| {
+        // Create NFTs
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, 0),
+            u64::MAX,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, 1),
+            u64::MAX,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_eq!(GamePowerMarket::estimate_portfolio_value(&ALICE), u64::MAX);
+    });
+}
+
+#[test]
+fn transfer_cooldown_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        let asset = (CLASS_ID, TOKEN_ID);
+
+        // First transfer between the pair has no cooldown
+        System::set_block_number(1);
+        assert_ok!(GamePowerMarket::transfer(Origin::signed(ALICE), BOB, asset));
+        assert_eq!(
+            GamePowerMarket::transfer_cooldown_map((ALICE, BOB), asset),
+            Some(1)
+        );
+
+        // The way back is a different pair
+        assert_ok!(GamePowerMarket::transfer(Origin::signed(BOB), ALICE, asset));
+
+        // Sending it the same way again is blocked until the cooldown passes
+        assert_noop!(
+            GamePowerMarket::transfer(Origin::signed(ALICE), BOB, asset),
+            Error::<Test>::TransferCooldownActive
+        );
+        System::set_block_number(1 + TransferCooldownPeriod::get() - 1);
+        assert_noop!(
+            GamePowerMarket::transfer(Origin::signed(ALICE), BOB, asset),
+            Error::<Test>::TransferCooldownActive
+        );
+
+        System::set_block_number(1 + TransferCooldownPeriod::get());
+        assert_ok!(GamePowerMarket::transfer(Origin::signed(ALICE), BOB, asset));
+        assert!(OrmlNFT::is_owner(&BOB, asset));
+    });
+}
+
+#[test]
+fn try_claim_all_should_work() {
+    new_test_ext().execute_with(|| {
+        // Claims 0 and 2 are in a tradeable class, claim 1 isn't
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, 0, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, 1, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, 0, vec![1], Default::default()));
+        for asset in [(0, 0), (1, 0), (0, 1)].iter() {
+            assert_ok!(GamePowerMarket::create_claim(
+                Origin::signed(ALICE),
+                BOB,
+                *asset,
+                None
+            ));
+        }
+        assert_ok!(GamePowerMarket::set_class_tradeable(
+            Origin::signed(ALICE),
+            1,
+            false
+        ));
+
+        // Partial success only charges the successful claims
+        let post_info = GamePowerMarket::try_claim_all(Origin::signed(BOB)).unwrap();
+        let claim_weight =
+            <() as WeightInfo>::claim(AllClaims::<Test>::decode_len().unwrap_or_default() as u32);
+        assert_eq!(post_info.actual_weight, Some(claim_weight * 2));
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketBatchClaimResult(
+                BOB,
+                vec![(0, true), (1, false), (2, true)]
+            )))
+        );
+        assert!(OrmlNFT::is_owner(&BOB, (0, 0)));
+        assert!(OrmlNFT::is_owner(&BOB, (0, 1)));
+        assert!(GamePowerMarket::open_claims(BOB, 1).is_some());
+        assert_eq!(GamePowerMarket::all_claims(), vec![(1, 0)]);
+
+        // Only failures left
+        let post_info = GamePowerMarket::try_claim_all(Origin::signed(BOB)).unwrap();
+        assert_eq!(post_info.actual_weight, Some(0));
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketBatchClaimResult(
+                BOB,
+                vec![(1, false)]
+            )))
+        );
+        assert!(GamePowerMarket::open_claims(BOB, 1).is_some());
+
+        // All succeed once the class is tradeable again
+        assert_ok!(GamePowerMarket::set_class_tradeable(
+            Origin::signed(ALICE),
+            1,
+            true
+        ));
+        let post_info = GamePowerMarket::try_claim_all(Origin::signed(BOB)).unwrap();
+        let claim_weight =
+            <() as WeightInfo>::claim(AllClaims::<Test>::decode_len().unwrap_or_default() as u32);
+        assert_eq!(post_info.actual_weight, Some(claim_weight));
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketBatchClaimResult(
+                BOB,
+                vec![(1, true)]
+            )))
+        );
+        assert!(OrmlNFT::is_owner(&BOB, (1, 0)));
+        assert!(GamePowerMarket::all_claims().is_empty());
+    });
+}
+
+#[test]
+fn pallet_metadata_should_work() {
+    new_test_ext().execute_with(|| {
+        // Set at genesis
+        assert_eq!(
+            GamePowerMarket::get_pallet_metadata(),
+            Some(MarketPalletInfo {
+                name: b"gamepower-market".to_vec(),
+                version: 1,
+                description: b"NFT wallet and marketplace".to_vec(),
+            })
+        );
+
+        let info = MarketPalletInfo {
+            name: b"gamepower-market".to_vec(),
+            version: 2,
+            description: b"Adds game ids".to_vec(),
+        };
+
+        // Only governance can update
+        assert_noop!(
+            GamePowerMarket::update_pallet_metadata(Origin::signed(ALICE), info.clone()),
+            DispatchError::BadOrigin
+        );
+
+        // Name and description are bounded
+        assert_noop!(
+            GamePowerMarket::update_pallet_metadata(
+                Origin::root(),
+                MarketPalletInfo {
+                    name: vec![b'a'; 65],
+                    ..info.clone()
+                }
+            ),
+            Error::<Test>::InvalidPalletMetadata
+        );
+        assert_noop!(
+            GamePowerMarket::update_pallet_metadata(
+                Origin::root(),
+                MarketPalletInfo {
+                    description: vec![b'a'; 257],
+                    ..info.clone()
+                }
+            ),
+            Error::<Test>::InvalidPalletMetadata
+        );
+
+        assert_ok!(GamePowerMarket::update_pallet_metadata(
+            Origin::root(),
+            info.clone()
+        ));
+        assert_eq!(GamePowerMarket::get_pallet_metadata(), Some(info));
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(
+                RawEvent::MarketPalletMetadataUpdated(2)
+            ))
+        );
+    });
+}
+
+#[test]
+fn auction_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        let asset = (CLASS_ID, TOKEN_ID);
+
+        assert_ok!(GamePowerMarket::create_auction(
+            Origin::signed(ALICE),
+            asset,
+            100,
+            300,
+            10
+        ));
+        assert_eq!(GamePowerMarket::next_auction_id(), 1);
+        assert!(GamePowerMarket::is_locked(&asset));
+        assert!(OrmlNFT::is_owner(
+            &GamePowerMarket::get_class_escrow_account(CLASS_ID),
+            asset
+        ));
+        assert_noop!(
+            GamePowerMarket::list(Origin::signed(ALICE), asset, 100, None, NATIVE_CURRENCY_ID),
+            Error::<Test>::NoPermission
+        );
+
+        // Bids must reach the starting price, then beat the highest bid
+        assert_noop!(
+            GamePowerMarket::bid(Origin::signed(BOB), 0, 50),
+            Error::<Test>::BidTooLow
+        );
+        assert_ok!(GamePowerMarket::bid(Origin::signed(BOB), 0, 100));
+        assert_noop!(
+            GamePowerMarket::bid(Origin::signed(3), 0, 100),
+            Error::<Test>::BidTooLow
+        );
+        assert_ok!(GamePowerMarket::bid(Origin::signed(3), 0, 200));
+        assert_noop!(
+            GamePowerMarket::bid(Origin::signed(ALICE), 0, 500),
+            Error::<Test>::CannotBidOnOwnAuction
+        );
+
+        // Raising a bid only reserves the difference
+        assert_ok!(GamePowerMarket::bid(Origin::signed(BOB), 0, 350));
+        assert_eq!(Balances::reserved_balance(BOB), 350);
+        assert_eq!(Balances::reserved_balance(3), 200);
+
+        // Settling waits for the end block, bidding stops at it
+        System::set_block_number(9);
+        assert_noop!(
+            GamePowerMarket::settle_auction(Origin::signed(3), 0),
+            Error::<Test>::AuctionNotEnded
+        );
+        System::set_block_number(10);
+        assert_noop!(
+            GamePowerMarket::bid(Origin::signed(3), 0, 400),
+            Error::<Test>::AuctionEnded
+        );
+
+        // Anyone can settle
+        assert_ok!(GamePowerMarket::settle_auction(Origin::signed(3), 0));
+        assert!(OrmlNFT::is_owner(&BOB, asset));
+        assert_eq!(Balances::free_balance(BOB), 1000000 - 350);
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(Balances::free_balance(3), 1000000);
+        assert_eq!(Balances::reserved_balance(3), 0);
+        assert_eq!(Balances::free_balance(ALICE), 1000000 + 350);
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketAuctionSettled(
+                ALICE, BOB, 0, 350
+            )))
+        );
+
+        // The auction is gone and the asset is free again
+        assert_eq!(GamePowerMarket::auctions(0), None);
+        assert!(!GamePowerMarket::is_locked(&asset));
+        assert_eq!(GamePowerMarket::bids_by_auction(0, BOB), 0);
+        assert_eq!(GamePowerMarket::token_price_history(asset), vec![(350, 10)]);
+        assert_noop!(
+            GamePowerMarket::settle_auction(Origin::signed(3), 0),
+            Error::<Test>::AuctionNotFound
+        );
+    });
+}
+
+#[test]
+fn auction_should_settle_a_bid_of_the_whole_balance() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        let asset = (CLASS_ID, TOKEN_ID);
+        assert_ok!(GamePowerMarket::create_auction(
+            Origin::signed(ALICE),
+            asset,
+            100,
+            100,
+            10
+        ));
+
+        // BOB bids every unit he has
+        assert_ok!(GamePowerMarket::bid(Origin::signed(BOB), 0, 1000000));
+        assert_eq!(Balances::free_balance(BOB), 0);
+
+        System::set_block_number(10);
+        assert_ok!(GamePowerMarket::settle_auction(Origin::signed(3), 0));
+        assert!(OrmlNFT::is_owner(&BOB, asset));
+        assert_eq!(Balances::total_balance(&BOB), 0);
+        assert_eq!(Balances::free_balance(ALICE), 1000000 + 1000000);
+        assert_eq!(GamePowerMarket::auctions(0), None);
+    });
+}
+
+#[test]
+fn auction_should_return_asset_when_payment_fails() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        let asset = (CLASS_ID, TOKEN_ID);
+        assert_ok!(GamePowerMarket::create_auction(
+            Origin::signed(ALICE),
+            asset,
+            100,
+            100,
+            10
+        ));
+        assert_ok!(GamePowerMarket::bid(Origin::signed(BOB), 0, 500));
+
+        // The bid is gone by the time the auction settles
+        let _ = <Balances as ReservableCurrency<_>>::slash_reserved(&BOB, 500);
+        Balances::make_free_balance_be(&BOB, 0);
+
+        System::set_block_number(10);
+        assert_ok!(GamePowerMarket::settle_auction(Origin::signed(3), 0));
+        assert!(OrmlNFT::is_owner(&ALICE, asset));
+        assert_eq!(Balances::free_balance(ALICE), 1000000);
+        assert_eq!(GamePowerMarket::auctions(0), None);
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketAuctionCancelled(0)))
+        );
+    });
+}
+
+#[test]
+fn auction_bid_history_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(GamePowerMarket::create_auction(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            100,
+            100,
+            10
+        ));
+
+        // Every bid is recorded in order
+        System::set_block_number(2);
+        assert_ok!(GamePowerMarket::bid(Origin::signed(BOB), 0, 100));
+        System::set_block_number(3);
+        assert_ok!(GamePowerMarket::bid(Origin::signed(3), 0, 200));
+        assert_ok!(GamePowerMarket::bid(Origin::signed(BOB), 0, 300));
+        assert_eq!(GamePowerMarket::auction_bid_count(0), 3);
+        assert_eq!(
+            GamePowerMarket::get_auction_bid_history(0),
+            vec![(BOB, 100, 2), (3, 200, 3), (BOB, 300, 3)]
+        );
+
+        // The cap is enforced after the bid checks
+        assert_noop!(
+            GamePowerMarket::bid(Origin::signed(3), 0, 300),
+            Error::<Test>::BidTooLow
+        );
+        assert_noop!(
+            GamePowerMarket::bid(Origin::signed(3), 0, 400),
+            Error::<Test>::TooManyBids
+        );
+
+        // The history is cleared with the auction
+        System::set_block_number(10);
+        assert_ok!(GamePowerMarket::settle_auction(Origin::signed(3), 0));
+        assert_eq!(GamePowerMarket::auction_bid_count(0), 0);
+        assert_eq!(GamePowerMarket::auction_bid_history(0, 0), None);
+        assert!(GamePowerMarket::get_auction_bid_history(0).is_empty());
+    });
+}
+
+#[test]
+fn auction_below_reserve_should_return_asset() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        let asset = (CLASS_ID, TOKEN_ID);
+
+        assert_noop!(
+            GamePowerMarket::create_auction(Origin::signed(ALICE), asset, 100, 300, 1),
+            Error::<Test>::InvalidAuctionEnd
+        );
+        assert_noop!(
+            GamePowerMarket::create_auction(Origin::signed(BOB), asset, 100, 300, 10),
+            Error::<Test>::NoPermission
+        );
+        assert_ok!(GamePowerMarket::create_auction(
+            Origin::signed(ALICE),
+            asset,
+            100,
+            300,
+            10
+        ));
+        assert_ok!(GamePowerMarket::bid(Origin::signed(BOB), 0, 200));
+
+        System::set_block_number(10);
+        assert_ok!(GamePowerMarket::settle_auction(Origin::signed(BOB), 0));
+        assert!(OrmlNFT::is_owner(&ALICE, asset));
+        assert_eq!(Balances::free_balance(BOB), 1000000);
+        assert_eq!(Balances::free_balance(ALICE), 1000000);
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketAuctionCancelled(0)))
+        );
+        assert!(!GamePowerMarket::is_locked(&asset));
+    });
+}
+
+#[test]
+fn cancel_auction_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFTs
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        assert_ok!(GamePowerMarket::create_auction(
+            Origin::signed(ALICE),
+            (CLASS_ID, 0),
+            100,
+            100,
+            10
+        ));
+        assert_ok!(GamePowerMarket::create_auction(
+            Origin::signed(ALICE),
+            (CLASS_ID, 1),
+            100,
+            100,
+            10
+        ));
+        assert_ok!(GamePowerMarket::bid(Origin::signed(BOB), 1, 100));
+
+        // Only the seller can cancel, and only without bids
+        assert_noop!(
+            GamePowerMarket::cancel_auction(Origin::signed(BOB), 0),
+            Error::<Test>::NoPermission
+        );
+        assert_noop!(
+            GamePowerMarket::cancel_auction(Origin::signed(ALICE), 1),
+            Error::<Test>::AuctionHasBids
+        );
+
+        assert_ok!(GamePowerMarket::cancel_auction(Origin::signed(ALICE), 0));
+        assert!(OrmlNFT::is_owner(&ALICE, (CLASS_ID, 0)));
+        assert_eq!(GamePowerMarket::auctions(0), None);
+        assert_eq!(GamePowerMarket::all_auctions(), vec![(CLASS_ID, 1)]);
+    });
+}
+
+#[test]
+fn cancel_auction_should_apply_early_unlist_penalty() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        for _ in 0..3 {
+            assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        }
+        let treasury = GamePowerMarket::get_treasury_account();
+        let treasury_balance = Balances::free_balance(treasury);
+
+        // The deposit is reserved while the auction is open
+        assert_ok!(GamePowerMarket::create_auction(
+            Origin::signed(ALICE),
+            (CLASS_ID, 0),
+            5000,
+            5000,
+            10
+        ));
+        assert_eq!(GamePowerMarket::auction_deposits(0), AuctionDeposit::get());
+        assert_eq!(Balances::reserved_balance(ALICE), 1000);
+
+        // 10% of the starting price goes to the treasury, the rest is returned
+        assert_ok!(GamePowerMarket::cancel_auction(Origin::signed(ALICE), 0));
+        assert!(System::events().iter().any(|record| record.event
+            == Event::gamepower_market(RawEvent::MarketEarlyUnlistPenaltyApplied(ALICE, 0, 500))));
+        assert_eq!(Balances::free_balance(treasury), treasury_balance + 500);
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        assert_eq!(Balances::free_balance(ALICE), 1000000 - 500);
+        assert_eq!(GamePowerMarket::auction_deposits(0), 0);
+
+        // The penalty never takes more than the deposit
+        assert_ok!(GamePowerMarket::create_auction(
+            Origin::signed(ALICE),
+            (CLASS_ID, 1),
+            20000,
+            20000,
+            10
+        ));
+        assert_ok!(GamePowerMarket::cancel_auction(Origin::signed(ALICE), 1));
+        assert!(System::events().iter().any(|record| record.event
+            == Event::gamepower_market(RawEvent::MarketEarlyUnlistPenaltyApplied(ALICE, 1, 1000))));
+        assert_eq!(Balances::free_balance(treasury), treasury_balance + 1500);
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        assert_eq!(Balances::free_balance(ALICE), 1000000 - 1500);
+
+        // Cancelling once the auction has ended returns the whole deposit
+        assert_ok!(GamePowerMarket::create_auction(
+            Origin::signed(ALICE),
+            (CLASS_ID, 2),
+            5000,
+            5000,
+            10
+        ));
+        System::set_block_number(10);
+        assert_ok!(GamePowerMarket::cancel_auction(Origin::signed(ALICE), 2));
+        assert_eq!(Balances::free_balance(treasury), treasury_balance + 1500);
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        assert_eq!(Balances::free_balance(ALICE), 1000000 - 1500);
+    });
+}
+
+#[test]
+fn royalty_should_work() {
+    new_test_ext().execute_with(|| {
+        let royalty_class = |rate| ClassData {
+            royalty_rate: Some(rate),
+            royalty_recipient: Some(3),
+            ..Default::default()
+        };
+
+        // Rate and recipient go together
+        assert_noop!(
+            GamePowerMarket::create_class(
+                Origin::signed(ALICE),
+                vec![1],
+                ClassData {
+                    royalty_rate: Some(Perbill::from_percent(10)),
+                    ..Default::default()
+                },
+                None
+            ),
+            Error::<Test>::InvalidRoyalty
+        );
+
+        // Class 0 pays 10%, class 1 pays nothing
+        assert_ok!(GamePowerMarket::create_class(
+            Origin::signed(ALICE),
+            vec![1],
+            royalty_class(Perbill::from_percent(10)),
+            None
+        ));
+        assert_ok!(GamePowerMarket::create_class(
+            Origin::signed(ALICE),
+            vec![1],
+            royalty_class(Perbill::zero()),
+            None
+        ));
+        assert_eq!(
+            GamePowerMarket::get_royalty(0),
+            Some((Perbill::from_percent(10), 3))
+        );
+        assert_ok!(OrmlNFT::mint(&ALICE, 0, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, 0, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, 1, vec![1], Default::default()));
+
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (0, 0),
+            1000,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::buy(Origin::signed(BOB), 0));
+        assert_eq!(Balances::free_balance(BOB), 1000000 - 1000);
+        assert_eq!(Balances::free_balance(3), 1000000 + 100);
+        assert_eq!(Balances::free_balance(ALICE), 1000000 + 900);
+
+        // A zero royalty pays the seller in full
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (1, 0),
+            1000,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::buy(Origin::signed(BOB), 1));
+        assert_eq!(Balances::free_balance(3), 1000000 + 100);
+        assert_eq!(Balances::free_balance(ALICE), 1000000 + 1900);
+
+        // The royalty only gets what the fee splits leave
+        assert_ok!(GamePowerMarket::set_fee_splits(
+            Origin::root(),
+            vec![(4, Perbill::from_percent(95))]
+        ));
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (0, 1),
+            1000,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::buy(Origin::signed(BOB), 2));
+        assert_eq!(Balances::free_balance(BOB), 1000000 - 3000);
+        assert_eq!(Balances::free_balance(4), 1000000 + 950);
+        assert_eq!(Balances::free_balance(3), 1000000 + 150);
+        assert_eq!(Balances::free_balance(ALICE), 1000000 + 1900);
+    });
+}
+
+#[test]
+fn batch_transfer_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFTs
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        let events_before = System::events().len();
+        assert_ok!(GamePowerMarket::batch_transfer(
+            Origin::signed(ALICE),
+            BOB,
+            vec![(CLASS_ID, 0), (CLASS_ID, 1), (CLASS_ID, 2)]
+        ));
+        for token_id in 0..3 {
+            assert!(OrmlNFT::is_owner(&BOB, (CLASS_ID, token_id)));
+        }
+
+        // One event for the whole batch
+        let events = System::events();
+        assert_eq!(events.len(), events_before + 1);
+        assert_eq!(
+            events.last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketBatchTransferred(
+                ALICE, BOB, 3
+            )))
+        );
+    });
+}
+
+#[test]
+fn batch_transfer_should_fail() {
+    new_test_ext().execute_with(|| {
+        // Create NFTs, the last one listed
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, 2),
+            100,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+
+        // A failure part way through moves nothing
+        assert_noop!(
+            GamePowerMarket::batch_transfer(
+                Origin::signed(ALICE),
+                BOB,
+                vec![(CLASS_ID, 0), (CLASS_ID, 1), (CLASS_ID, 2)]
+            ),
+            Error::<Test>::NoPermission
+        );
+        assert!(OrmlNFT::is_owner(&ALICE, (CLASS_ID, 0)));
+        assert!(OrmlNFT::is_owner(&ALICE, (CLASS_ID, 1)));
+
+        // Batches are capped by MaxBatchSize
+        assert_noop!(
+            GamePowerMarket::batch_transfer(Origin::signed(ALICE), BOB, vec![(CLASS_ID, 0); 4]),
+            Error::<Test>::BatchTooLarge
+        );
+    });
+}
+
+#[test]
+fn update_listing_price_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFTs
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, 0),
+            100,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, 1),
+            500,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+
+        assert_ok!(GamePowerMarket::update_listing_price(
+            Origin::signed(ALICE),
+            1,
+            200
+        ));
+        assert_eq!(GamePowerMarket::listings(1).unwrap().price, 200);
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(
+                RawEvent::MarketListingPriceUpdated(ALICE, 1, 500, 200)
+            ))
+        );
+
+        // The asset stays in escrow and class figures follow the new price
+        assert!(OrmlNFT::is_owner(
+            &GamePowerMarket::get_class_escrow_account(CLASS_ID),
+            (CLASS_ID, 1)
+        ));
+        assert_eq!(GamePowerMarket::highest_listing_price(CLASS_ID), Some(200));
+        assert_eq!(GamePowerMarket::estimate_portfolio_value(&ALICE), 300);
+
+        // Buyers pay the new price
+        assert_ok!(GamePowerMarket::buy(Origin::signed(BOB), 1));
+        assert_eq!(Balances::free_balance(BOB), 1000000 - 200);
+    });
+}
+
+#[test]
+fn update_listing_price_should_fail() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            100,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+
+        assert_noop!(
+            GamePowerMarket::update_listing_price(Origin::signed(ALICE), LISTING_ID_NOT_EXIST, 200),
+            Error::<Test>::ListingNotFound
+        );
+        assert_noop!(
+            GamePowerMarket::update_listing_price(Origin::signed(BOB), LISTING_ID, 200),
+            Error::<Test>::NoPermission
+        );
+    });
+}
+
+#[test]
+fn offer_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        let asset = (CLASS_ID, TOKEN_ID);
+
+        assert_ok!(GamePowerMarket::make_offer(
+            Origin::signed(BOB),
+            asset,
+            500,
+            10,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_eq!(Balances::reserved_balance(BOB), 500);
+        assert!(GamePowerMarket::offers(0).is_some());
+        assert!(OffersByAsset::<Test>::contains_key(asset, 0));
+
+        // Only the owner can accept
+        assert_noop!(
+            GamePowerMarket::accept_offer(Origin::signed(3), 0),
+            Error::<Test>::NoPermission
+        );
+
+        System::set_block_number(10);
+        assert_ok!(GamePowerMarket::accept_offer(Origin::signed(ALICE), 0));
+        assert!(OrmlNFT::is_owner(&BOB, asset));
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(Balances::free_balance(BOB), 1000000 - 500);
+        assert_eq!(Balances::free_balance(ALICE), 1000000 + 500);
+        assert_eq!(GamePowerMarket::offers(0), None);
+        assert!(!OffersByAsset::<Test>::contains_key(asset, 0));
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketOfferAccepted(
+                ALICE, BOB, 0, 500
+            )))
+        );
+    });
+}
+
+#[test]
+fn offer_currencies_should_be_independent() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        let asset = (CLASS_ID, TOKEN_ID);
+        set_token_balance(OTHER_CURRENCY_ID, 3, 1000);
+
+        // Offers can only be made in accepted currencies
+        assert_noop!(
+            GamePowerMarket::make_offer(Origin::signed(BOB), asset, 500, 10, 99),
+            Error::<Test>::CurrencyNotAccepted
+        );
+
+        // One offer in each currency on the same asset
+        assert_ok!(GamePowerMarket::make_offer(
+            Origin::signed(BOB),
+            asset,
+            500,
+            10,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::make_offer(
+            Origin::signed(3),
+            asset,
+            300,
+            10,
+            OTHER_CURRENCY_ID
+        ));
+        assert_eq!(Balances::reserved_balance(BOB), 500);
+        assert_eq!(token_balance(OTHER_CURRENCY_ID, 3), 700);
+        assert_eq!(reserved_token_balance(OTHER_CURRENCY_ID, 3), 300);
+        assert_eq!(
+            GamePowerMarket::offers(1).map(|offer| offer.currency_id),
+            Some(OTHER_CURRENCY_ID)
+        );
+
+        // Accepting the token offer pays in that token only
+        assert_ok!(GamePowerMarket::accept_offer(Origin::signed(ALICE), 1));
+        assert!(OrmlNFT::is_owner(&3, asset));
+        assert_eq!(reserved_token_balance(OTHER_CURRENCY_ID, 3), 0);
+        assert_eq!(token_balance(OTHER_CURRENCY_ID, 3), 700);
+        assert_eq!(token_balance(OTHER_CURRENCY_ID, ALICE), 300);
+        assert_eq!(Balances::free_balance(ALICE), 1000000);
+
+        // The native offer is untouched and releases its own reserve
+        assert!(GamePowerMarket::offers(0).is_some());
+        assert_eq!(Balances::reserved_balance(BOB), 500);
+        assert_ok!(GamePowerMarket::cancel_offer(Origin::signed(BOB), 0));
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(Balances::free_balance(BOB), 1000000);
+    });
+}
+
+#[test]
+fn price_update_should_invalidate_offers() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        let asset = (CLASS_ID, TOKEN_ID);
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            asset,
+            1000,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::make_offer(
+            Origin::signed(BOB),
+            asset,
+            500,
+            10,
+            NATIVE_CURRENCY_ID
+        ));
+
+        // Repricing the listing invalidates the offers on its asset
+        assert_ok!(GamePowerMarket::update_listing_price(
+            Origin::signed(ALICE),
+            LISTING_ID,
+            800
+        ));
+        assert!(InvalidatedOffers::contains_key(0));
+        assert!(System::events().iter().any(|record| record.event
+            == Event::gamepower_market(RawEvent::MarketOffersInvalidated(LISTING_ID, 1))));
+
+        // A stale offer can't be accepted
+        assert_noop!(
+            GamePowerMarket::accept_offer(Origin::signed(ALICE), 0),
+            Error::<Test>::OfferInvalidated
+        );
+
+        // Only the buyer can refresh it, reserving the new amount
+        assert_noop!(
+            GamePowerMarket::refresh_offer(Origin::signed(ALICE), 0, 700),
+            Error::<Test>::NoPermission
+        );
+        assert_ok!(GamePowerMarket::refresh_offer(Origin::signed(BOB), 0, 700));
+        assert!(!InvalidatedOffers::contains_key(0));
+        assert_eq!(Balances::reserved_balance(BOB), 700);
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketOfferRefreshed(
+                BOB, 0, 700
+            )))
+        );
+
+        // Only the seller of the listing can accept offers on the escrowed asset
+        assert_noop!(
+            GamePowerMarket::accept_offer(Origin::signed(3), 0),
+            Error::<Test>::NoPermission
+        );
+
+        // The refreshed offer goes through at its new amount, closing the listing
+        assert_ok!(GamePowerMarket::accept_offer(Origin::signed(ALICE), 0));
+        assert!(OrmlNFT::is_owner(&BOB, asset));
+        assert_eq!(GamePowerMarket::listings(LISTING_ID), None);
+        assert!(!GamePowerMarket::is_locked(&asset));
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(Balances::free_balance(ALICE), 1000000 + 700);
+    });
+}
+
+#[test]
+fn unlist_should_clear_invalidated_offers() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        let asset = (CLASS_ID, TOKEN_ID);
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            asset,
+            1000,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::make_offer(
+            Origin::signed(BOB),
+            asset,
+            500,
+            10,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::update_listing_price(
+            Origin::signed(ALICE),
+            LISTING_ID,
+            800
+        ));
+        assert!(InvalidatedOffers::contains_key(0));
+
+        // Without the listing there is no new price the offer missed
+        assert_ok!(GamePowerMarket::unlist(Origin::signed(ALICE), LISTING_ID));
+        assert!(!InvalidatedOffers::contains_key(0));
+        assert_ok!(GamePowerMarket::accept_offer(Origin::signed(ALICE), 0));
+        assert!(OrmlNFT::is_owner(&BOB, asset));
+        assert_eq!(Balances::free_balance(ALICE), 1000000 + 500);
+    });
+}
+
+#[test]
+fn offers_should_expire_on_initialize() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        let asset = (CLASS_ID, TOKEN_ID);
+        assert_ok!(GamePowerMarket::make_offer(
+            Origin::signed(BOB),
+            asset,
+            500,
+            10,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::make_offer(
+            Origin::signed(3),
+            asset,
+            300,
+            10,
+            NATIVE_CURRENCY_ID
+        ));
+        assert!(OfferExpiryQueue::<Test>::contains_key(11, 0));
+
+        // Withdrawn offers leave the queue
+        assert_ok!(GamePowerMarket::cancel_offer(Origin::signed(3), 1));
+        assert!(!OfferExpiryQueue::<Test>::contains_key(11, 1));
+
+        // Offers are released in the block after their expiry block
+        GamePowerMarket::on_initialize(10);
+        assert!(GamePowerMarket::offers(0).is_some());
+        GamePowerMarket::on_initialize(11);
+        assert_eq!(GamePowerMarket::offers(0), None);
+        assert!(!OffersByAsset::<Test>::contains_key(asset, 0));
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketOfferExpired(
+                BOB, 0, 500
+            )))
+        );
+
+        // on_finalize finds nothing left to release
+        let events = System::events().len();
+        GamePowerMarket::on_finalize(11);
+        assert_eq!(System::events().len(), events);
+    });
+}
+
+#[test]
+fn offer_expiries_over_the_cap_should_wait_for_finalize() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        for buyer in [BOB, 3, 4].iter() {
+            assert_ok!(GamePowerMarket::make_offer(
+                Origin::signed(*buyer),
+                (CLASS_ID, TOKEN_ID),
+                500,
+                10,
+                NATIVE_CURRENCY_ID
+            ));
+        }
+        MaxOfferExpiriesOnInitialize::set(1);
+
+        // on_initialize only releases up to the cap
+        GamePowerMarket::on_initialize(11);
+        assert_eq!(OfferExpiryQueue::<Test>::iter_prefix(11).count(), 2);
+        assert_eq!(
+            (0..3)
+                .filter(|offer_id| GamePowerMarket::offers(offer_id).is_some())
+                .count(),
+            2
+        );
+
+        // on_finalize releases what the cap left
+        GamePowerMarket::on_finalize(11);
+        assert_eq!(OfferExpiryQueue::<Test>::iter_prefix(11).count(), 0);
+        for (offer_id, buyer) in [BOB, 3, 4].iter().enumerate() {
+            assert_eq!(GamePowerMarket::offers(offer_id as u64), None);
+            assert_eq!(Balances::reserved_balance(*buyer), 0);
+        }
+    });
+}
+
+#[test]
+fn offers_should_expire_on_finalize() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(GamePowerMarket::make_offer(
+            Origin::signed(BOB),
+            (CLASS_ID, TOKEN_ID),
+            500,
+            10,
+            NATIVE_CURRENCY_ID
+        ));
+
+        // on_initialize is skipped for the block, on_finalize still releases the offer
+        GamePowerMarket::on_finalize(11);
+        assert_eq!(GamePowerMarket::offers(0), None);
+        assert!(!OfferExpiryQueue::<Test>::contains_key(11, 0));
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketOfferExpired(
+                BOB, 0, 500
+            )))
+        );
+    });
+}
+
+#[test]
+fn offers_by_buyer_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        for _ in 0..3 {
+            assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        }
+        for token_id in 0..3 {
+            assert_ok!(GamePowerMarket::make_offer(
+                Origin::signed(BOB),
+                (CLASS_ID, token_id),
+                100,
+                10 + token_id,
+                NATIVE_CURRENCY_ID
+            ));
+        }
+        assert_eq!(GamePowerMarket::offers_given(BOB), 3);
+        let offer_ids = |page, limit| {
+            GamePowerMarket::get_offers_by_buyer(&BOB, page, limit)
+                .into_iter()
+                .map(|(offer_id, _)| offer_id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(offer_ids(0, 2), vec![0, 1]);
+        assert_eq!(offer_ids(1, 2), vec![2]);
+        assert_eq!(
+            GamePowerMarket::get_offers_by_buyer(&BOB, 0, 1),
+            vec![(0, GamePowerMarket::offers(0).unwrap())]
+        );
+
+        // Accepting removes the offer from the buyer's index
+        assert_ok!(GamePowerMarket::accept_offer(Origin::signed(ALICE), 0));
+        assert_eq!(offer_ids(0, 10), vec![1, 2]);
+        assert_eq!(GamePowerMarket::offers_given(BOB), 2);
+
+        // So does cancelling
+        assert_ok!(GamePowerMarket::cancel_offer(Origin::signed(BOB), 1));
+        assert_eq!(offer_ids(0, 10), vec![2]);
+        assert_eq!(GamePowerMarket::offers_given(BOB), 1);
+
+        // And expiry
+        GamePowerMarket::on_initialize(13);
+        assert!(offer_ids(0, 10).is_empty());
+        assert_eq!(GamePowerMarket::offers_given(BOB), 0);
+    });
+}
+
+#[test]
+fn offer_should_fail() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        let asset = (CLASS_ID, TOKEN_ID);
+
+        System::set_block_number(5);
+        assert_noop!(
+            GamePowerMarket::make_offer(Origin::signed(BOB), asset, 500, 4, NATIVE_CURRENCY_ID),
+            Error::<Test>::InvalidOfferExpiry
+        );
+        assert_noop!(
+            GamePowerMarket::make_offer(Origin::signed(ALICE), asset, 500, 10, NATIVE_CURRENCY_ID),
+            Error::<Test>::CannotOfferOnOwnAsset
+        );
+        assert_noop!(
+            GamePowerMarket::make_offer(
+                Origin::signed(BOB),
+                (CLASS_ID, TOKEN_ID_NOT_EXIST),
+                500,
+                10,
+                NATIVE_CURRENCY_ID
+            ),
+            Error::<Test>::AssetNotFound
+        );
+
+        // Expired offers can't be accepted
+        assert_ok!(GamePowerMarket::make_offer(
+            Origin::signed(BOB),
+            asset,
+            500,
+            10,
+            NATIVE_CURRENCY_ID
+        ));
+        System::set_block_number(11);
+        assert_noop!(
+            GamePowerMarket::accept_offer(Origin::signed(ALICE), 0),
+            Error::<Test>::OfferExpired
+        );
+        assert_noop!(
+            GamePowerMarket::accept_offer(Origin::signed(ALICE), 1),
+            Error::<Test>::OfferNotFound
+        );
+    });
+}
+
+#[test]
+fn cancel_and_cleanup_offer_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        let asset = (CLASS_ID, TOKEN_ID);
+
+        assert_ok!(GamePowerMarket::make_offer(
+            Origin::signed(BOB),
+            asset,
+            500,
+            10,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::make_offer(
+            Origin::signed(3),
+            asset,
+            300,
+            10,
+            NATIVE_CURRENCY_ID
+        ));
+
+        // Only the buyer can cancel
+        assert_noop!(
+            GamePowerMarket::cancel_offer(Origin::signed(ALICE), 0),
+            Error::<Test>::NoPermission
+        );
+        assert_ok!(GamePowerMarket::cancel_offer(Origin::signed(BOB), 0));
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(GamePowerMarket::offers(0), None);
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketOfferCancelled(0)))
+        );
+
+        // Anyone can clean up once the offer has expired
+        assert_noop!(
+            GamePowerMarket::cleanup_offer(Origin::signed(ALICE), 1),
+            Error::<Test>::OfferNotExpired
+        );
+        System::set_block_number(11);
+        assert_ok!(GamePowerMarket::cleanup_offer(Origin::signed(ALICE), 1));
+        assert_eq!(Balances::reserved_balance(3), 0);
+        assert_eq!(GamePowerMarket::offers(1), None);
+        assert!(!OffersByAsset::<Test>::contains_key(asset, 1));
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketOfferExpired(
+                3, 1, 300
+            )))
+        );
+    });
+}
+
+#[test]
+fn listing_expiry_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        System::set_block_number(5);
+        assert_noop!(
+            GamePowerMarket::list(
+                Origin::signed(ALICE),
+                (CLASS_ID, TOKEN_ID),
+                100,
+                Some(4),
+                NATIVE_CURRENCY_ID
+            ),
+            Error::<Test>::InvalidListingExpiry
+        );
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            100,
+            Some(10),
+            NATIVE_CURRENCY_ID
+        ));
+        assert_eq!(GamePowerMarket::listings(0).unwrap().expiry_block, Some(10));
+
+        // Can't expire a listing before its expiry block
+        System::set_block_number(10);
+        assert_noop!(
+            GamePowerMarket::expire_listing(Origin::none(), 0),
+            Error::<Test>::ListingNotExpired
+        );
+
+        System::set_block_number(11);
+        assert_noop!(
+            GamePowerMarket::buy(Origin::signed(BOB), 0),
+            Error::<Test>::ListingExpired
+        );
+        assert_noop!(
+            GamePowerMarket::expire_listing(Origin::signed(BOB), 0),
+            DispatchError::BadOrigin
+        );
+
+        // The asset goes back to the seller
+        assert_ok!(GamePowerMarket::expire_listing(Origin::none(), 0));
+        assert!(OrmlNFT::is_owner(&ALICE, (CLASS_ID, TOKEN_ID)));
+        assert_eq!(GamePowerMarket::listings(0), None);
+        assert!(GamePowerMarket::all_listings().is_empty());
+        assert!(!GamePowerMarket::is_locked(&(CLASS_ID, TOKEN_ID)));
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketListingExpired(
+                ALICE, 0, CLASS_ID, TOKEN_ID
+            )))
+        );
+    });
+}
+
+#[test]
+fn expire_listing_validate_unsigned_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            100,
+            Some(10),
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID + 1),
+            100,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+
+        let expire = |listing_id| {
+            GamePowerMarket::validate_unsigned(
+                TransactionSource::Local,
+                &crate::Call::expire_listing(listing_id),
+            )
+        };
+
+        assert_eq!(expire(0), InvalidTransaction::Future.into());
+        System::set_block_number(11);
+        assert!(expire(0).is_ok());
+        assert_eq!(expire(1), InvalidTransaction::Future.into());
+        assert_eq!(expire(2), InvalidTransaction::Stale.into());
+        assert_eq!(
+            GamePowerMarket::validate_unsigned(TransactionSource::Local, &crate::Call::unlist(0)),
+            InvalidTransaction::Call.into()
+        );
+    });
+}
+
+#[test]
+fn offchain_worker_should_expire_listings() {
+    let mut ext = new_test_ext();
+    let (pool, pool_state) = TestTransactionPoolExt::new();
+    ext.register_extension(TransactionPoolExt::new(pool));
+
+    ext.execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            100,
+            Some(10),
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID + 1),
+            100,
+            Some(20),
+            NATIVE_CURRENCY_ID
+        ));
+
+        GamePowerMarket::offchain_worker(10);
+        assert!(pool_state.read().transactions.is_empty());
+
+        // Only the expired listing is submitted
+        GamePowerMarket::offchain_worker(11);
+        let tx = pool_state.write().transactions.pop().unwrap();
+        assert!(pool_state.read().transactions.is_empty());
+        let tx = frame_system::mocking::MockUncheckedExtrinsic::<Test>::decode(&mut &*tx).unwrap();
+        assert_eq!(tx.signature, None);
+        assert_eq!(
+            tx.function,
+            Call::GamePowerMarket(crate::Call::expire_listing(0))
+        );
+    });
+}
+
+#[test]
+fn buy_with_marketplace_fee_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        MarketplaceFeeRate::set(Perbill::from_percent(5));
+
+        // 10% to account 3 on top of the marketplace fee
+        assert_ok!(GamePowerMarket::set_fee_splits(
+            Origin::root(),
+            vec![(3, Perbill::from_percent(10))]
+        ));
+
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            1000,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::buy(Origin::signed(BOB), LISTING_ID));
+        assert!(System::events().iter().any(|record| record.event
+            == Event::gamepower_market(RawEvent::MarketFeeCollected(50, FeeRecipient::get()))));
+
+        // Check Balances
+        assert_eq!(Balances::free_balance(BOB), 1000000 - 1000);
+        assert_eq!(Balances::free_balance(FeeRecipient::get()), 1000000 + 50);
+        assert_eq!(Balances::free_balance(3), 1000000 + 100);
+        assert_eq!(Balances::free_balance(ALICE), 1000000 + 850);
+    });
+}
+
+#[test]
+fn bundle_listing_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFTs in two classes
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(
+            &ALICE,
+            CLASS_ID + 1,
+            vec![1],
+            Default::default()
+        ));
+        let assets = vec![(CLASS_ID, TOKEN_ID), (CLASS_ID + 1, TOKEN_ID)];
+
+        assert_ok!(GamePowerMarket::list_bundle(
+            Origin::signed(ALICE),
+            assets.clone(),
+            1001
+        ));
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketBundleListed(
+                ALICE,
+                0,
+                assets.clone(),
+                1001
+            )))
+        );
+        assert_eq!(GamePowerMarket::all_listings(), assets);
+        for asset in assets.iter() {
+            assert!(GamePowerMarket::is_locked(asset));
+            assert!(OrmlNFT::is_owner(
+                &GamePowerMarket::get_class_escrow_account(asset.0),
+                *asset
+            ));
+        }
+
+        assert_ok!(GamePowerMarket::buy_bundle(Origin::signed(BOB), 0));
+        for asset in assets.iter() {
+            assert!(OrmlNFT::is_owner(&BOB, *asset));
+            assert!(!GamePowerMarket::is_locked(asset));
+        }
+        assert_eq!(GamePowerMarket::bundle_listings(0), None);
+        assert!(GamePowerMarket::all_listings().is_empty());
+        assert_eq!(Balances::free_balance(BOB), 1000000 - 1001);
+        assert_eq!(Balances::free_balance(ALICE), 1000000 + 1001);
+
+        // The price is split evenly between the assets, the last one takes the rest
+        assert_eq!(GamePowerMarket::class_last_sale_price(CLASS_ID), Some(500));
+        assert_eq!(
+            GamePowerMarket::class_last_sale_price(CLASS_ID + 1),
+            Some(501)
+        );
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketBundlePurchased(
+                ALICE, BOB, 0, 1001
+            )))
+        );
+    });
+}
+
+#[test]
+fn partial_buy_bundle_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        for _ in 0..3 {
+            assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        }
+        let assets = vec![(CLASS_ID, 0), (CLASS_ID, 1), (CLASS_ID, 2)];
+        assert_ok!(GamePowerMarket::list_bundle(
+            Origin::signed(ALICE),
+            assets.clone(),
+            1001
+        ));
+
+        // The selection is checked as a whole
+        assert_noop!(
+            GamePowerMarket::partial_buy_bundle(Origin::signed(BOB), 0, vec![]),
+            Error::<Test>::EmptyBundle
+        );
+        assert_noop!(
+            GamePowerMarket::partial_buy_bundle(Origin::signed(BOB), 0, vec![0, 3]),
+            Error::<Test>::AssetIndexOutOfBounds
+        );
+        assert_noop!(
+            GamePowerMarket::partial_buy_bundle(Origin::signed(BOB), 0, vec![1, 0, 1]),
+            Error::<Test>::DuplicateAssetIndex
+        );
+
+        // Two of three assets at a third of the price each
+        assert_ok!(GamePowerMarket::partial_buy_bundle(
+            Origin::signed(BOB),
+            0,
+            vec![2, 0]
+        ));
+        assert!(OrmlNFT::is_owner(&BOB, assets[0]));
+        assert!(OrmlNFT::is_owner(&BOB, assets[2]));
+        assert!(!GamePowerMarket::is_locked(&assets[0]));
+        assert!(GamePowerMarket::is_locked(&assets[1]));
+        assert_eq!(Balances::free_balance(BOB), 1000000 - 666);
+        assert_eq!(Balances::free_balance(ALICE), 1000000 + 666);
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(
+                RawEvent::MarketBundlePartiallyPurchased(ALICE, BOB, 0, 2, 666)
+            ))
+        );
+
+        // The rest of the bundle stays listed at the rest of the price
+        let bundle = GamePowerMarket::bundle_listings(0).unwrap();
+        assert_eq!(bundle.assets, vec![assets[1]]);
+        assert_eq!(bundle.price, 335);
+        assert_eq!(GamePowerMarket::all_listings(), vec![assets[1]]);
+
+        // Buying what is left removes the bundle
+        assert_ok!(GamePowerMarket::partial_buy_bundle(
+            Origin::signed(3),
+            0,
+            vec![0]
+        ));
+        assert!(OrmlNFT::is_owner(&3, assets[1]));
+        assert_eq!(Balances::free_balance(ALICE), 1000000 + 1001);
+        assert_eq!(GamePowerMarket::bundle_listings(0), None);
+        assert!(GamePowerMarket::all_listings().is_empty());
+    });
+}
+
+#[test]
+fn partial_buy_bundle_should_be_atomic() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        for _ in 0..3 {
+            assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        }
+        let assets = vec![(CLASS_ID, 0), (CLASS_ID, 1), (CLASS_ID, 2)];
+        assert_ok!(GamePowerMarket::list_bundle(
+            Origin::signed(ALICE),
+            assets.clone(),
+            1800000
+        ));
+
+        // BOB can pay for the first asset but not the second
+        assert!(GamePowerMarket::partial_buy_bundle(Origin::signed(BOB), 0, vec![0, 1]).is_err());
+
+        // Nothing was consumed
+        assert_eq!(Balances::free_balance(BOB), 1000000);
+        assert_eq!(Balances::free_balance(ALICE), 1000000);
+        let bundle = GamePowerMarket::bundle_listings(0).unwrap();
+        assert_eq!(bundle.assets, assets);
+        assert_eq!(bundle.price, 1800000);
+        for asset in assets.iter() {
+            assert!(GamePowerMarket::is_locked(asset));
+            assert!(OrmlNFT::is_owner(
+                &GamePowerMarket::get_class_escrow_account(asset.0),
+                *asset
+            ));
+        }
+    });
+}
+
+#[test]
+fn unlist_bundle_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFTs
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        let assets = vec![(CLASS_ID, TOKEN_ID), (CLASS_ID, TOKEN_ID + 1)];
+
+        assert_ok!(GamePowerMarket::list_bundle(
+            Origin::signed(ALICE),
+            assets.clone(),
+            1000
+        ));
+
+        // Only the seller can unlist
+        assert_noop!(
+            GamePowerMarket::unlist_bundle(Origin::signed(BOB), 0),
+            Error::<Test>::NoPermission
+        );
+        assert_ok!(GamePowerMarket::unlist_bundle(Origin::signed(ALICE), 0));
+        for asset in assets.iter() {
+            assert!(OrmlNFT::is_owner(&ALICE, *asset));
+            assert!(!GamePowerMarket::is_locked(asset));
+        }
+        assert_eq!(GamePowerMarket::listing_count(), 0);
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketBundleUnlisted(
+                ALICE, 0
+            )))
+        );
+        assert_noop!(
+            GamePowerMarket::buy_bundle(Origin::signed(BOB), 0),
+            Error::<Test>::BundleListingNotFound
+        );
+    });
+}
+
+#[test]
+fn bundle_listing_should_fail() {
+    new_test_ext().execute_with(|| {
+        // Create NFTs
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&BOB, CLASS_ID, vec![1], Default::default()));
+
+        assert_noop!(
+            GamePowerMarket::list_bundle(Origin::signed(ALICE), vec![], 1000),
+            Error::<Test>::EmptyBundle
+        );
+        assert_noop!(
+            GamePowerMarket::list_bundle(
+                Origin::signed(ALICE),
+                vec![(CLASS_ID, TOKEN_ID), (CLASS_ID, TOKEN_ID + 2)],
+                1000
+            ),
+            Error::<Test>::NoPermission
+        );
+
+        // Equipped items can't be bundled
+        assert_ok!(GamePowerMarket::equip(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID + 1),
+            (CLASS_ID, TOKEN_ID)
+        ));
+        assert_noop!(
+            GamePowerMarket::list_bundle(
+                Origin::signed(ALICE),
+                vec![(CLASS_ID, TOKEN_ID), (CLASS_ID, TOKEN_ID + 1)],
+                1000
+            ),
+            Error::<Test>::AssetLocked
+        );
+
+        // A failed purchase leaves the bundle in place
+        assert_ok!(GamePowerMarket::list_bundle(
+            Origin::signed(ALICE),
+            vec![(CLASS_ID, TOKEN_ID)],
+            2000000
+        ));
+        assert!(GamePowerMarket::buy_bundle(Origin::signed(BOB), 0).is_err());
+        assert!(GamePowerMarket::bundle_listings(0).is_some());
+        assert!(GamePowerMarket::is_locked(&(CLASS_ID, TOKEN_ID)));
+    });
+}
+
+#[test]
+fn list_and_buy_handlers_should_be_called() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            100,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_eq!(ListHookCalls::get(), 1);
+        assert_eq!(BuyHookCalls::get(), 0);
+
+        assert_ok!(GamePowerMarket::buy(Origin::signed(BOB), LISTING_ID));
+        assert_eq!(ListHookCalls::get(), 1);
+        assert_eq!(BuyHookCalls::get(), 1);
+    });
+}
+
+#[test]
+fn list_and_buy_handlers_should_cover_secret_and_bundle_listings() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        for _ in 0..4 {
+            assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        }
+
+        assert_ok!(GamePowerMarket::list_secret(
+            Origin::signed(ALICE),
+            (CLASS_ID, 0),
+            100,
+            42
+        ));
+        assert_eq!(ListHookCalls::get(), 1);
+        assert_ok!(GamePowerMarket::buy_secret(Origin::signed(BOB), 0, 100, 42));
+        assert_eq!(BuyHookCalls::get(), 1);
+
+        // Every asset of a bundle is listed and bought on its own
+        assert_ok!(GamePowerMarket::list_bundle(
+            Origin::signed(ALICE),
+            vec![(CLASS_ID, 1), (CLASS_ID, 2), (CLASS_ID, 3)],
+            300
+        ));
+        assert_eq!(ListHookCalls::get(), 4);
+        assert_ok!(GamePowerMarket::partial_buy_bundle(
+            Origin::signed(BOB),
+            0,
+            vec![0]
+        ));
+        assert_eq!(BuyHookCalls::get(), 2);
+        assert_ok!(GamePowerMarket::buy_bundle(Origin::signed(BOB), 0));
+        assert_eq!(BuyHookCalls::get(), 4);
+    });
+}
+
+#[test]
+fn soulbound_asset_should_not_move() {
+    new_test_ext().execute_with(|| {
+        // Create a soulbound NFT and a regular one
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(
+            &ALICE,
+            CLASS_ID,
+            vec![1],
+            AssetData {
+                soulbound: true,
+                ..Default::default()
+            }
+        ));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        let soulbound = (CLASS_ID, TOKEN_ID);
+        assert!(GamePowerMarket::is_soulbound(&soulbound));
+
+        assert_noop!(
+            GamePowerMarket::transfer(Origin::signed(ALICE), BOB, soulbound),
+            Error::<Test>::SoulboundAsset
+        );
+        assert_noop!(
+            GamePowerMarket::list(
+                Origin::signed(ALICE),
+                soulbound,
+                100,
+                None,
+                NATIVE_CURRENCY_ID
+            ),
+            Error::<Test>::SoulboundAsset
+        );
+        assert_noop!(
+            GamePowerMarket::list_secret(Origin::signed(ALICE), soulbound, 100, 1),
+            Error::<Test>::SoulboundAsset
+        );
+        assert_noop!(
+            GamePowerMarket::create_claim(Origin::signed(ALICE), BOB, soulbound, None),
+            Error::<Test>::SoulboundAsset
+        );
+        assert_noop!(
+            GamePowerMarket::create_auction(Origin::signed(ALICE), soulbound, 100, 100, 10),
+            Error::<Test>::SoulboundAsset
+        );
+
+        // Regular assets still move
+        assert_ok!(GamePowerMarket::transfer(
+            Origin::signed(ALICE),
+            BOB,
+            (CLASS_ID, TOKEN_ID + 1)
+        ));
+        assert!(OrmlNFT::is_owner(&BOB, (CLASS_ID, TOKEN_ID + 1)));
+
+        // Soulbound assets can still be burned
+        assert_ok!(GamePowerMarket::burn(Origin::signed(ALICE), soulbound));
+        assert_eq!(OrmlNFT::tokens(CLASS_ID, TOKEN_ID), None);
+    });
+}
+
+#[test]
+fn batch_burn_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFTs
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        assert_ok!(GamePowerMarket::batch_burn(
+            Origin::signed(ALICE),
+            vec![(CLASS_ID, TOKEN_ID), (CLASS_ID, TOKEN_ID + 1)]
+        ));
+        assert_eq!(OrmlNFT::tokens(CLASS_ID, TOKEN_ID), None);
+        assert_eq!(OrmlNFT::tokens(CLASS_ID, TOKEN_ID + 1), None);
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketBatchBurned(
+                ALICE, 2
+            )))
+        );
+    });
+}
+
+#[test]
+fn batch_burn_should_fail() {
+    new_test_ext().execute_with(|| {
+        // Create NFTs
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&BOB, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        // The first asset fails, so the second one isn't burned either
+        assert_noop!(
+            GamePowerMarket::batch_burn(
+                Origin::signed(ALICE),
+                vec![(CLASS_ID, TOKEN_ID), (CLASS_ID, TOKEN_ID + 1)]
+            ),
+            Error::<Test>::NoPermission
+        );
+        assert!(OrmlNFT::is_owner(&ALICE, (CLASS_ID, TOKEN_ID + 1)));
+
+        assert_noop!(
+            GamePowerMarket::batch_burn(Origin::signed(ALICE), vec![(CLASS_ID, TOKEN_ID + 1); 4]),
+            Error::<Test>::BatchTooLarge
+        );
+    });
+}
+
+#[test]
+fn order_history_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        let asset = (CLASS_ID, TOKEN_ID);
+
+        // Sold by ALICE to BOB, then back to ALICE
+        System::set_block_number(3);
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            asset,
+            100,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::buy(Origin::signed(BOB), 0));
+        System::set_block_number(7);
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(BOB),
+            asset,
+            200,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::buy(Origin::signed(ALICE), 1));
+
+        assert_eq!(GamePowerMarket::orders_by_asset(asset), vec![0, 1]);
+        assert_eq!(GamePowerMarket::next_order_id(), 2);
+        assert_eq!(
+            GamePowerMarket::get_order_history(asset, 0, 10),
+            vec![
+                Order {
+                    id: 0,
+                    seller: ALICE,
+                    buyer: BOB,
+                    asset,
+                    price: 100,
+                    paid_amount: 100,
+                    block_number: 3,
+                },
+                Order {
+                    id: 1,
+                    seller: BOB,
+                    buyer: ALICE,
+                    asset,
+                    price: 200,
+                    paid_amount: 200,
+                    block_number: 7,
+                },
+            ]
+        );
+        assert_eq!(
+            GamePowerMarket::get_order_history_for_asset(CLASS_ID, TOKEN_ID),
+            GamePowerMarket::get_order_history(asset, 0, 10)
+        );
+        assert!(
+            GamePowerMarket::get_order_history_for_asset(CLASS_ID, TOKEN_ID_NOT_EXIST).is_empty()
+        );
+
+        // Paginated
+        let page = GamePowerMarket::get_order_history(asset, 1, 1);
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].id, 1);
+        assert!(GamePowerMarket::get_order_history(asset, 2, 10).is_empty());
+        assert!(
+            GamePowerMarket::get_order_history((CLASS_ID, TOKEN_ID_NOT_EXIST), 0, 10).is_empty()
+        );
+    });
+}
+
+#[test]
+fn claim_deadline_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        System::set_block_number(5);
+        assert_noop!(
+            GamePowerMarket::create_claim(
+                Origin::signed(ALICE),
+                BOB,
+                (CLASS_ID, TOKEN_ID),
+                Some(4)
+            ),
+            Error::<Test>::InvalidClaimDeadline
+        );
+        assert_ok!(GamePowerMarket::create_claim(
+            Origin::signed(ALICE),
+            BOB,
+            (CLASS_ID, TOKEN_ID),
+            Some(10)
+        ));
+        assert_ok!(GamePowerMarket::create_claim(
+            Origin::signed(ALICE),
+            BOB,
+            (CLASS_ID, TOKEN_ID + 1),
+            Some(10)
+        ));
+        let claim = GamePowerMarket::open_claims(BOB, 0).unwrap();
+        assert_eq!(claim.creator, ALICE);
+        assert_eq!(claim.deadline, Some(10));
+
+        // Claimable up to and including the deadline
+        System::set_block_number(10);
+        assert_ok!(GamePowerMarket::claim(Origin::signed(BOB), 0));
+        assert!(OrmlNFT::is_owner(&BOB, (CLASS_ID, TOKEN_ID)));
+
+        System::set_block_number(11);
+        assert_noop!(
+            GamePowerMarket::claim(Origin::signed(BOB), 1),
+            Error::<Test>::ClaimExpired
+        );
+    });
+}
+
+#[test]
+fn reclaim_expired_claim_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(GamePowerMarket::create_claim(
+            Origin::signed(ALICE),
+            BOB,
+            (CLASS_ID, TOKEN_ID),
+            Some(10)
+        ));
+
+        assert_noop!(
+            GamePowerMarket::reclaim_expired_claim(Origin::signed(ALICE), BOB, 0),
+            Error::<Test>::ClaimNotExpired
+        );
+
+        System::set_block_number(11);
+        assert_noop!(
+            GamePowerMarket::reclaim_expired_claim(Origin::signed(BOB), BOB, 0),
+            Error::<Test>::NoPermission
+        );
+        assert_ok!(GamePowerMarket::reclaim_expired_claim(
+            Origin::signed(ALICE),
+            BOB,
+            0
+        ));
+        assert!(OrmlNFT::is_owner(&ALICE, (CLASS_ID, TOKEN_ID)));
+        assert_eq!(GamePowerMarket::open_claims(BOB, 0), None);
+        assert!(GamePowerMarket::all_claims().is_empty());
+        assert!(!GamePowerMarket::is_locked(&(CLASS_ID, TOKEN_ID)));
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketClaimExpired(
+                ALICE, BOB, CLASS_ID, TOKEN_ID
+            )))
+        );
+
+        // Claims without a deadline never expire
+        assert_ok!(GamePowerMarket::create_claim(
+            Origin::signed(ALICE),
+            BOB,
+            (CLASS_ID, TOKEN_ID),
+            None
+        ));
+        System::set_block_number(1000);
+        assert_noop!(
+            GamePowerMarket::reclaim_expired_claim(Origin::signed(ALICE), BOB, 1),
+            Error::<Test>::ClaimNotExpired
+        );
+    });
+}
+
+#[test]
+fn transfer_claim_receiver_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(GamePowerMarket::create_claim(
+            Origin::signed(ALICE),
+            BOB,
+            (CLASS_ID, TOKEN_ID),
+            None
+        ));
+
+        // Only the creator can change the receiver
+        assert_noop!(
+            GamePowerMarket::transfer_claim_receiver(Origin::signed(BOB), 0, 3),
+            Error::<Test>::NoPermission
+        );
+        assert_noop!(
+            GamePowerMarket::transfer_claim_receiver(Origin::signed(ALICE), 1, 3),
+            Error::<Test>::ClaimNotFound
+        );
+
+        assert_ok!(GamePowerMarket::transfer_claim_receiver(
+            Origin::signed(ALICE),
+            0,
+            3
+        ));
+        assert_eq!(GamePowerMarket::open_claims(BOB, 0), None);
+        assert_eq!(GamePowerMarket::open_claims(3, 0).unwrap().receiver, 3);
+        assert_eq!(GamePowerMarket::claim_receivers(0), Some(3));
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(
+                RawEvent::MarketClaimReceiverChanged(ALICE, BOB, 3, 0)
+            ))
+        );
+
+        // The old receiver can no longer claim, the new one can
+        assert_noop!(
+            GamePowerMarket::claim(Origin::signed(BOB), 0),
+            Error::<Test>::ClaimNotFound
+        );
+        assert_ok!(GamePowerMarket::claim(Origin::signed(3), 0));
+        assert!(OrmlNFT::is_owner(&3, (CLASS_ID, TOKEN_ID)));
+        assert_eq!(GamePowerMarket::claim_receivers(0), None);
+    });
+}
+
+#[test]
+fn batch_create_claim_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFTs
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        assert_ok!(GamePowerMarket::batch_create_claim(
+            Origin::signed(ALICE),
+            vec![BOB, 3],
+            vec![(CLASS_ID, TOKEN_ID), (CLASS_ID, TOKEN_ID + 1)]
+        ));
+        assert_eq!(
+            GamePowerMarket::open_claims(BOB, 0).unwrap().asset,
+            (CLASS_ID, TOKEN_ID)
+        );
+        assert_eq!(
+            GamePowerMarket::open_claims(3, 1).unwrap().asset,
+            (CLASS_ID, TOKEN_ID + 1)
+        );
+        assert_eq!(GamePowerMarket::all_claims().len(), 2);
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketBatchClaimCreated(
+                ALICE, 2
+            )))
+        );
+
+        assert_ok!(GamePowerMarket::claim(Origin::signed(3), 1));
+        assert!(OrmlNFT::is_owner(&3, (CLASS_ID, TOKEN_ID + 1)));
+    });
+}
+
+#[test]
+fn batch_create_claim_should_fail() {
+    new_test_ext().execute_with(|| {
+        // Create NFTs
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&BOB, CLASS_ID, vec![1], Default::default()));
+
+        assert_noop!(
+            GamePowerMarket::batch_create_claim(
+                Origin::signed(ALICE),
+                vec![BOB, 3],
+                vec![(CLASS_ID, TOKEN_ID)]
+            ),
+            Error::<Test>::BatchLengthMismatch
+        );
+
+        // The second asset isn't ALICE's, so no claim is created
+        assert_noop!(
+            GamePowerMarket::batch_create_claim(
+                Origin::signed(ALICE),
+                vec![BOB, 3],
+                vec![(CLASS_ID, TOKEN_ID), (CLASS_ID, TOKEN_ID + 1)]
+            ),
+            Error::<Test>::NoPermission
+        );
+        assert!(OrmlNFT::is_owner(&ALICE, (CLASS_ID, TOKEN_ID)));
+        assert!(GamePowerMarket::all_claims().is_empty());
+    });
+}
+
+#[test]
+fn mint_handler_error_should_abort_mint() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+
+        RejectMint::set(true);
+        assert_noop!(
+            GamePowerMarket::mint(
+                Origin::signed(ALICE),
+                CLASS_ID,
+                vec![1],
+                Default::default(),
+                2
+            ),
+            DispatchError::Other("mint rejected")
+        );
+        assert_eq!(OrmlNFT::tokens(CLASS_ID, TOKEN_ID), None);
+        assert_eq!(GamePowerMarket::class_minted_count(CLASS_ID), 0);
+
+        RejectMint::set(false);
+        assert_ok!(GamePowerMarket::mint(
+            Origin::signed(ALICE),
+            CLASS_ID,
+            vec![1],
+            Default::default(),
+            2
+        ));
+        assert!(OrmlNFT::is_owner(&ALICE, (CLASS_ID, TOKEN_ID + 1)));
+    });
+}
+
+#[test]
+fn class_restrictions_should_work() {
+    new_test_ext().execute_with(|| {
+        let restricted_class = ClassData {
+            allow_transfer: false,
+            allow_listing: false,
+            allow_burn: false,
+            ..Default::default()
+        };
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], restricted_class));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        assert_noop!(
+            GamePowerMarket::transfer(Origin::signed(ALICE), BOB, (CLASS_ID, TOKEN_ID)),
+            Error::<Test>::ClassTransferRestricted
+        );
+        assert_noop!(
+            GamePowerMarket::burn(Origin::signed(ALICE), (CLASS_ID, TOKEN_ID)),
+            Error::<Test>::ClassBurnRestricted
+        );
+        assert_noop!(
+            GamePowerMarket::list(
+                Origin::signed(ALICE),
+                (CLASS_ID, TOKEN_ID),
+                100,
+                None,
+                NATIVE_CURRENCY_ID
+            ),
+            Error::<Test>::ClassListingRestricted
+        );
+        assert_noop!(
+            GamePowerMarket::list_secret(Origin::signed(ALICE), (CLASS_ID, TOKEN_ID), 100, 1),
+            Error::<Test>::ClassListingRestricted
+        );
+        assert_noop!(
+            GamePowerMarket::list_bundle(
+                Origin::signed(ALICE),
+                vec![(CLASS_ID, TOKEN_ID), (CLASS_ID, TOKEN_ID + 1)],
+                100
+            ),
+            Error::<Test>::ClassListingRestricted
+        );
+        assert_noop!(
+            GamePowerMarket::create_auction(
+                Origin::signed(ALICE),
+                (CLASS_ID, TOKEN_ID),
+                100,
+                100,
+                10
+            ),
+            Error::<Test>::ClassListingRestricted
+        );
+        assert_noop!(
+            GamePowerMarket::create_dutch_auction(
+                Origin::signed(ALICE),
+                (CLASS_ID, TOKEN_ID),
+                100,
+                10,
+                1,
+                10
+            ),
+            Error::<Test>::ClassListingRestricted
+        );
+    });
+}
+
+#[test]
+fn class_restrictions_should_be_independent() {
+    new_test_ext().execute_with(|| {
+        // Only listing is restricted
+        let pass_class = ClassData {
+            allow_listing: false,
+            ..Default::default()
+        };
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], pass_class));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        assert_noop!(
+            GamePowerMarket::list(
+                Origin::signed(ALICE),
+                (CLASS_ID, TOKEN_ID),
+                100,
+                None,
+                NATIVE_CURRENCY_ID
+            ),
+            Error::<Test>::ClassListingRestricted
+        );
+        assert_ok!(GamePowerMarket::transfer(
+            Origin::signed(ALICE),
+            BOB,
+            (CLASS_ID, TOKEN_ID)
+        ));
+        assert_ok!(GamePowerMarket::burn(
+            Origin::signed(BOB),
+            (CLASS_ID, TOKEN_ID)
+        ));
+    });
+}
+
+#[test]
+fn approve_operator_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        // Not approved yet
+        assert!(!GamePowerMarket::is_approved_or_owner(
+            &BOB,
+            &(CLASS_ID, TOKEN_ID)
+        ));
+        assert_noop!(
+            GamePowerMarket::transfer(Origin::signed(BOB), BOB, (CLASS_ID, TOKEN_ID)),
+            Error::<Test>::NoPermission
+        );
+
+        assert_ok!(GamePowerMarket::approve_operator(
+            Origin::signed(ALICE),
+            BOB,
+            true
+        ));
+        assert!(GamePowerMarket::approvals(ALICE, BOB));
+        assert!(GamePowerMarket::is_approved_or_owner(
+            &ALICE,
+            &(CLASS_ID, TOKEN_ID)
+        ));
+        assert!(GamePowerMarket::is_approved_or_owner(
+            &BOB,
+            &(CLASS_ID, TOKEN_ID)
+        ));
+
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketOperatorApproved(
+                ALICE, BOB
+            )))
+        );
+
+        // The operator can transfer, list, burn and create claims for the owner
+        assert_ok!(GamePowerMarket::transfer(
+            Origin::signed(BOB),
+            3,
+            (CLASS_ID, TOKEN_ID)
+        ));
+        assert!(OrmlNFT::is_owner(&3, (CLASS_ID, TOKEN_ID)));
+
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(BOB),
+            (CLASS_ID, TOKEN_ID + 1),
+            100,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_eq!(
+            GamePowerMarket::listings(LISTING_ID).map(|listing| listing.seller),
+            Some(ALICE)
+        );
+
+        assert_ok!(GamePowerMarket::burn(
+            Origin::signed(BOB),
+            (CLASS_ID, TOKEN_ID + 2)
+        ));
+        assert_eq!(OrmlNFT::tokens(CLASS_ID, TOKEN_ID + 2), None);
+
+        assert_ok!(GamePowerMarket::create_claim(
+            Origin::signed(BOB),
+            3,
+            (CLASS_ID, TOKEN_ID + 3),
+            None
+        ));
+        assert_eq!(
+            GamePowerMarket::open_claims(3, 0).map(|claim| claim.creator),
+            Some(ALICE)
+        );
+
+        // Approval doesn't extend to the operator's own assets
+        assert!(!GamePowerMarket::approvals(BOB, ALICE));
+    });
+}
+
+#[test]
+fn revoke_operator_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        // Nothing to revoke
+        assert_noop!(
+            GamePowerMarket::revoke_operator(Origin::signed(ALICE), BOB),
+            Error::<Test>::ApprovalNotFound
+        );
+
+        assert_ok!(GamePowerMarket::approve_operator(
+            Origin::signed(ALICE),
+            BOB,
+            true
+        ));
+        assert_ok!(GamePowerMarket::revoke_operator(Origin::signed(ALICE), BOB));
+        assert!(!GamePowerMarket::approvals(ALICE, BOB));
+
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketOperatorRevoked(
+                ALICE, BOB
+            )))
+        );
+
+        assert_noop!(
+            GamePowerMarket::burn(Origin::signed(BOB), (CLASS_ID, TOKEN_ID)),
+            Error::<Test>::NoPermission
+        );
+
+        // Approving with false also removes the operator
+        assert_ok!(GamePowerMarket::approve_operator(
+            Origin::signed(ALICE),
+            BOB,
+            true
+        ));
+        assert_ok!(GamePowerMarket::approve_operator(
+            Origin::signed(ALICE),
+            BOB,
+            false
+        ));
+        assert!(!GamePowerMarket::approvals(ALICE, BOB));
+        assert_noop!(
+            GamePowerMarket::list(
+                Origin::signed(BOB),
+                (CLASS_ID, TOKEN_ID),
+                100,
+                None,
+                NATIVE_CURRENCY_ID
+            ),
+            Error::<Test>::NoPermission
+        );
+        assert_noop!(
+            GamePowerMarket::create_claim(Origin::signed(BOB), 3, (CLASS_ID, TOKEN_ID), None),
+            Error::<Test>::NoPermission
+        );
+    });
+}
+
+#[test]
+fn pause_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        for _ in 0..5 {
+            assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        }
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID + 4),
+            100,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::create_claim(
+            Origin::signed(ALICE),
+            BOB,
+            (CLASS_ID, TOKEN_ID + 3),
+            None
+        ));
+
+        // Only the pause origin can pause the market
+        assert_noop!(
+            GamePowerMarket::pause(Origin::signed(ALICE)),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(GamePowerMarket::pause(Origin::root()));
+        assert!(GamePowerMarket::paused());
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketPaused))
+        );
+
+        assert_noop!(
+            GamePowerMarket::transfer(Origin::signed(ALICE), BOB, (CLASS_ID, TOKEN_ID)),
+            Error::<Test>::ModulePaused
+        );
+        assert_noop!(
+            GamePowerMarket::burn(Origin::signed(ALICE), (CLASS_ID, TOKEN_ID + 1)),
+            Error::<Test>::ModulePaused
+        );
+        assert_noop!(
+            GamePowerMarket::list(
+                Origin::signed(ALICE),
+                (CLASS_ID, TOKEN_ID + 2),
+                100,
+                None,
+                NATIVE_CURRENCY_ID
+            ),
+            Error::<Test>::ModulePaused
+        );
+        assert_noop!(
+            GamePowerMarket::unlist(Origin::signed(ALICE), LISTING_ID),
+            Error::<Test>::ModulePaused
+        );
+        assert_noop!(
+            GamePowerMarket::buy(Origin::signed(BOB), LISTING_ID),
+            Error::<Test>::ModulePaused
+        );
+        assert_noop!(
+            GamePowerMarket::emote(Origin::signed(BOB), (CLASS_ID, TOKEN_ID), b"fish".to_vec()),
+            Error::<Test>::ModulePaused
+        );
+        assert_noop!(
+            GamePowerMarket::claim(Origin::signed(BOB), 0),
+            Error::<Test>::ModulePaused
+        );
+        assert_noop!(
+            GamePowerMarket::create_claim(
+                Origin::signed(ALICE),
+                BOB,
+                (CLASS_ID, TOKEN_ID + 2),
+                None
+            ),
+            Error::<Test>::ModulePaused
+        );
+
+        // Everything works again once unpaused
+        assert_noop!(
+            GamePowerMarket::unpause(Origin::signed(ALICE)),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(GamePowerMarket::unpause(Origin::root()));
+        assert!(!GamePowerMarket::paused());
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketUnpaused))
+        );
+
+        assert_ok!(GamePowerMarket::transfer(
+            Origin::signed(ALICE),
+            BOB,
+            (CLASS_ID, TOKEN_ID)
+        ));
+        assert_ok!(GamePowerMarket::burn(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID + 1)
+        ));
+        assert_ok!(GamePowerMarket::emote(
+            Origin::signed(BOB),
+            (CLASS_ID, TOKEN_ID),
+            b"fish".to_vec()
+        ));
+        assert_ok!(GamePowerMarket::claim(Origin::signed(BOB), 0));
+        assert_ok!(GamePowerMarket::create_claim(
+            Origin::signed(ALICE),
+            BOB,
+            (CLASS_ID, TOKEN_ID + 2),
+            None
+        ));
+        assert_ok!(GamePowerMarket::unlist(Origin::signed(ALICE), LISTING_ID));
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID + 4),
+            100,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::buy(Origin::signed(BOB), LISTING_ID + 1));
+    });
+}
+
+#[test]
+fn get_listings_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        for _ in 0..4 {
+            assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        }
+        for token_id in 0..4 {
+            assert_ok!(GamePowerMarket::list(
+                Origin::signed(ALICE),
+                (CLASS_ID, token_id),
+                100 + token_id,
+                None,
+                NATIVE_CURRENCY_ID
+            ));
+        }
+        assert_eq!(GamePowerMarket::listing_count(), 4);
+
+        // Pages follow the order the assets were listed in
+        let prices = |page, page_size| {
+            GamePowerMarket::get_listings(page, page_size)
+                .into_iter()
+                .map(|listing| listing.price)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(prices(0, 3), vec![100, 101, 102]);
+        assert_eq!(prices(1, 3), vec![103]);
+        assert_eq!(prices(2, 3), Vec::<u64>::new());
+        assert_eq!(prices(0, 0), Vec::<u64>::new());
+
+        // Removed listings drop out of the pages
+        assert_ok!(GamePowerMarket::unlist(Origin::signed(ALICE), 1));
+        assert_eq!(GamePowerMarket::listing_count(), 3);
+        assert_eq!(prices(0, 3), vec![100, 102, 103]);
+
+        assert_eq!(
+            GamePowerMarket::get_listing(2).map(|listing| listing.asset),
+            Some((CLASS_ID, 2))
+        );
+        assert_eq!(GamePowerMarket::get_listing(1), None);
+    });
+}
+
+#[test]
+fn list_in_other_currency_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        // Only accepted currencies can price a listing
+        assert_noop!(
+            GamePowerMarket::list(Origin::signed(ALICE), (CLASS_ID, TOKEN_ID), 100, None, 7),
+            Error::<Test>::CurrencyNotAccepted
+        );
+
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            100,
+            None,
+            OTHER_CURRENCY_ID
+        ));
+        assert_eq!(
+            GamePowerMarket::listings(LISTING_ID).map(|listing| listing.currency_id),
+            Some(OTHER_CURRENCY_ID)
+        );
+
+        // The buyer pays in the listing currency, not the native one
+        assert_noop!(
+            GamePowerMarket::buy(Origin::signed(BOB), LISTING_ID),
+            DispatchError::Other("insufficient token balance")
+        );
+
+        set_token_balance(OTHER_CURRENCY_ID, BOB, 150);
+        MarketplaceFeeRate::set(Perbill::from_percent(10));
+        assert_ok!(GamePowerMarket::buy(Origin::signed(BOB), LISTING_ID));
+
+        assert!(OrmlNFT::is_owner(&BOB, (CLASS_ID, TOKEN_ID)));
+        assert_eq!(token_balance(OTHER_CURRENCY_ID, BOB), 50);
+        assert_eq!(token_balance(OTHER_CURRENCY_ID, FeeRecipient::get()), 10);
+        assert_eq!(token_balance(OTHER_CURRENCY_ID, ALICE), 90);
+        assert_eq!(Balances::free_balance(BOB), 1_000_000);
+        assert_eq!(Balances::free_balance(ALICE), 1_000_000);
+    });
+}
+
+#[test]
+fn revoke_claim_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(GamePowerMarket::create_claim_with_reward(
+            Origin::signed(ALICE),
+            BOB,
+            (CLASS_ID, TOKEN_ID),
+            100
+        ));
+        assert_eq!(Balances::reserved_balance(ALICE), 100);
+
+        assert_noop!(
+            GamePowerMarket::revoke_claim(Origin::signed(ALICE), BOB, 1),
+            Error::<Test>::ClaimNotFound
+        );
+        assert_noop!(
+            GamePowerMarket::revoke_claim(Origin::signed(BOB), BOB, 0),
+            Error::<Test>::NoPermission
+        );
+
+        // No deadline needs to pass
+        assert_ok!(GamePowerMarket::revoke_claim(Origin::signed(ALICE), BOB, 0));
+        assert!(OrmlNFT::is_owner(&ALICE, (CLASS_ID, TOKEN_ID)));
+        assert_eq!(GamePowerMarket::open_claims(BOB, 0), None);
+        assert!(GamePowerMarket::all_claims().is_empty());
+        assert!(!GamePowerMarket::is_locked(&(CLASS_ID, TOKEN_ID)));
+        assert_eq!(Balances::reserved_balance(ALICE), 0);
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketClaimRevoked(
+                ALICE, BOB, CLASS_ID, TOKEN_ID
+            )))
+        );
+
+        // The receiver can no longer redeem it
+        assert_noop!(
+            GamePowerMarket::claim(Origin::signed(BOB), 0),
+            Error::<Test>::ClaimNotFound
+        );
+    });
+}
+
+#[test]
+fn dutch_auction_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        let asset = (CLASS_ID, TOKEN_ID);
+        System::set_block_number(1);
+
+        assert_noop!(
+            GamePowerMarket::create_dutch_auction(Origin::signed(ALICE), asset, 100, 200, 10, 20),
+            Error::<Test>::InvalidDutchAuctionPrice
+        );
+        assert_noop!(
+            GamePowerMarket::create_dutch_auction(Origin::signed(ALICE), asset, 1000, 200, 10, 10),
+            Error::<Test>::InvalidAuctionEnd
+        );
+        assert_noop!(
+            GamePowerMarket::create_dutch_auction(Origin::signed(BOB), asset, 1000, 200, 10, 20),
+            Error::<Test>::NoPermission
+        );
+
+        assert_ok!(GamePowerMarket::create_dutch_auction(
+            Origin::signed(ALICE),
+            asset,
+            1000,
+            200,
+            10,
+            20
+        ));
+        assert!(GamePowerMarket::is_locked(&asset));
+        assert!(OrmlNFT::is_owner(
+            &GamePowerMarket::get_class_escrow_account(CLASS_ID),
+            asset
+        ));
+
+        // The price falls linearly and stops at the floor
+        let auction = GamePowerMarket::dutch_auctions(0).unwrap();
+        assert_eq!(GamePowerMarket::get_dutch_auction_price(&auction, 10), 1000);
+        assert_eq!(GamePowerMarket::get_dutch_auction_price(&auction, 15), 600);
+        assert_eq!(GamePowerMarket::get_dutch_auction_price(&auction, 20), 200);
+        assert_eq!(GamePowerMarket::get_dutch_auction_price(&auction, 100), 200);
+
+        assert_noop!(
+            GamePowerMarket::buy_dutch(Origin::signed(BOB), 0),
+            Error::<Test>::AuctionNotStarted
+        );
+        System::set_block_number(15);
+        assert_noop!(
+            GamePowerMarket::buy_dutch(Origin::signed(ALICE), 0),
+            Error::<Test>::CannotBidOnOwnAuction
+        );
+        assert_ok!(GamePowerMarket::buy_dutch(Origin::signed(BOB), 0));
+
+        assert!(OrmlNFT::is_owner(&BOB, asset));
+        assert!(!GamePowerMarket::is_locked(&asset));
+        assert_eq!(GamePowerMarket::dutch_auctions(0), None);
+        assert_eq!(Balances::free_balance(BOB), 1_000_000 - 600);
+        assert_eq!(Balances::free_balance(ALICE), 1_000_000 + 600);
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(
+                RawEvent::MarketDutchAuctionPurchased(ALICE, BOB, 0, 600)
+            ))
+        );
+        assert_noop!(
+            GamePowerMarket::buy_dutch(Origin::signed(3), 0),
+            Error::<Test>::AuctionNotFound
+        );
+    });
+}
+
+#[test]
+fn cancel_dutch_auction_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        let asset = (CLASS_ID, TOKEN_ID);
+
+        assert_ok!(GamePowerMarket::create_dutch_auction(
+            Origin::signed(ALICE),
+            asset,
+            1000,
+            200,
+            0,
+            20
+        ));
+
+        assert_noop!(
+            GamePowerMarket::cancel_dutch_auction(Origin::signed(BOB), 0),
+            Error::<Test>::NoPermission
+        );
+        assert_ok!(GamePowerMarket::cancel_dutch_auction(
+            Origin::signed(ALICE),
+            0
+        ));
+
+        assert!(OrmlNFT::is_owner(&ALICE, asset));
+        assert!(!GamePowerMarket::is_locked(&asset));
+        assert_eq!(GamePowerMarket::dutch_auctions(0), None);
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(
+                RawEvent::MarketDutchAuctionCancelled(0)
+            ))
+        );
+        assert_noop!(
+            GamePowerMarket::buy_dutch(Origin::signed(BOB), 0),
+            Error::<Test>::AuctionNotFound
+        );
+    });
+}
+
+#[test]
+fn total_minted_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_eq!(GamePowerMarket::total_minted(CLASS_ID), 0);
+
+        assert_ok!(GamePowerMarket::mint(
+            Origin::signed(ALICE),
+            CLASS_ID,
+            vec![1],
+            Default::default(),
+            2
+        ));
+        assert_eq!(GamePowerMarket::total_minted(CLASS_ID), 2);
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketAssetMinted(
+                ALICE,
+                CLASS_ID,
+                TOKEN_ID + 1
+            )))
+        );
+
+        // Assets minted elsewhere count once reported to the mint handler
+        assert_ok!(OrmlNFT::mint(&BOB, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(<GamePowerMarket as OnMintHandler<_, _, _>>::on_mint(
+            &BOB,
+            (CLASS_ID, TOKEN_ID + 2)
+        ));
+        assert_eq!(GamePowerMarket::total_minted(CLASS_ID), 3);
+
+        // Burning doesn't lower the count
+        assert_ok!(GamePowerMarket::burn(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID)
+        ));
+        assert_eq!(GamePowerMarket::total_minted(CLASS_ID), 3);
+    });
+}
+
+#[test]
+fn emote_limit_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        let asset = (CLASS_ID, TOKEN_ID);
+
+        // Post emotes up to the limit
+        for _ in 0..MaxEmotesPerAccountPerAsset::get() {
+            assert_ok!(GamePowerMarket::emote(
+                Origin::signed(BOB),
+                asset,
+                "fish".as_bytes().to_vec()
+            ));
+        }
+        assert_eq!(
+            GamePowerMarket::emotes(asset, BOB).len(),
+            MaxEmotesPerAccountPerAsset::get() as usize
+        );
+
+        // One more is rejected
+        assert_noop!(
+            GamePowerMarket::emote(Origin::signed(BOB), asset, "fish".as_bytes().to_vec()),
+            Error::<Test>::EmoteLimitReached
+        );
+
+        // The limit is per account
+        assert_ok!(GamePowerMarket::emote(
+            Origin::signed(ALICE),
+            asset,
+            "fish".as_bytes().to_vec()
+        ));
+    });
+}
+
+#[test]
+fn remove_emote_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        let asset = (CLASS_ID, TOKEN_ID);
+
+        assert_ok!(GamePowerMarket::emote(
+            Origin::signed(BOB),
+            asset,
+            "fish".as_bytes().to_vec()
+        ));
+        assert_ok!(GamePowerMarket::emote(
+            Origin::signed(BOB),
+            asset,
+            "fire".as_bytes().to_vec()
+        ));
+        let fire = GamePowerMarket::emotes(asset, BOB)[1].clone();
+
+        // Remove the first emote
+        assert_ok!(GamePowerMarket::remove_emote(Origin::signed(BOB), asset, 0));
+        assert_eq!(GamePowerMarket::emotes(asset, BOB), vec![fire]);
+        assert!(!GamePowerMarket::has_emoted(asset, &BOB, b"fish"));
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketAssetEmoteRemoved(
+                BOB, CLASS_ID, TOKEN_ID, 0
+            )))
+        );
+
+        // Removing the last emote clears the storage entry
+        assert_ok!(GamePowerMarket::remove_emote(Origin::signed(BOB), asset, 0));
+        assert!(!<crate::Emotes<Test>>::contains_key(asset, BOB));
+    });
+}
+
+#[test]
+fn remove_emote_should_fail() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        let asset = (CLASS_ID, TOKEN_ID);
+
+        assert_ok!(GamePowerMarket::emote(
+            Origin::signed(BOB),
+            asset,
+            "fish".as_bytes().to_vec()
+        ));
+
+        // Index out of bounds
+        assert_noop!(
+            GamePowerMarket::remove_emote(Origin::signed(BOB), asset, 1),
+            Error::<Test>::InvalidEmoteIndex
+        );
+
+        // Emotes of other accounts can't be removed
+        assert_noop!(
+            GamePowerMarket::remove_emote(Origin::signed(ALICE), asset, 0),
+            Error::<Test>::InvalidEmoteIndex
+        );
+
+        // Asset doesn't exist
+        assert_noop!(
+            GamePowerMarket::remove_emote(Origin::signed(BOB), (CLASS_ID, TOKEN_ID_NOT_EXIST), 0),
+            Error::<Test>::InvalidEmoteIndex
+        );
+    });
+}
+
+#[test]
+fn emote_count_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        let asset = (CLASS_ID, TOKEN_ID);
+
+        assert_eq!(
+            GamePowerMarket::get_emote_count(CLASS_ID, TOKEN_ID, b"fish"),
+            0
+        );
+
+        assert_ok!(GamePowerMarket::emote(
+            Origin::signed(ALICE),
+            asset,
+            "fish".as_bytes().to_vec()
+        ));
+        assert_ok!(GamePowerMarket::emote(
+            Origin::signed(BOB),
+            asset,
+            "fish".as_bytes().to_vec()
+        ));
+        assert_ok!(GamePowerMarket::emote(
+            Origin::signed(BOB),
+            asset,
+            "fire".as_bytes().to_vec()
+        ));
+
+        // Counts can be read by name or emoji
+        let fish = GamePowerMarket::emotes(asset, BOB)[0].clone();
+        assert_eq!(
+            GamePowerMarket::get_emote_count(CLASS_ID, TOKEN_ID, b"fish"),
+            2
+        );
+        assert_eq!(
+            GamePowerMarket::get_emote_count(CLASS_ID, TOKEN_ID, &fish),
+            2
+        );
+        assert_eq!(
+            GamePowerMarket::get_emote_count(CLASS_ID, TOKEN_ID, b"fire"),
+            1
+        );
+
+        // Removing an emote lowers its count
+        assert_ok!(GamePowerMarket::remove_emote(Origin::signed(BOB), asset, 0));
+        assert_eq!(
+            GamePowerMarket::get_emote_count(CLASS_ID, TOKEN_ID, b"fish"),
+            1
+        );
+
+        // The entry is cleared once the count drops to zero
+        assert_ok!(GamePowerMarket::remove_emote(Origin::signed(BOB), asset, 0));
+        assert_eq!(
+            GamePowerMarket::get_emote_count(CLASS_ID, TOKEN_ID, b"fire"),
+            0
+        );
+        assert!(!<crate::EmoteCountByAsset<Test>>::contains_key(
+            asset,
+            GamePowerMarket::lookup_emoji(b"fire").unwrap()
+        ));
+    });
+}
+
+#[test]
+fn schema_version_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(GamePowerMarket::mint(
+            Origin::signed(ALICE),
+            CLASS_ID,
+            vec![1],
+            Default::default(),
+            1
+        ));
+        let asset = (CLASS_ID, TOKEN_ID);
+
+        // Only the class owner can bump the schema, and only upwards
+        assert_noop!(
+            GamePowerMarket::update_class_schema_version(Origin::signed(BOB), CLASS_ID, 1),
+            Error::<Test>::NoPermission
+        );
+        assert_ok!(GamePowerMarket::update_class_schema_version(
+            Origin::signed(ALICE),
+            CLASS_ID,
+            2
+        ));
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketClassSchemaUpdated(
+                CLASS_ID, 0, 2
+            )))
+        );
+        assert_noop!(
+            GamePowerMarket::update_class_schema_version(Origin::signed(ALICE), CLASS_ID, 2),
+            Error::<Test>::InvalidSchemaVersion
+        );
+        assert_noop!(
+            GamePowerMarket::update_class_schema_version(Origin::signed(ALICE), CLASS_ID, 1),
+            Error::<Test>::InvalidSchemaVersion
+        );
+
+        // Properties must follow the class's current schema
+        assert_noop!(
+            GamePowerMarket::update_token_properties(Origin::signed(ALICE), asset, vec![2], 0),
+            Error::<Test>::SchemaVersionMismatch
+        );
+        assert_noop!(
+            GamePowerMarket::update_token_properties(Origin::signed(BOB), asset, vec![2], 2),
+            Error::<Test>::NoPermission
+        );
+        assert_ok!(GamePowerMarket::update_token_properties(
+            Origin::signed(ALICE),
+            asset,
+            vec![2],
+            2
+        ));
+        let data = OrmlNFT::tokens(CLASS_ID, TOKEN_ID).unwrap().data;
+        assert_eq!(data.properties, vec![2]);
+        assert_eq!(data.schema_version, 2);
+
+        // New assets follow the class's current schema
+        assert_ok!(GamePowerMarket::mint(
+            Origin::signed(ALICE),
+            CLASS_ID,
+            vec![1],
+            Default::default(),
+            1
+        ));
+        assert_eq!(
+            OrmlNFT::tokens(CLASS_ID, TOKEN_ID + 1)
+                .unwrap()
+                .data
+                .schema_version,
+            2
+        );
+    });
+}
+
+#[test]
+fn transfer_class_ownership_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+
+        // Only the class owner can hand the class over
+        assert_noop!(
+            GamePowerMarket::transfer_class_ownership(Origin::signed(BOB), CLASS_ID, BOB),
+            Error::<Test>::NoPermission
+        );
+        assert_noop!(
+            GamePowerMarket::transfer_class_ownership(
+                Origin::signed(ALICE),
+                CLASS_ID_NOT_EXIST,
+                BOB
+            ),
+            Error::<Test>::AssetNotFound
+        );
+
+        assert_ok!(GamePowerMarket::transfer_class_ownership(
+            Origin::signed(ALICE),
+            CLASS_ID,
+            BOB
+        ));
+        assert_eq!(OrmlNFT::classes(CLASS_ID).unwrap().owner, BOB);
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(
+                RawEvent::MarketClassOwnershipTransferred(CLASS_ID, ALICE, BOB)
+            ))
+        );
+
+        // The old owner loses control of the class
+        assert_noop!(
+            GamePowerMarket::mint(
+                Origin::signed(ALICE),
+                CLASS_ID,
+                vec![1],
+                Default::default(),
+                1
+            ),
+            Error::<Test>::NoPermission
+        );
+        assert_noop!(
+            GamePowerMarket::update_class_schema_version(Origin::signed(ALICE), CLASS_ID, 1),
+            Error::<Test>::NoPermission
+        );
+
+        // The new owner can mint and hand assets out through claims right away
+        assert_ok!(GamePowerMarket::mint(
+            Origin::signed(BOB),
+            CLASS_ID,
+            vec![1],
+            Default::default(),
+            1
+        ));
+        assert_ok!(GamePowerMarket::create_claim(
+            Origin::signed(BOB),
+            ALICE,
+            (CLASS_ID, TOKEN_ID),
+            None
+        ));
+    });
+}
+
+#[test]
+fn min_listing_price_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        MinListingPrice::set(100);
+
+        // Listing below the minimum fails
+        assert_noop!(
+            GamePowerMarket::list(
+                Origin::signed(ALICE),
+                (CLASS_ID, TOKEN_ID),
+                99,
+                None,
+                NATIVE_CURRENCY_ID
+            ),
+            Error::<Test>::PriceTooLow
+        );
+
+        // Auctions can't start or fall below the minimum either
+        assert_noop!(
+            GamePowerMarket::create_auction(
+                Origin::signed(ALICE),
+                (CLASS_ID, TOKEN_ID),
+                99,
+                100,
+                10
+            ),
+            Error::<Test>::PriceTooLow
+        );
+        assert_noop!(
+            GamePowerMarket::create_dutch_auction(
+                Origin::signed(ALICE),
+                (CLASS_ID, TOKEN_ID),
+                200,
+                99,
+                1,
+                10
+            ),
+            Error::<Test>::PriceTooLow
+        );
+
+        // Listing at exactly the minimum succeeds
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            100,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+
+        // The price can't be lowered below the minimum later on
+        assert_noop!(
+            GamePowerMarket::update_listing_price(Origin::signed(ALICE), LISTING_ID, 50),
+            Error::<Test>::PriceTooLow
+        );
+        assert_ok!(GamePowerMarket::update_listing_price(
+            Origin::signed(ALICE),
+            LISTING_ID,
+            150
+        ));
+    });
+}
+
+#[test]
+fn reject_offer_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        let asset = (CLASS_ID, TOKEN_ID);
+
+        assert_ok!(GamePowerMarket::make_offer(
+            Origin::signed(BOB),
+            asset,
+            500,
+            10,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_eq!(Balances::reserved_balance(BOB), 500);
+
+        // Only the asset owner can reject
+        assert_noop!(
+            GamePowerMarket::reject_offer(Origin::signed(BOB), 0),
+            Error::<Test>::NoPermission
+        );
+        assert_noop!(
+            GamePowerMarket::reject_offer(Origin::signed(ALICE), 1),
+            Error::<Test>::OfferNotFound
+        );
+
+        assert_ok!(GamePowerMarket::reject_offer(Origin::signed(ALICE), 0));
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(Balances::free_balance(BOB), 1000000);
+        assert_eq!(GamePowerMarket::offers(0), None);
+        assert!(!OffersByAsset::<Test>::contains_key(asset, 0));
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketOfferRejected(
+                ALICE, BOB, 0, 500
+            )))
+        );
+    });
+}
+
+#[test]
+fn unlist_hook_failure_should_roll_back() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            100,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+
+        // A failing hook leaves the listing untouched
+        RejectUnlist::set(true);
+        assert_noop!(
+            GamePowerMarket::unlist(Origin::signed(ALICE), LISTING_ID),
+            DispatchError::Other("unlist rejected")
+        );
+        assert_noop!(
+            GamePowerMarket::buy(Origin::signed(BOB), LISTING_ID),
+            DispatchError::Other("unlist rejected")
+        );
+        assert!(GamePowerMarket::listings(LISTING_ID).is_some());
+        assert_eq!(GamePowerMarket::all_listings(), vec![(CLASS_ID, TOKEN_ID)]);
+        assert!(!OrmlNFT::is_owner(&ALICE, (CLASS_ID, TOKEN_ID)));
+        assert_eq!(Balances::free_balance(BOB), 1000000);
+
+        RejectUnlist::set(false);
+        assert_ok!(GamePowerMarket::unlist(Origin::signed(ALICE), LISTING_ID));
+        assert!(OrmlNFT::is_owner(&ALICE, (CLASS_ID, TOKEN_ID)));
+    });
+}
+
+#[test]
+fn listings_by_class_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        for _ in 0..3 {
+            assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        }
+        for token_id in 0..3 {
+            assert_ok!(GamePowerMarket::list(
+                Origin::signed(ALICE),
+                (CLASS_ID, token_id),
+                100,
+                None,
+                NATIVE_CURRENCY_ID
+            ));
+        }
+        assert_eq!(GamePowerMarket::listings_by_class(CLASS_ID), vec![0, 1, 2]);
+
+        // Pages follow the listing order
+        let page: Vec<ListingId> = GamePowerMarket::get_listings_by_class(CLASS_ID, 0, 2)
+            .into_iter()
+            .map(|listing| listing.id)
+            .collect();
+        assert_eq!(page, vec![0, 1]);
+        let page: Vec<ListingId> = GamePowerMarket::get_listings_by_class(CLASS_ID, 1, 2)
+            .into_iter()
+            .map(|listing| listing.id)
+            .collect();
+        assert_eq!(page, vec![2]);
+        assert!(GamePowerMarket::get_listings_by_class(CLASS_ID, 2, 2).is_empty());
+        assert!(GamePowerMarket::get_listings_by_class(CLASS_ID_NOT_EXIST, 0, 2).is_empty());
+
+        // Unlisting and buying prune the index
+        assert_ok!(GamePowerMarket::unlist(Origin::signed(ALICE), 1));
+        assert_eq!(GamePowerMarket::listings_by_class(CLASS_ID), vec![0, 2]);
+        assert_ok!(GamePowerMarket::buy(Origin::signed(BOB), 0));
+        assert_ok!(GamePowerMarket::buy(Origin::signed(BOB), 2));
+        assert!(GamePowerMarket::listings_by_class(CLASS_ID).is_empty());
+        assert!(!<crate::ListingsByClass<Test>>::contains_key(CLASS_ID));
+    });
+}
+
+#[test]
+fn claim_count_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        for _ in 0..3 {
+            assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        }
+
+        assert_ok!(GamePowerMarket::create_claim(
+            Origin::signed(ALICE),
+            BOB,
+            (CLASS_ID, TOKEN_ID),
+            None
+        ));
+        assert_ok!(GamePowerMarket::create_claim(
+            Origin::signed(ALICE),
+            BOB,
+            (CLASS_ID, TOKEN_ID + 1),
+            None
+        ));
+        assert_eq!(GamePowerMarket::claim_count(), 2);
+
+        // Redeeming and revoking free up room
+        assert_ok!(GamePowerMarket::claim(Origin::signed(BOB), 0));
+        assert_eq!(GamePowerMarket::claim_count(), 1);
+        assert_ok!(GamePowerMarket::revoke_claim(Origin::signed(ALICE), BOB, 1));
+        assert_eq!(GamePowerMarket::claim_count(), 0);
+    });
+}
+
+#[test]
+fn max_claims_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        for _ in 0..3 {
+            assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        }
+        MaxClaims::set(2);
+
+        assert_ok!(GamePowerMarket::create_claim(
+            Origin::signed(ALICE),
+            BOB,
+            (CLASS_ID, TOKEN_ID),
+            None
+        ));
+        assert_ok!(GamePowerMarket::create_claim(
+            Origin::signed(ALICE),
+            BOB,
+            (CLASS_ID, TOKEN_ID + 1),
+            None
+        ));
+
+        // No room for a third open claim
+        assert_noop!(
+            GamePowerMarket::create_claim(
+                Origin::signed(ALICE),
+                BOB,
+                (CLASS_ID, TOKEN_ID + 2),
+                None
+            ),
+            Error::<Test>::NoAvailableClaimId
+        );
+
+        // Until one of them is redeemed
+        assert_ok!(GamePowerMarket::claim(Origin::signed(BOB), 0));
+        assert_ok!(GamePowerMarket::create_claim(
+            Origin::signed(ALICE),
+            BOB,
+            (CLASS_ID, TOKEN_ID + 2),
+            None
+        ));
+    });
+}
+
+#[test]
+fn transfer_with_memo_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        let memo = b"trade for sword".to_vec();
+
+        // Memos over the limit are rejected
+        assert_noop!(
+            GamePowerMarket::transfer_with_memo(
+                Origin::signed(ALICE),
+                BOB,
+                (CLASS_ID, TOKEN_ID),
+                vec![0; MaxMemoLength::get() as usize + 1]
+            ),
+            Error::<Test>::MemoTooLong
+        );
+
+        // Transfers follow the rules of a plain transfer
+        assert_noop!(
+            GamePowerMarket::transfer_with_memo(
+                Origin::signed(BOB),
+                ALICE,
+                (CLASS_ID, TOKEN_ID),
+                memo.clone()
+            ),
+            Error::<Test>::NoPermission
+        );
+
+        assert_ok!(GamePowerMarket::transfer_with_memo(
+            Origin::signed(ALICE),
+            BOB,
+            (CLASS_ID, TOKEN_ID),
+            memo.clone()
+        ));
+        assert!(OrmlNFT::is_owner(&BOB, (CLASS_ID, TOKEN_ID)));
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(
+                RawEvent::MarketAssetTransferredWithMemo(ALICE, BOB, CLASS_ID, TOKEN_ID, memo)
+            ))
+        );
+    });
+}
+
+#[test]
+fn class_max_supply_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(GamePowerMarket::mint(
+            Origin::signed(ALICE),
+            CLASS_ID,
+            vec![1],
+            Default::default(),
+            1
+        ));
+
+        // Only the class owner can cap the class, and only above the current supply
+        assert_noop!(
+            GamePowerMarket::set_class_max_supply(Origin::signed(BOB), CLASS_ID, 3),
+            Error::<Test>::NoPermission
+        );
+        assert_noop!(
+            GamePowerMarket::set_class_max_supply(Origin::signed(ALICE), CLASS_ID, 1),
+            Error::<Test>::InvalidMaxSupply
+        );
+        assert_ok!(GamePowerMarket::set_class_max_supply(
+            Origin::signed(ALICE),
+            CLASS_ID,
+            3
+        ));
+        assert_eq!(OrmlNFT::classes(CLASS_ID).unwrap().data.max_supply, Some(3));
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketClassMaxSupplySet(
+                CLASS_ID, 3
+            )))
+        );
+
+        // The cap can't be changed, not even downwards
+        assert_noop!(
+            GamePowerMarket::set_class_max_supply(Origin::signed(ALICE), CLASS_ID, 2),
+            Error::<Test>::MaxSupplyAlreadySet
+        );
+
+        // Minting past the cap fails, minting up to it succeeds
+        assert_noop!(
+            GamePowerMarket::mint(
+                Origin::signed(ALICE),
+                CLASS_ID,
+                vec![1],
+                Default::default(),
+                3
+            ),
+            Error::<Test>::MaxSupplyExceeded
+        );
+        assert_ok!(GamePowerMarket::mint(
+            Origin::signed(ALICE),
+            CLASS_ID,
+            vec![1],
+            Default::default(),
+            2
+        ));
+        assert_eq!(GamePowerMarket::total_minted(CLASS_ID), 3);
+        assert_noop!(
+            GamePowerMarket::mint(
+                Origin::signed(ALICE),
+                CLASS_ID,
+                vec![1],
+                Default::default(),
+                1
+            ),
+            Error::<Test>::MaxSupplyExceeded
+        );
+    });
+}
+
+#[test]
+fn class_max_supply_should_respect_limited_editions() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(GamePowerMarket::create_class(
+            Origin::signed(ALICE),
+            vec![1],
+            Default::default(),
+            Some(5)
+        ));
+
+        assert_noop!(
+            GamePowerMarket::set_class_max_supply(Origin::signed(ALICE), CLASS_ID, 3),
+            Error::<Test>::MaxSupplyAlreadySet
+        );
+    });
+}
+
+#[test]
+fn holding_period_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        MinHoldingPeriod::set(10);
+        let asset = (CLASS_ID, TOKEN_ID);
+
+        // Minted assets can be listed straight away
+        System::set_block_number(5);
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            asset,
+            100,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::buy(Origin::signed(BOB), LISTING_ID));
+        assert_eq!(
+            OrmlNFT::tokens(CLASS_ID, TOKEN_ID)
+                .unwrap()
+                .data
+                .last_purchased_at,
+            Some(5)
+        );
+
+        // Bob has to hold the asset before flipping it
+        assert_noop!(
+            GamePowerMarket::list(Origin::signed(BOB), asset, 200, None, NATIVE_CURRENCY_ID),
+            Error::<Test>::HoldingPeriodNotMet
+        );
+        System::set_block_number(14);
+        assert_noop!(
+            GamePowerMarket::list(Origin::signed(BOB), asset, 200, None, NATIVE_CURRENCY_ID),
+            Error::<Test>::HoldingPeriodNotMet
+        );
+
+        // Auctions are held to the same period
+        assert_noop!(
+            GamePowerMarket::create_auction(Origin::signed(BOB), asset, 200, 200, 20),
+            Error::<Test>::HoldingPeriodNotMet
+        );
+        assert_noop!(
+            GamePowerMarket::create_dutch_auction(Origin::signed(BOB), asset, 200, 100, 14, 20),
+            Error::<Test>::HoldingPeriodNotMet
+        );
+
+        System::set_block_number(15);
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(BOB),
+            asset,
+            200,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+    });
+}
+
+#[test]
+fn get_owned_assets_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(GamePowerMarket::mint(
+            Origin::signed(ALICE),
+            CLASS_ID,
+            vec![1],
+            Default::default(),
+            2
+        ));
+        assert_ok!(GamePowerMarket::mint(
+            Origin::signed(ALICE),
+            CLASS_ID + 1,
+            vec![1],
+            Default::default(),
+            1
+        ));
+        assert_eq!(
+            GamePowerMarket::get_owned_assets(&ALICE),
+            vec![
+                (CLASS_ID, TOKEN_ID),
+                (CLASS_ID, TOKEN_ID + 1),
+                (CLASS_ID + 1, TOKEN_ID)
+            ]
+        );
+        assert_eq!(
+            GamePowerMarket::get_owned_assets_by_class(&ALICE, CLASS_ID + 1),
+            vec![(CLASS_ID + 1, TOKEN_ID)]
+        );
+
+        // Transfers move the asset between owners
+        assert_ok!(GamePowerMarket::transfer(
+            Origin::signed(ALICE),
+            BOB,
+            (CLASS_ID, TOKEN_ID)
+        ));
+        assert_eq!(
+            GamePowerMarket::get_owned_assets(&BOB),
+            vec![(CLASS_ID, TOKEN_ID)]
+        );
+        assert_eq!(
+            GamePowerMarket::get_owned_assets_by_class(&ALICE, CLASS_ID),
+            vec![(CLASS_ID, TOKEN_ID + 1)]
+        );
+
+        // Burned assets are dropped
+        assert_ok!(GamePowerMarket::burn(
+            Origin::signed(BOB),
+            (CLASS_ID, TOKEN_ID)
+        ));
+        assert!(GamePowerMarket::get_owned_assets(&BOB).is_empty());
+    });
+}
+
+#[test]
+fn set_royalty_split_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(GamePowerMarket::create_class(
+            Origin::signed(ALICE),
+            vec![1],
+            ClassData {
+                royalty_rate: Some(Perbill::from_percent(10)),
+                royalty_recipient: Some(3),
+                ..Default::default()
+            },
+            None
+        ));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        // Only the class owner can set the split
+        let splits = vec![
+            (4, Perbill::from_percent(5)),
+            (5, Perbill::from_percent(15)),
+        ];
+        assert_noop!(
+            GamePowerMarket::set_royalty_split(Origin::signed(BOB), CLASS_ID, splits.clone()),
+            Error::<Test>::NoPermission
+        );
+        assert_noop!(
+            GamePowerMarket::set_royalty_split(
+                Origin::signed(ALICE),
+                CLASS_ID_NOT_EXIST,
+                splits.clone()
+            ),
+            Error::<Test>::AssetNotFound
+        );
+
+        // Recipients are capped by MaxRoyaltyRecipients
+        assert_noop!(
+            GamePowerMarket::set_royalty_split(
+                Origin::signed(ALICE),
+                CLASS_ID,
+                vec![
+                    (3, Perbill::from_percent(1)),
+                    (4, Perbill::from_percent(1)),
+                    (5, Perbill::from_percent(1))
+                ]
+            ),
+            Error::<Test>::TooManyRoyaltyRecipients
+        );
+
+        // Shares can't add up to more than the sale price
+        assert_noop!(
+            GamePowerMarket::set_royalty_split(
+                Origin::signed(ALICE),
+                CLASS_ID,
+                vec![
+                    (4, Perbill::from_percent(60)),
+                    (5, Perbill::from_percent(50))
+                ]
+            ),
+            Error::<Test>::InvalidRoyaltySplit
+        );
+
+        assert_ok!(GamePowerMarket::set_royalty_split(
+            Origin::signed(ALICE),
+            CLASS_ID,
+            splits.clone()
+        ));
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketRoyaltySplitSet(
+                CLASS_ID, splits
+            )))
+        );
+
+        // The royalty recipient and each collaborator get their share
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            1000,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::buy(Origin::signed(BOB), LISTING_ID));
+        assert!(System::events().iter().any(|record| record.event
+            == Event::gamepower_market(RawEvent::MarketRoyaltiesDistributed(CLASS_ID, 300))));
+        assert_eq!(Balances::free_balance(BOB), 1000000 - 1000);
+        assert_eq!(Balances::free_balance(3), 1000000 + 100);
+        assert_eq!(Balances::free_balance(4), 1000000 + 50);
+        assert_eq!(Balances::free_balance(5), 1000000 + 150);
+        assert_eq!(Balances::free_balance(ALICE), 1000000 + 700);
+
+        // Splits can also be given when the class is created
+        assert_noop!(
+            GamePowerMarket::create_class(
+                Origin::signed(ALICE),
+                vec![1],
+                ClassData {
+                    royalty_recipients: vec![
+                        (4, Perbill::from_percent(60)),
+                        (5, Perbill::from_percent(50))
+                    ],
+                    ..Default::default()
+                },
+                None
+            ),
+            Error::<Test>::InvalidRoyaltySplit
+        );
+    });
+}
+
+#[test]
+fn list_rate_limit_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        for _ in 0..3 {
+            assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        }
+        MaxListingsPerAccountPerBlock::set(2);
+
+        for token_id in 0..2 {
+            assert_ok!(GamePowerMarket::list(
+                Origin::signed(ALICE),
+                (CLASS_ID, token_id),
+                10,
+                None,
+                NATIVE_CURRENCY_ID
+            ));
+        }
+        assert_eq!(GamePowerMarket::listings_created_this_block((ALICE, 1)), 2);
+
+        // A third listing in the same block is rejected
+        assert_noop!(
+            GamePowerMarket::list(
+                Origin::signed(ALICE),
+                (CLASS_ID, 2),
+                10,
+                None,
+                NATIVE_CURRENCY_ID
+            ),
+            Error::<Test>::RateLimitExceeded
+        );
+
+        // Counters are cleared when the next block starts
+        System::set_block_number(2);
+        GamePowerMarket::on_initialize(2);
+        assert_eq!(GamePowerMarket::listings_created_this_block((ALICE, 1)), 0);
+        assert!(!ListingsCreatedThisBlock::<Test>::contains_key((ALICE, 1)));
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, 2),
+            10,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+    });
+}
+
+#[test]
+fn secret_and_bundle_listings_should_be_rate_limited() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        for _ in 0..4 {
+            assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        }
+        MaxListingsPerAccountPerBlock::set(2);
+
+        // A bundle counts as one listing
+        assert_ok!(GamePowerMarket::list_secret(
+            Origin::signed(ALICE),
+            (CLASS_ID, 0),
+            10,
+            1
+        ));
+        assert_ok!(GamePowerMarket::list_bundle(
+            Origin::signed(ALICE),
+            vec![(CLASS_ID, 1), (CLASS_ID, 2)],
+            10
+        ));
+        assert_eq!(GamePowerMarket::listings_created_this_block((ALICE, 1)), 2);
+        assert_noop!(
+            GamePowerMarket::list_secret(Origin::signed(ALICE), (CLASS_ID, 3), 10, 1),
+            Error::<Test>::RateLimitExceeded
+        );
+        assert_noop!(
+            GamePowerMarket::list_bundle(Origin::signed(ALICE), vec![(CLASS_ID, 3)], 10),
+            Error::<Test>::RateLimitExceeded
+        );
+
+        // Bundles respect the minimum listing price
+        System::set_block_number(2);
+        GamePowerMarket::on_initialize(2);
+        MinListingPrice::set(100);
+        assert_noop!(
+            GamePowerMarket::list_bundle(Origin::signed(ALICE), vec![(CLASS_ID, 3)], 99),
+            Error::<Test>::PriceTooLow
+        );
+        assert_ok!(GamePowerMarket::list_bundle(
+            Origin::signed(ALICE),
+            vec![(CLASS_ID, 3)],
+            100
+        ));
+    });
+}
+
+#[test]
+fn set_transferable_after_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        for _ in 0..3 {
+            assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        }
+
+        // Only the class owner can lock a class, and only until a future block
+        assert_noop!(
+            GamePowerMarket::set_transferable_after(Origin::signed(BOB), CLASS_ID, 10),
+            Error::<Test>::NoPermission
+        );
+        assert_noop!(
+            GamePowerMarket::set_transferable_after(Origin::signed(ALICE), CLASS_ID, 1),
+            Error::<Test>::InvalidTransferableAfter
+        );
+        assert_ok!(GamePowerMarket::set_transferable_after(
+            Origin::signed(ALICE),
+            CLASS_ID,
+            10
+        ));
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(
+                RawEvent::MarketClassTransferableAfterSet(CLASS_ID, 10)
+            ))
+        );
+
+        // Nothing moves before the unlock block
+        System::set_block_number(9);
+        assert_noop!(
+            GamePowerMarket::transfer(Origin::signed(ALICE), BOB, (CLASS_ID, 0)),
+            Error::<Test>::AssetNotYetTransferable
+        );
+        assert_noop!(
+            GamePowerMarket::list(
+                Origin::signed(ALICE),
+                (CLASS_ID, 1),
+                10,
+                None,
+                NATIVE_CURRENCY_ID
+            ),
+            Error::<Test>::AssetNotYetTransferable
+        );
+        assert_noop!(
+            GamePowerMarket::create_claim(Origin::signed(ALICE), BOB, (CLASS_ID, 2), None),
+            Error::<Test>::AssetNotYetTransferable
+        );
+
+        // From the unlock block on the assets trade normally
+        System::set_block_number(10);
+        assert_ok!(GamePowerMarket::transfer(
+            Origin::signed(ALICE),
+            BOB,
+            (CLASS_ID, 0)
+        ));
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, 1),
+            10,
+            None,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::create_claim(
+            Origin::signed(ALICE),
+            BOB,
+            (CLASS_ID, 2),
+            None
+        ));
+    });
+}
+
+#[test]
+fn swap_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&BOB, CLASS_ID, vec![1], Default::default()));
+        let alice_asset = (CLASS_ID, 0);
+        let bob_asset = (CLASS_ID, 1);
+
+        assert_noop!(
+            GamePowerMarket::propose_swap(Origin::signed(ALICE), alice_asset, bob_asset, ALICE, 10),
+            Error::<Test>::CannotSwapWithSelf
+        );
+        assert_noop!(
+            GamePowerMarket::propose_swap(Origin::signed(ALICE), bob_asset, alice_asset, BOB, 10),
+            Error::<Test>::NoPermission
+        );
+        assert_noop!(
+            GamePowerMarket::propose_swap(
+                Origin::signed(ALICE),
+                alice_asset,
+                (CLASS_ID, TOKEN_ID_NOT_EXIST + 1),
+                BOB,
+                10
+            ),
+            Error::<Test>::AssetNotFound
+        );
+
+        // The offered asset is held by the swap escrow
+        assert_ok!(GamePowerMarket::propose_swap(
+            Origin::signed(ALICE),
+            alice_asset,
+            bob_asset,
+            BOB,
+            10
+        ));
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketSwapProposed(
+                ALICE, BOB, 0
+            )))
+        );
+        assert!(OrmlNFT::is_owner(
+            &GamePowerMarket::get_swap_escrow_account(),
+            alice_asset
+        ));
+
+        // Only the counterparty can accept
+        assert_noop!(
+            GamePowerMarket::accept_swap(Origin::signed(ALICE), 0),
+            Error::<Test>::NoPermission
+        );
+        assert_ok!(GamePowerMarket::accept_swap(Origin::signed(BOB), 0));
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketSwapCompleted(
+                ALICE, BOB, CLASS_ID, 0, CLASS_ID, 1
+            )))
+        );
+        assert!(OrmlNFT::is_owner(&BOB, alice_asset));
+        assert!(OrmlNFT::is_owner(&ALICE, bob_asset));
+        assert_eq!(GamePowerMarket::swap_proposals(0), None);
+        assert_noop!(
+            GamePowerMarket::accept_swap(Origin::signed(BOB), 0),
+            Error::<Test>::SwapNotFound
+        );
+    });
+}
+
+#[test]
+fn cancel_swap_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&BOB, CLASS_ID, vec![1], Default::default()));
+        let alice_asset = (CLASS_ID, 0);
+        let bob_asset = (CLASS_ID, 1);
+
+        assert_ok!(GamePowerMarket::propose_swap(
+            Origin::signed(ALICE),
+            alice_asset,
+            bob_asset,
+            BOB,
+            5
+        ));
+
+        // Expired swaps can't be accepted
+        System::set_block_number(6);
+        assert_noop!(
+            GamePowerMarket::accept_swap(Origin::signed(BOB), 0),
+            Error::<Test>::SwapExpired
+        );
+
+        // Only the initiator can take their asset back
+        assert_noop!(
+            GamePowerMarket::cancel_swap(Origin::signed(BOB), 0),
+            Error::<Test>::NoPermission
+        );
+        assert_ok!(GamePowerMarket::cancel_swap(Origin::signed(ALICE), 0));
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketSwapCancelled(
+                ALICE, 0
+            )))
+        );
+        assert!(OrmlNFT::is_owner(&ALICE, alice_asset));
+        assert!(OrmlNFT::is_owner(&BOB, bob_asset));
+        assert_eq!(GamePowerMarket::swap_proposals(0), None);
+    });
+}
+
+#[test]
+fn swap_dispute_should_wait_for_timeout() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&BOB, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(GamePowerMarket::propose_swap(
+            Origin::signed(ALICE),
+            (CLASS_ID, 0),
+            (CLASS_ID, 1),
+            BOB,
+            100
+        ));
+        let proposed_block = System::block_number();
+
+        // Swaps can only be disputed after SwapTimeoutBlocks
+        System::set_block_number(proposed_block + SwapTimeoutBlocks::get());
+        assert_noop!(
+            GamePowerMarket::raise_swap_dispute(Origin::signed(ALICE), 0),
+            Error::<Test>::SwapDisputeTooEarly
+        );
+
+        // Only the parties of the swap can dispute it
+        System::set_block_number(proposed_block + SwapTimeoutBlocks::get() + 1);
+        assert_noop!(
+            GamePowerMarket::raise_swap_dispute(Origin::signed(3), 0),
+            Error::<Test>::NoPermission
+        );
+        assert_noop!(
+            GamePowerMarket::raise_swap_dispute(Origin::signed(ALICE), 1),
+            Error::<Test>::SwapNotFound
+        );
+        assert_ok!(GamePowerMarket::raise_swap_dispute(Origin::signed(BOB), 0));
+        assert_eq!(
+            GamePowerMarket::swap_disputes(0),
+            Some(DisputeStatus::Open(BOB))
+        );
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketSwapDisputeRaised(
+                BOB, 0
+            )))
+        );
+
+        // A disputed swap is held until it is resolved
+        assert_noop!(
+            GamePowerMarket::accept_swap(Origin::signed(BOB), 0),
+            Error::<Test>::SwapDisputed
+        );
+        assert_noop!(
+            GamePowerMarket::cancel_swap(Origin::signed(ALICE), 0),
+            Error::<Test>::SwapDisputed
+        );
+    });
+}
+
+#[test]
+fn swap_dispute_should_be_resolved_by_arbitration() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&BOB, CLASS_ID, vec![1], Default::default()));
+        let alice_asset = (CLASS_ID, 0);
+        assert_ok!(GamePowerMarket::propose_swap(
+            Origin::signed(ALICE),
+            alice_asset,
+            (CLASS_ID, 1),
+            BOB,
+            100
+        ));
+
+        // Only open disputes can be resolved
+        assert_noop!(
+            GamePowerMarket::resolve_swap_dispute(Origin::root(), 0, ALICE),
+            Error::<Test>::SwapNotDisputed
+        );
+
+        System::set_block_number(System::block_number() + SwapTimeoutBlocks::get() + 1);
+        assert_ok!(GamePowerMarket::raise_swap_dispute(
+            Origin::signed(ALICE),
+            0
+        ));
+
+        // Only the arbitration origin can resolve, in favor of one of the parties
+        assert_noop!(
+            GamePowerMarket::resolve_swap_dispute(Origin::signed(ALICE), 0, ALICE),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            GamePowerMarket::resolve_swap_dispute(Origin::root(), 0, 3),
+            Error::<Test>::NoPermission
+        );
+        assert_ok!(GamePowerMarket::resolve_swap_dispute(
+            Origin::root(),
+            0,
+            ALICE
+        ));
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(
+                RawEvent::MarketSwapDisputeResolved(0, ALICE)
+            ))
+        );
+        assert!(OrmlNFT::is_owner(&ALICE, alice_asset));
+        assert_eq!(GamePowerMarket::swap_proposals(0), None);
+        assert_eq!(
+            GamePowerMarket::swap_disputes(0),
+            Some(DisputeStatus::Resolved(ALICE))
+        );
+    });
+}
+
+#[test]
+fn swap_dispute_should_not_be_raised_twice() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&BOB, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(GamePowerMarket::propose_swap(
+            Origin::signed(ALICE),
+            (CLASS_ID, 0),
+            (CLASS_ID, 1),
+            BOB,
+            100
+        ));
+
+        System::set_block_number(System::block_number() + SwapTimeoutBlocks::get() + 1);
+        assert_ok!(GamePowerMarket::raise_swap_dispute(
+            Origin::signed(ALICE),
+            0
+        ));
+        assert_noop!(
+            GamePowerMarket::raise_swap_dispute(Origin::signed(ALICE), 0),
+            Error::<Test>::SwapAlreadyDisputed
+        );
+        assert_noop!(
+            GamePowerMarket::raise_swap_dispute(Origin::signed(BOB), 0),
+            Error::<Test>::SwapAlreadyDisputed
+        );
+
+        // Resolved in favor of the counterparty, who receives the offered asset
+        assert_ok!(GamePowerMarket::resolve_swap_dispute(
+            Origin::root(),
+            0,
+            BOB
+        ));
+        assert!(OrmlNFT::is_owner(&BOB, (CLASS_ID, 0)));
+        assert_noop!(
+            GamePowerMarket::resolve_swap_dispute(Origin::root(), 0, BOB),
+            Error::<Test>::SwapNotFound
+        );
+    });
+}
+
+#[test]
+fn set_token_attribute_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&BOB, CLASS_ID, vec![1], Default::default()));
+        let asset = (CLASS_ID, TOKEN_ID);
+
+        // Only the class owner can set attributes, even on assets it doesn't own
+        assert_noop!(
+            GamePowerMarket::set_token_attribute(
+                Origin::signed(BOB),
+                asset,
+                b"level".to_vec(),
+                b"1".to_vec()
+            ),
+            Error::<Test>::NoPermission
+        );
+        assert_ok!(GamePowerMarket::set_token_attribute(
+            Origin::signed(ALICE),
+            asset,
+            b"level".to_vec(),
+            b"1".to_vec()
+        ));
+        assert_ok!(GamePowerMarket::set_token_attribute(
+            Origin::signed(ALICE),
+            asset,
+            b"class".to_vec(),
+            b"mage".to_vec()
+        ));
+
+        // Setting a key again replaces its value
+        assert_ok!(GamePowerMarket::set_token_attribute(
+            Origin::signed(ALICE),
+            asset,
+            b"level".to_vec(),
+            b"42".to_vec()
+        ));
+        assert_eq!(
+            System::events().last().map(|record| record.event.clone()),
+            Some(Event::gamepower_market(RawEvent::MarketTokenAttributeSet(
+                CLASS_ID,
+                TOKEN_ID,
+                b"level".to_vec(),
+                b"42".to_vec()
+            )))
+        );
+        assert_eq!(
+            GamePowerMarket::get_token_attribute(CLASS_ID, TOKEN_ID, b"level"),
+            Some(b"42".to_vec())
+        );
+        assert_eq!(
+            GamePowerMarket::get_token_attribute(CLASS_ID, TOKEN_ID, b"speed"),
+            None
+        );
+
+        // New keys are capped by MaxAttributeCount
+        assert_noop!(
+            GamePowerMarket::set_token_attribute(
+                Origin::signed(ALICE),
+                asset,
+                b"speed".to_vec(),
+                b"7".to_vec()
+            ),
+            Error::<Test>::TooManyAttributes
+        );
+
+        // The cap also applies to attributes given at mint
+        assert_noop!(
+            GamePowerMarket::mint(
+                Origin::signed(ALICE),
+                CLASS_ID,
+                vec![1],
+                AssetData {
+                    game_attributes: vec![
+                        (b"a".to_vec(), vec![]),
+                        (b"b".to_vec(), vec![]),
+                        (b"c".to_vec(), vec![])
+                    ],
+                    ..Default::default()
+                },
+                1
+            ),
+            Error::<Test>::TooManyAttributes
+        );
+    });
+}
+
+#[test]
+fn transferable_after_should_cover_auctions_and_offers() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        for _ in 0..3 {
+            assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        }
+        assert_ok!(GamePowerMarket::make_offer(
+            Origin::signed(BOB),
+            (CLASS_ID, 2),
+            100,
+            20,
+            NATIVE_CURRENCY_ID
+        ));
+        assert_ok!(GamePowerMarket::set_transferable_after(
+            Origin::signed(ALICE),
+            CLASS_ID,
+            10
+        ));
+
+        // Locked assets can't be auctioned or sold to an offer
+        System::set_block_number(9);
+        assert_noop!(
+            GamePowerMarket::create_auction(Origin::signed(ALICE), (CLASS_ID, 0), 10, 10, 15),
+            Error::<Test>::AssetNotYetTransferable
+        );
+        assert_noop!(
+            GamePowerMarket::create_dutch_auction(
+                Origin::signed(ALICE),
+                (CLASS_ID, 1),
+                100,
+                10,
+                9,
+                15
+            ),
+            Error::<Test>::AssetNotYetTransferable
+        );
+        assert_noop!(
+            GamePowerMarket::accept_offer(Origin::signed(ALICE), 0),
+            Error::<Test>::AssetNotYetTransferable
+        );
+
+        System::set_block_number(10);
+        assert_ok!(GamePowerMarket::create_auction(
+            Origin::signed(ALICE),
+            (CLASS_ID, 0),
+            10,
+            10,
+            15
+        ));
+        assert_ok!(GamePowerMarket::create_dutch_auction(
+            Origin::signed(ALICE),
+            (CLASS_ID, 1),
+            100,
+            10,
+            10,
+            15
+        ));
+        assert_ok!(GamePowerMarket::accept_offer(Origin::signed(ALICE), 0));
+        assert!(OrmlNFT::is_owner(&BOB, (CLASS_ID, 2)));
     });
 }
@@ -0,0 +1,685 @@
+use crate::mock_u128::*;
+use crate::{Claim, Error, Listing, Offer, Order, Releases, SecretListing, SwapProposal};
+use codec::Encode;
+use frame_support::{
+    assert_noop, assert_ok,
+    storage::migration::{get_storage_value, put_storage_value},
+    traits::OnRuntimeUpgrade,
+    Blake2_128Concat, StorageHasher, StorageMap, StorageValue, Twox64Concat,
+};
+use gamepower_primitives::{AssetData, ClassData};
+
+#[test]
+fn transfer_with_u128_ids_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&BOB, CLASS_ID, vec![1], Default::default()));
+
+        // Make a valid transfer
+        assert_ok!(GamePowerMarket::transfer(
+            Origin::signed(BOB),
+            ALICE,
+            (CLASS_ID, TOKEN_ID)
+        ));
+        assert!(OrmlNFT::is_owner(&ALICE, (CLASS_ID, TOKEN_ID)));
+    });
+}
+
+#[test]
+fn list_and_buy_with_u128_ids_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        // List the asset
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            1000,
+            None,
+            0
+        ));
+        assert_eq!(GamePowerMarket::all_listings(), vec![(CLASS_ID, TOKEN_ID)]);
+        assert_eq!(GamePowerMarket::listings_by_owner(&ALICE), vec![0]);
+
+        // Listed assets are locked
+        assert!(GamePowerMarket::is_locked(&(CLASS_ID, TOKEN_ID)));
+        assert_noop!(
+            GamePowerMarket::transfer(Origin::signed(ALICE), BOB, (CLASS_ID, TOKEN_ID)),
+            Error::<Test>::NoPermission
+        );
+
+        // Buy the asset
+        assert_ok!(GamePowerMarket::buy(Origin::signed(BOB), 0));
+        assert!(OrmlNFT::is_owner(&BOB, (CLASS_ID, TOKEN_ID)));
+        assert!(GamePowerMarket::all_listings().is_empty());
+    });
+}
+
+#[test]
+fn claim_with_u128_ids_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        // Create and complete a claim
+        assert_ok!(GamePowerMarket::create_claim(
+            Origin::signed(ALICE),
+            BOB,
+            (CLASS_ID, TOKEN_ID),
+            None
+        ));
+        assert_eq!(GamePowerMarket::all_claims(), vec![(CLASS_ID, TOKEN_ID)]);
+        assert_ok!(GamePowerMarket::claim(Origin::signed(BOB), 0));
+        assert!(OrmlNFT::is_owner(&BOB, (CLASS_ID, TOKEN_ID)));
+        assert!(GamePowerMarket::all_claims().is_empty());
+    });
+}
+
+#[test]
+fn list_should_use_class_escrow() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            1000,
+            None,
+            0
+        ));
+        assert!(OrmlNFT::is_owner(
+            &GamePowerMarket::get_class_escrow_account(CLASS_ID),
+            (CLASS_ID, TOKEN_ID)
+        ));
+
+        // Class escrows are distinct from the global escrow and the claim account
+        assert_ne!(
+            GamePowerMarket::get_class_escrow_account(CLASS_ID),
+            GamePowerMarket::get_escrow_account()
+        );
+        assert_ne!(
+            GamePowerMarket::get_class_escrow_account(100),
+            GamePowerMarket::get_claim_account()
+        );
+    });
+}
+
+#[test]
+fn global_escrow_listing_should_unlist_before_migration() {
+    new_test_ext().execute_with(|| {
+        // Create NFTs
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        // Listings made before per-class escrow are held by the global escrow
+        for token_id in TOKEN_ID..TOKEN_ID + 2 {
+            assert_ok!(GamePowerMarket::list(
+                Origin::signed(ALICE),
+                (CLASS_ID, token_id),
+                1000,
+                None,
+                0
+            ));
+            assert_ok!(OrmlNFT::transfer(
+                &GamePowerMarket::get_class_escrow_account(CLASS_ID),
+                &GamePowerMarket::get_escrow_account(),
+                (CLASS_ID, token_id)
+            ));
+        }
+
+        // They can still be unlisted and bought during the transition
+        assert_ok!(GamePowerMarket::unlist(Origin::signed(ALICE), 0));
+        assert!(OrmlNFT::is_owner(&ALICE, (CLASS_ID, TOKEN_ID)));
+        assert_ok!(GamePowerMarket::buy(Origin::signed(BOB), 1));
+        assert!(OrmlNFT::is_owner(&BOB, (CLASS_ID, TOKEN_ID + 1)));
+    });
+}
+
+#[test]
+fn class_escrow_migration_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        // Listing held by the global escrow
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            1000,
+            None,
+            0
+        ));
+        assert_ok!(OrmlNFT::transfer(
+            &GamePowerMarket::get_class_escrow_account(CLASS_ID),
+            &GamePowerMarket::get_escrow_account(),
+            (CLASS_ID, TOKEN_ID)
+        ));
+        assert_eq!(GamePowerMarket::storage_version(), Releases::V1_0_0);
+
+        // The upgrade moves the asset into its class escrow
+        GamePowerMarket::on_runtime_upgrade();
+        assert_eq!(GamePowerMarket::storage_version(), Releases::V15_0_0);
+        assert!(OrmlNFT::is_owner(
+            &GamePowerMarket::get_class_escrow_account(CLASS_ID),
+            (CLASS_ID, TOKEN_ID)
+        ));
+
+        // Running it again is a no-op
+        GamePowerMarket::on_runtime_upgrade();
+        assert_ok!(GamePowerMarket::unlist(Origin::signed(ALICE), 0));
+        assert!(OrmlNFT::is_owner(&ALICE, (CLASS_ID, TOKEN_ID)));
+    });
+}
+
+#[test]
+fn listings_by_owner_migration_should_work() {
+    new_test_ext().execute_with(|| {
+        // Listing ids stored as a single vector per owner
+        let key = Blake2_128Concat::hash(&ALICE.encode());
+        put_storage_value(b"GamePowerMarket", b"ListingsByOwner", &key, vec![3u64, 1]);
+        crate::StorageVersion::put(Releases::V2_0_0);
+
+        // The upgrade splits them into one entry per listing
+        GamePowerMarket::on_runtime_upgrade();
+        assert_eq!(GamePowerMarket::storage_version(), Releases::V15_0_0);
+        assert_eq!(GamePowerMarket::listings_by_owner(&ALICE), vec![1, 3]);
+        assert!(GamePowerMarket::listings_by_owner(&BOB).is_empty());
+    });
+}
+
+#[test]
+fn upgrade_checks_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFTs and some market activity
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID),
+            1000,
+            None,
+            0
+        ));
+        assert_ok!(GamePowerMarket::list(
+            Origin::signed(ALICE),
+            (CLASS_ID, TOKEN_ID + 1),
+            1000,
+            None,
+            0
+        ));
+        assert_ok!(GamePowerMarket::buy(Origin::signed(BOB), 1));
+
+        // A full migration keeps the counters intact
+        let state = GamePowerMarket::pre_upgrade().unwrap();
+        crate::StorageVersion::put(Releases::V1_0_0);
+        GamePowerMarket::on_runtime_upgrade();
+        assert_eq!(GamePowerMarket::post_upgrade(state.clone()), Ok(()));
+
+        // Any drift is reported
+        crate::ListingCount::put(5);
+        assert_eq!(
+            GamePowerMarket::post_upgrade(state),
+            Err("ListingCount changed during upgrade")
+        );
+        assert_eq!(
+            GamePowerMarket::post_upgrade(vec![]),
+            Err("invalid pre_upgrade state")
+        );
+    });
+}
+
+#[test]
+fn claim_index_migration_should_work() {
+    new_test_ext().execute_with(|| {
+        // Create NFT
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        assert_ok!(OrmlNFT::mint(&ALICE, CLASS_ID, vec![1], Default::default()));
+
+        // Claims made before the index existed
+        assert_ok!(GamePowerMarket::create_claim(
+            Origin::signed(ALICE),
+            BOB,
+            (CLASS_ID, TOKEN_ID),
+            None
+        ));
+        crate::ClaimIndex::<Test>::remove((CLASS_ID, TOKEN_ID));
+        crate::StorageVersion::put(Releases::V3_0_0);
+        assert!(!GamePowerMarket::is_locked(&(CLASS_ID, TOKEN_ID)));
+
+        GamePowerMarket::on_runtime_upgrade();
+        assert_eq!(GamePowerMarket::storage_version(), Releases::V15_0_0);
+        assert!(GamePowerMarket::claim_index((CLASS_ID, TOKEN_ID)));
+        assert!(GamePowerMarket::is_locked(&(CLASS_ID, TOKEN_ID)));
+    });
+}
+
+#[test]
+fn listing_expiry_migration_should_work() {
+    new_test_ext().execute_with(|| {
+        // Listing stored before listings could expire
+        let key = Twox64Concat::hash(&7u64.encode());
+        put_storage_value(
+            b"GamePowerMarket",
+            b"Listings",
+            &key,
+            (7u64, ALICE, (CLASS_ID, TOKEN_ID), 1000u64),
+        );
+        crate::StorageVersion::put(Releases::V4_0_0);
+
+        GamePowerMarket::on_runtime_upgrade();
+        assert_eq!(GamePowerMarket::storage_version(), Releases::V15_0_0);
+        assert_eq!(
+            GamePowerMarket::listings(7),
+            Some(Listing {
+                id: 7,
+                seller: ALICE,
+                asset: (CLASS_ID, TOKEN_ID),
+                price: 1000,
+                expiry_block: None,
+                currency_id: 0,
+            })
+        );
+    });
+}
+
+#[test]
+fn claim_deadline_migration_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+
+        // Claim stored before claims had a creator and deadline
+        let key = [
+            Blake2_128Concat::hash(&BOB.encode()),
+            Twox64Concat::hash(&3u64.encode()),
+        ]
+        .concat();
+        put_storage_value(
+            b"GamePowerMarket",
+            b"OpenClaims",
+            &key,
+            (BOB, (CLASS_ID, TOKEN_ID)),
+        );
+        crate::StorageVersion::put(Releases::V5_0_0);
+
+        GamePowerMarket::on_runtime_upgrade();
+        assert_eq!(GamePowerMarket::storage_version(), Releases::V15_0_0);
+        assert_eq!(
+            GamePowerMarket::open_claims(BOB, 3),
+            Some(Claim {
+                receiver: BOB,
+                asset: (CLASS_ID, TOKEN_ID),
+                creator: ALICE,
+                deadline: None,
+            })
+        );
+        assert_eq!(GamePowerMarket::claim_receivers(3), Some(BOB));
+    });
+}
+
+#[test]
+fn claim_deadline_migration_should_keep_claims_without_class() {
+    new_test_ext().execute_with(|| {
+        // Claim on a class that is gone
+        let key = [
+            Blake2_128Concat::hash(&BOB.encode()),
+            Twox64Concat::hash(&3u64.encode()),
+        ]
+        .concat();
+        put_storage_value(
+            b"GamePowerMarket",
+            b"OpenClaims",
+            &key,
+            (BOB, (CLASS_ID, TOKEN_ID)),
+        );
+        crate::StorageVersion::put(Releases::V5_0_0);
+
+        GamePowerMarket::on_runtime_upgrade();
+        assert_eq!(
+            GamePowerMarket::open_claims(BOB, 3),
+            Some(Claim {
+                receiver: BOB,
+                asset: (CLASS_ID, TOKEN_ID),
+                creator: BOB,
+                deadline: None,
+            })
+        );
+        assert_eq!(GamePowerMarket::claim_receivers(3), Some(BOB));
+    });
+}
+
+#[test]
+fn offer_currency_migration_should_work() {
+    new_test_ext().execute_with(|| {
+        // Offer stored before offers had a currency
+        let key = Twox64Concat::hash(&3u64.encode());
+        put_storage_value(
+            b"GamePowerMarket",
+            b"Offers",
+            &key,
+            (3u64, BOB, (CLASS_ID, TOKEN_ID), 500u64, 20u64),
+        );
+        crate::StorageVersion::put(Releases::V12_0_0);
+
+        GamePowerMarket::on_runtime_upgrade();
+        assert_eq!(GamePowerMarket::storage_version(), Releases::V15_0_0);
+        assert_eq!(
+            GamePowerMarket::offers(3),
+            Some(Offer {
+                offer_id: 3,
+                buyer: BOB,
+                asset: (CLASS_ID, TOKEN_ID),
+                amount: 500,
+                expiry_block: 20,
+                currency_id: 0,
+            })
+        );
+    });
+}
+
+#[test]
+fn swap_proposed_block_migration_should_work() {
+    new_test_ext().execute_with(|| {
+        // Swap stored before swaps recorded their proposal block
+        let key = Twox64Concat::hash(&2u64.encode());
+        put_storage_value(
+            b"GamePowerMarket",
+            b"SwapProposals",
+            &key,
+            (
+                2u64,
+                ALICE,
+                BOB,
+                (CLASS_ID, TOKEN_ID),
+                (CLASS_ID, TOKEN_ID + 1),
+                30u64,
+            ),
+        );
+        crate::StorageVersion::put(Releases::V13_0_0);
+        System::set_block_number(7);
+
+        GamePowerMarket::on_runtime_upgrade();
+        assert_eq!(GamePowerMarket::storage_version(), Releases::V15_0_0);
+        assert_eq!(
+            GamePowerMarket::swap_proposals(2),
+            Some(SwapProposal {
+                swap_id: 2,
+                initiator: ALICE,
+                counterparty: BOB,
+                offered_asset: (CLASS_ID, TOKEN_ID),
+                requested_asset: (CLASS_ID, TOKEN_ID + 1),
+                expiry_block: 30,
+                proposed_block: 7,
+            })
+        );
+    });
+}
+
+#[test]
+fn listing_currency_migration_should_work() {
+    new_test_ext().execute_with(|| {
+        // Listing stored before listings had a currency
+        let key = Twox64Concat::hash(&7u64.encode());
+        put_storage_value(
+            b"GamePowerMarket",
+            b"Listings",
+            &key,
+            (7u64, ALICE, (CLASS_ID, TOKEN_ID), 1000u64, Some(20u64)),
+        );
+        crate::StorageVersion::put(Releases::V6_0_0);
+
+        GamePowerMarket::on_runtime_upgrade();
+        assert_eq!(GamePowerMarket::storage_version(), Releases::V15_0_0);
+        assert_eq!(
+            GamePowerMarket::listings(7),
+            Some(Listing {
+                id: 7,
+                seller: ALICE,
+                asset: (CLASS_ID, TOKEN_ID),
+                price: 1000,
+                expiry_block: Some(20),
+                currency_id: 0,
+            })
+        );
+    });
+}
+
+#[test]
+fn listings_by_asset_migration_should_work() {
+    new_test_ext().execute_with(|| {
+        // Listing stored before listed assets were indexed
+        let listing = Listing {
+            id: 7,
+            seller: ALICE,
+            asset: (CLASS_ID, TOKEN_ID),
+            price: 1000,
+            expiry_block: None,
+            currency_id: 0,
+        };
+        crate::Listings::<Test>::insert(7, listing.clone());
+        crate::StorageVersion::put(Releases::V7_0_0);
+
+        GamePowerMarket::on_runtime_upgrade();
+        assert_eq!(GamePowerMarket::storage_version(), Releases::V15_0_0);
+        assert_eq!(
+            GamePowerMarket::listings_by_asset((CLASS_ID, TOKEN_ID)),
+            Some(7)
+        );
+        assert_eq!(
+            GamePowerMarket::get_listing_by_asset(CLASS_ID, TOKEN_ID),
+            Some(listing)
+        );
+    });
+}
+
+#[test]
+fn listings_by_class_migration_should_work() {
+    new_test_ext().execute_with(|| {
+        // Listings stored before open listings were indexed by class
+        for (listing_id, token_id) in [(9u64, TOKEN_ID), (4u64, TOKEN_ID + 1)].iter() {
+            crate::Listings::<Test>::insert(
+                listing_id,
+                Listing {
+                    id: *listing_id,
+                    seller: ALICE,
+                    asset: (CLASS_ID, *token_id),
+                    price: 1000,
+                    expiry_block: None,
+                    currency_id: 0,
+                },
+            );
+        }
+        crate::StorageVersion::put(Releases::V8_0_0);
+
+        GamePowerMarket::on_runtime_upgrade();
+        assert_eq!(GamePowerMarket::storage_version(), Releases::V15_0_0);
+        assert_eq!(GamePowerMarket::listings_by_class(CLASS_ID), vec![4, 9]);
+    });
+}
+
+#[test]
+fn secret_listing_indexes_migration_should_work() {
+    new_test_ext().execute_with(|| {
+        // Secret listings opened before they were indexed
+        for (secret_listing_id, token_id) in [(6u64, TOKEN_ID), (2u64, TOKEN_ID + 1)].iter() {
+            crate::SecretListings::<Test>::insert(
+                secret_listing_id,
+                SecretListing {
+                    id: *secret_listing_id,
+                    seller: ALICE,
+                    asset: (CLASS_ID, *token_id),
+                    price_hash: Default::default(),
+                },
+            );
+        }
+        crate::StorageVersion::put(Releases::V14_0_0);
+
+        GamePowerMarket::on_runtime_upgrade();
+        assert_eq!(GamePowerMarket::storage_version(), Releases::V15_0_0);
+        assert_eq!(
+            GamePowerMarket::secret_listings_by_class(CLASS_ID),
+            vec![2, 6]
+        );
+        assert_eq!(
+            GamePowerMarket::secret_listings_by_asset((CLASS_ID, TOKEN_ID)),
+            Some(6)
+        );
+    });
+}
+
+#[test]
+fn order_paid_amount_migration_should_work() {
+    new_test_ext().execute_with(|| {
+        // Order stored before orders recorded the paid amount
+        let asset = (CLASS_ID, TOKEN_ID);
+        let key = [
+            Twox64Concat::hash(&asset.encode()),
+            Twox64Concat::hash(&3u64.encode()),
+        ]
+        .concat();
+        put_storage_value(
+            b"GamePowerMarket",
+            b"OrderHistory",
+            &key,
+            (3u64, ALICE, BOB, asset, 1000u64, 5u64),
+        );
+        crate::StorageVersion::put(Releases::V9_0_0);
+
+        GamePowerMarket::on_runtime_upgrade();
+        assert_eq!(GamePowerMarket::storage_version(), Releases::V15_0_0);
+        assert_eq!(
+            GamePowerMarket::order_history(asset, 3),
+            Some(Order {
+                id: 3,
+                seller: ALICE,
+                buyer: BOB,
+                asset,
+                price: 1000,
+                paid_amount: 1000,
+                block_number: 5,
+            })
+        );
+    });
+}
+
+#[test]
+fn claim_count_migration_should_work() {
+    new_test_ext().execute_with(|| {
+        // Claims opened before claims were counted
+        crate::AllClaims::<Test>::put(vec![(CLASS_ID, TOKEN_ID), (CLASS_ID, TOKEN_ID + 1)]);
+        crate::StorageVersion::put(Releases::V10_0_0);
+
+        GamePowerMarket::on_runtime_upgrade();
+        assert_eq!(GamePowerMarket::storage_version(), Releases::V15_0_0);
+        assert_eq!(GamePowerMarket::claim_count(), 2);
+    });
+}
+
+#[test]
+fn asset_data_migration_should_work() {
+    new_test_ext().execute_with(|| {
+        // Token stored in the current layout
+        assert_ok!(OrmlNFT::create_class(&ALICE, vec![1], Default::default()));
+        let current_data = AssetData {
+            properties: vec![9],
+            game_attributes: vec![(b"level".to_vec(), b"42".to_vec())],
+            ..Default::default()
+        };
+        assert_ok!(OrmlNFT::mint(
+            &ALICE,
+            CLASS_ID,
+            vec![1],
+            current_data.clone()
+        ));
+
+        // Class and token stored when their data only held properties
+        let old_class = CLASS_ID + 1;
+        put_storage_value(
+            b"OrmlNFT",
+            b"Classes",
+            &Twox64Concat::hash(&old_class.encode()),
+            (vec![1u8], 1u128, ALICE, vec![7u8]),
+        );
+        let token_key = [
+            Twox64Concat::hash(&old_class.encode()),
+            Twox64Concat::hash(&TOKEN_ID.encode()),
+        ]
+        .concat();
+        put_storage_value(
+            b"OrmlNFT",
+            b"Tokens",
+            &token_key,
+            (vec![2u8], BOB, vec![8u8]),
+        );
+        assert!(OrmlNFT::classes(old_class).is_none());
+        assert!(OrmlNFT::tokens(old_class, TOKEN_ID).is_none());
+        crate::StorageVersion::put(Releases::V11_0_0);
+
+        GamePowerMarket::on_runtime_upgrade();
+        assert_eq!(GamePowerMarket::storage_version(), Releases::V15_0_0);
+
+        // Old entries keep their properties and take the defaults for everything else
+        let class_info = OrmlNFT::classes(old_class).unwrap();
+        assert_eq!(class_info.owner, ALICE);
+        assert_eq!(class_info.total_issuance, 1);
+        assert_eq!(
+            class_info.data,
+            ClassData {
+                properties: vec![7],
+                ..Default::default()
+            }
+        );
+        let token_info = OrmlNFT::tokens(old_class, TOKEN_ID).unwrap();
+        assert_eq!(token_info.owner, BOB);
+        assert_eq!(token_info.metadata, vec![2]);
+        assert_eq!(
+            token_info.data,
+            AssetData {
+                properties: vec![8],
+                ..Default::default()
+            }
+        );
+
+        // Current entries are untouched
+        assert_eq!(
+            OrmlNFT::tokens(CLASS_ID, TOKEN_ID).unwrap().data,
+            current_data
+        );
+    });
+}
+
+#[test]
+fn asset_data_migration_should_keep_unreadable_entries() {
+    new_test_ext().execute_with(|| {
+        let class_key = Twox64Concat::hash(&CLASS_ID.encode());
+        let token_key = [
+            Twox64Concat::hash(&CLASS_ID.encode()),
+            Twox64Concat::hash(&TOKEN_ID.encode()),
+        ]
+        .concat();
+        put_storage_value(b"OrmlNFT", b"Classes", &class_key, vec![0xffu8; 3]);
+        put_storage_value(b"OrmlNFT", b"Tokens", &token_key, 7u8);
+        crate::StorageVersion::put(Releases::V11_0_0);
+
+        GamePowerMarket::on_runtime_upgrade();
+        assert_eq!(GamePowerMarket::storage_version(), Releases::V15_0_0);
+
+        // Entries in neither layout keep their bytes instead of being dropped
+        assert_eq!(
+            get_storage_value::<Vec<u8>>(b"OrmlNFT", b"Classes", &class_key),
+            Some(vec![0xffu8; 3])
+        );
+        assert_eq!(
+            get_storage_value::<u8>(b"OrmlNFT", b"Tokens", &token_key),
+            Some(7u8)
+        );
+    });
+}
@@ -0,0 +1,171 @@
+// This file is part of GamePower Network.
+
+// Copyright (C) 2021 GamePower Network.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Weights for gamepower_market
+//!
+//! Regenerate with the Substrate benchmark CLI against the target runtime built with the
+//! `runtime-benchmarks` feature:
+//! `benchmark --pallet=gamepower_market --extrinsic=* --steps=50 --repeat=20`
+//!
+//! The DB read and write counts below are tallied from the distinct storage items each call
+//! touches on its most expensive path, ignoring the List, Unlist and Buy handlers. The
+//! execution times are estimates until the weights are regenerated.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{
+    traits::Get,
+    weights::{constants::RocksDbWeight, Weight},
+};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for gamepower_market.
+pub trait WeightInfo {
+    /// `m` is the number of entries in `AllListings`
+    fn transfer(m: u32) -> Weight;
+    /// `m` is the number of entries in `AllListings`
+    fn burn(m: u32) -> Weight;
+    /// `m` is the number of entries in `AllListings`
+    fn list(m: u32) -> Weight;
+    /// `m` is the number of entries in `AllListings`
+    fn unlist(m: u32) -> Weight;
+    /// `m` is the number of entries in `AllListings`
+    fn buy(m: u32) -> Weight;
+    fn emote() -> Weight;
+    /// `c` is the number of entries in `AllClaims`
+    fn claim(c: u32) -> Weight;
+    /// `c` is the number of entries in `AllClaims`
+    fn create_claim(c: u32) -> Weight;
+}
+
+/// Weights for gamepower_market using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+    fn transfer(m: u32) -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add((210_000 as Weight).saturating_mul(m as Weight))
+            .saturating_add(T::DbWeight::get().reads(10 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+    }
+
+    fn burn(m: u32) -> Weight {
+        (52_000_000 as Weight)
+            .saturating_add((210_000 as Weight).saturating_mul(m as Weight))
+            .saturating_add(T::DbWeight::get().reads(11 as Weight))
+            .saturating_add(T::DbWeight::get().writes(9 as Weight))
+    }
+
+    fn list(m: u32) -> Weight {
+        (68_000_000 as Weight)
+            .saturating_add((420_000 as Weight).saturating_mul(m as Weight))
+            .saturating_add(T::DbWeight::get().reads(17 as Weight))
+            .saturating_add(T::DbWeight::get().writes(15 as Weight))
+    }
+
+    fn unlist(m: u32) -> Weight {
+        (61_000_000 as Weight)
+            .saturating_add((430_000 as Weight).saturating_mul(m as Weight))
+            .saturating_add(T::DbWeight::get().reads(13 as Weight))
+            .saturating_add(T::DbWeight::get().writes(12 as Weight))
+    }
+
+    fn buy(m: u32) -> Weight {
+        (112_000_000 as Weight)
+            .saturating_add((430_000 as Weight).saturating_mul(m as Weight))
+            .saturating_add(T::DbWeight::get().reads(27 as Weight))
+            .saturating_add(T::DbWeight::get().writes(27 as Weight))
+    }
+
+    fn emote() -> Weight {
+        (24_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+
+    fn claim(c: u32) -> Weight {
+        (58_000_000 as Weight)
+            .saturating_add((220_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add(T::DbWeight::get().reads(10 as Weight))
+            .saturating_add(T::DbWeight::get().writes(12 as Weight))
+    }
+
+    fn create_claim(c: u32) -> Weight {
+        (63_000_000 as Weight)
+            .saturating_add((430_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add(T::DbWeight::get().reads(11 as Weight))
+            .saturating_add(T::DbWeight::get().writes(10 as Weight))
+    }
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+    fn transfer(m: u32) -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add((210_000 as Weight).saturating_mul(m as Weight))
+            .saturating_add(RocksDbWeight::get().reads(10 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+    }
+
+    fn burn(m: u32) -> Weight {
+        (52_000_000 as Weight)
+            .saturating_add((210_000 as Weight).saturating_mul(m as Weight))
+            .saturating_add(RocksDbWeight::get().reads(11 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(9 as Weight))
+    }
+
+    fn list(m: u32) -> Weight {
+        (68_000_000 as Weight)
+            .saturating_add((420_000 as Weight).saturating_mul(m as Weight))
+            .saturating_add(RocksDbWeight::get().reads(17 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(15 as Weight))
+    }
+
+    fn unlist(m: u32) -> Weight {
+        (61_000_000 as Weight)
+            .saturating_add((430_000 as Weight).saturating_mul(m as Weight))
+            .saturating_add(RocksDbWeight::get().reads(13 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(12 as Weight))
+    }
+
+    fn buy(m: u32) -> Weight {
+        (112_000_000 as Weight)
+            .saturating_add((430_000 as Weight).saturating_mul(m as Weight))
+            .saturating_add(RocksDbWeight::get().reads(27 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(27 as Weight))
+    }
+
+    fn emote() -> Weight {
+        (24_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+
+    fn claim(c: u32) -> Weight {
+        (58_000_000 as Weight)
+            .saturating_add((220_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add(RocksDbWeight::get().reads(10 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(12 as Weight))
+    }
+
+    fn create_claim(c: u32) -> Weight {
+        (63_000_000 as Weight)
+            .saturating_add((430_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add(RocksDbWeight::get().reads(11 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(10 as Weight))
+    }
+}
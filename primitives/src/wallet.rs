@@ -1,22 +1,244 @@
 use codec::{Decode, Encode};
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
-use sp_runtime::RuntimeDebug;
+use sp_runtime::{Perbill, RuntimeDebug};
 use sp_std::vec::Vec;
 
 /// Listing Id
 pub type ListingId = u64;
 /// Claim Id
 pub type ClaimId = u64;
+/// Secret Listing Id
+pub type SecretListingId = u64;
+/// Auction Id
+pub type AuctionId = u64;
+/// Offer Id
+pub type OfferId = u64;
+/// Bundle Listing Id
+pub type BundleListingId = u64;
+/// Order Id
+pub type OrderId = u64;
+/// Swap Id
+pub type SwapId = u64;
+
+/// Maximum length of the pallet name in MarketPalletInfo
+pub const MAX_PALLET_NAME_LENGTH: usize = 64;
+/// Maximum length of the pallet description in MarketPalletInfo
+pub const MAX_PALLET_DESCRIPTION_LENGTH: usize = 256;
+
+#[derive(Encode, Decode, Default, Clone, RuntimeDebug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+/// Listing data
+pub struct Listing<ClassIdOf, TokenIdOf, AccountId, Balance, BlockNumber, CurrencyId> {
+    /// Listing Id
+    pub id: ListingId,
+    /// Seller of the listing
+    pub seller: AccountId,
+    /// Asset - (class_id, token_id)
+    pub asset: (ClassIdOf, TokenIdOf),
+    /// Price of the asset listed
+    pub price: Balance,
+    /// Last block the asset can be bought in. Listings without one never expire.
+    pub expiry_block: Option<BlockNumber>,
+    /// Currency the price is paid in
+    pub currency_id: CurrencyId,
+}
+
+#[derive(Encode, Decode, Default, Clone, RuntimeDebug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+/// Completed sale of an asset
+pub struct Order<ClassIdOf, TokenIdOf, AccountId, Balance, BlockNumber> {
+    /// Order Id
+    pub id: OrderId,
+    /// Account the asset was bought from
+    pub seller: AccountId,
+    /// Account that bought the asset
+    pub buyer: AccountId,
+    /// Asset - (class_id, token_id)
+    pub asset: (ClassIdOf, TokenIdOf),
+    /// Price the asset was sold at
+    pub price: Balance,
+    /// Amount the buyer actually transferred for the asset
+    pub paid_amount: Balance,
+    /// Block the sale happened in
+    pub block_number: BlockNumber,
+}
+
+#[derive(Encode, Decode, Default, Clone, RuntimeDebug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+/// Offer to buy an asset that isn't listed. The amount is reserved from the buyer.
+pub struct Offer<ClassIdOf, TokenIdOf, AccountId, Balance, BlockNumber, CurrencyId> {
+    /// Offer Id
+    pub offer_id: OfferId,
+    /// Account making the offer
+    pub buyer: AccountId,
+    /// Asset - (class_id, token_id)
+    pub asset: (ClassIdOf, TokenIdOf),
+    /// Amount offered
+    pub amount: Balance,
+    /// Last block the offer can be accepted in
+    pub expiry_block: BlockNumber,
+    /// Currency the amount is reserved in
+    pub currency_id: CurrencyId,
+}
 
 #[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
-pub struct ClassData {
+pub struct ClassData<AccountId, BlockNumber> {
     pub properties: Vec<u8>,
+    /// UTF-8 off-chain identity of the creator, such as a website or address
+    pub creator_address: Vec<u8>,
+    /// Game the class belongs to, 0 if it isn't tied to a game
+    pub game_id: u32,
+    /// Share of every resale paid to the royalty recipient
+    pub royalty_rate: Option<Perbill>,
+    /// Account receiving the class royalty
+    pub royalty_recipient: Option<AccountId>,
+    /// Collaborators sharing the royalty, each with their share of every resale
+    pub royalty_recipients: Vec<(AccountId, Perbill)>,
+    /// Whether assets of the class can be transferred between accounts
+    pub allow_transfer: bool,
+    /// Whether assets of the class can be listed on the market
+    pub allow_listing: bool,
+    /// Whether assets of the class can be burned
+    pub allow_burn: bool,
+    /// Version of the schema the properties of the class's assets follow
+    pub schema_version: u32,
+    /// Maximum number of assets that can ever be minted in the class
+    pub max_supply: Option<u32>,
+    /// Block from which assets of the class can be transferred, listed or claimed
+    pub transferable_after: Option<BlockNumber>,
 }
 
-#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+impl<AccountId, BlockNumber> Default for ClassData<AccountId, BlockNumber> {
+    fn default() -> Self {
+        ClassData {
+            properties: Default::default(),
+            creator_address: Default::default(),
+            game_id: 0,
+            royalty_rate: None,
+            royalty_recipient: None,
+            royalty_recipients: Vec::new(),
+            allow_transfer: true,
+            allow_listing: true,
+            allow_burn: true,
+            schema_version: 0,
+            max_supply: None,
+            transferable_after: None,
+        }
+    }
+}
+
+#[derive(Encode, Decode, Default, Clone, RuntimeDebug, PartialEq, Eq)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
-pub struct AssetData {
+pub struct AssetData<ClassId, TokenId, BlockNumber> {
     pub properties: Vec<u8>,
+    /// Number of times the asset has been listed on the market
+    pub mint_count: u32,
+    /// Character asset this item is equipped to
+    pub equipped_to: Option<(ClassId, TokenId)>,
+    /// Position of the asset in its class's mint order, starting at 0
+    pub serial_number: u64,
+    /// Game of the asset's class, copied in at mint
+    pub game_id: u32,
+    /// Bound to its owner for good. Soulbound assets can only be burned.
+    pub soulbound: bool,
+    /// Version of the class schema the properties follow
+    pub schema_version: u32,
+    /// Block the asset was last bought in on the market
+    pub last_purchased_at: Option<BlockNumber>,
+    /// Game stats of the asset as (key, value) pairs, such as ("level", "42")
+    pub game_attributes: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+#[derive(Encode, Decode, Default, Clone, RuntimeDebug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+/// A game whose assets are hosted by the runtime
+pub struct GameInfo {
+    /// Display name of the game
+    pub name: Vec<u8>,
+    /// Off-chain game data, usually an IPFS hash
+    pub metadata: Vec<u8>,
+}
+
+#[derive(Encode, Decode, Default, Clone, RuntimeDebug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct ClassMarketData<Balance, BlockNumber> {
+    /// Block of the most recent sale in the class
+    pub last_sale_block: Option<BlockNumber>,
+    /// Price of the most recent sale in the class
+    pub last_sale_price: Option<Balance>,
+    /// Lowest asking price among the class's open listings
+    pub floor_price: Option<Balance>,
+    /// Amount paid for assets of the class over the last day
+    pub volume_24h: Balance,
+}
+
+#[derive(Encode, Decode, Default, Clone, RuntimeDebug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct ClassListingStats<Balance> {
+    /// Number of open listings in the class
+    pub active_listing_count: u32,
+    /// Lowest asking price among the class's open listings
+    pub floor_price: Option<Balance>,
+    /// Highest asking price among the class's open listings
+    pub highest_listing_price: Option<Balance>,
+    /// Amount paid for assets of the class over the last day
+    pub volume_24h: Balance,
+    /// Number of sales in the class
+    pub sales_count: u64,
+    /// Number of distinct sellers with open listings in the class
+    pub unique_sellers: u32,
+}
+
+#[derive(Encode, Decode, Default, Clone, RuntimeDebug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+/// Name and version of the market pallet, for on-chain tooling
+pub struct MarketPalletInfo {
+    /// Pallet name, at most MAX_PALLET_NAME_LENGTH bytes
+    pub name: Vec<u8>,
+    /// Pallet version
+    pub version: u32,
+    /// Pallet description, at most MAX_PALLET_DESCRIPTION_LENGTH bytes
+    pub description: Vec<u8>,
+}
+
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+/// Reason an asset can't currently be moved by its owner
+pub enum LockReason {
+    /// Asset is held in escrow by a listing
+    Listed,
+    /// Asset is held by an open claim
+    Claiming,
+    /// Item is equipped to a character
+    Equipped,
+    /// Asset is held in escrow by an auction
+    Auction,
+}
+
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+/// Everything the market knows about an asset
+pub struct TokenDetail<AccountId, ClassId, TokenId, Balance, BlockNumber> {
+    /// Current owner of the asset
+    pub owner: AccountId,
+    /// Class of the asset
+    pub class_id: ClassId,
+    /// Id of the asset in its class
+    pub token_id: TokenId,
+    /// Asset metadata
+    pub metadata: Vec<u8>,
+    /// Asset data
+    pub data: AssetData<ClassId, TokenId, BlockNumber>,
+    /// Why the asset is locked, if it is
+    pub lock_reason: Option<LockReason>,
+    /// Number of times each emoji was posted on the asset, by emoji
+    pub emote_counts: Vec<(Vec<u8>, u64)>,
+    /// Price of the most recent sale
+    pub last_sale_price: Option<Balance>,
+    /// Block the asset was minted in
+    pub mint_block: Option<BlockNumber>,
+    /// Serial number of the asset in its class
+    pub serial_number: Option<u64>,
 }
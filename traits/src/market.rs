@@ -1,4 +1,4 @@
-use sp_runtime::DispatchResult;
+use sp_runtime::{DispatchError, DispatchResult};
 
 /// An asset transfer handler
 pub trait OnTransferHandler<AccountId, ClassId, TokenId> {
@@ -17,3 +17,102 @@ pub trait OnClaimHandler<AccountId, ClassId, TokenId> {
     /// claim the given asset
     fn claim(owner: &AccountId, asset: (ClassId, TokenId)) -> DispatchResult;
 }
+
+/// An asset mint handler
+pub trait OnMintHandler<AccountId, ClassId, TokenId> {
+    /// called after the given asset is minted
+    fn on_mint(owner: &AccountId, asset: (ClassId, TokenId)) -> DispatchResult;
+}
+
+impl<AccountId, ClassId, TokenId> OnMintHandler<AccountId, ClassId, TokenId> for () {
+    fn on_mint(_owner: &AccountId, _asset: (ClassId, TokenId)) -> DispatchResult {
+        Ok(())
+    }
+}
+
+/// A currency handler for sales and offers priced in tokens other than the native currency
+pub trait MultiCurrency<AccountId, CurrencyId, Balance> {
+    /// Transfer `amount` of the given currency from one account to another.
+    fn transfer(
+        currency_id: CurrencyId,
+        from: &AccountId,
+        to: &AccountId,
+        amount: Balance,
+    ) -> DispatchResult;
+
+    /// Reserve `amount` of the given currency from an account's free balance.
+    fn reserve(currency_id: CurrencyId, who: &AccountId, amount: Balance) -> DispatchResult;
+
+    /// Move up to `amount` of the given currency back to an account's free balance.
+    /// Returns the amount that could not be unreserved.
+    fn unreserve(currency_id: CurrencyId, who: &AccountId, amount: Balance) -> Balance;
+}
+
+impl<AccountId, CurrencyId, Balance> MultiCurrency<AccountId, CurrencyId, Balance> for () {
+    fn transfer(
+        _currency_id: CurrencyId,
+        _from: &AccountId,
+        _to: &AccountId,
+        _amount: Balance,
+    ) -> DispatchResult {
+        Err(DispatchError::Other("currency not supported"))
+    }
+
+    fn reserve(_currency_id: CurrencyId, _who: &AccountId, _amount: Balance) -> DispatchResult {
+        Err(DispatchError::Other("currency not supported"))
+    }
+
+    fn unreserve(_currency_id: CurrencyId, _who: &AccountId, amount: Balance) -> Balance {
+        amount
+    }
+}
+
+/// An asset listing handler
+pub trait OnListHandler<AccountId, ClassId, TokenId, Balance> {
+    /// called after the given asset is sent to escrow and listed
+    fn on_list(seller: &AccountId, asset: (ClassId, TokenId), price: Balance) -> DispatchResult;
+}
+
+impl<AccountId, ClassId, TokenId, Balance> OnListHandler<AccountId, ClassId, TokenId, Balance>
+    for ()
+{
+    fn on_list(_seller: &AccountId, _asset: (ClassId, TokenId), _price: Balance) -> DispatchResult {
+        Ok(())
+    }
+}
+
+/// An asset unlisting handler
+pub trait OnUnlistHandler<AccountId, ClassId, TokenId> {
+    /// called when the given asset leaves the market, either sold or returned to the seller
+    fn on_unlist(seller: &AccountId, asset: (ClassId, TokenId), was_sold: bool) -> DispatchResult;
+}
+
+impl<AccountId, ClassId, TokenId> OnUnlistHandler<AccountId, ClassId, TokenId> for () {
+    fn on_unlist(_seller: &AccountId, _asset: (ClassId, TokenId), _was_sold: bool) -> DispatchResult {
+        Ok(())
+    }
+}
+
+/// An asset purchase handler
+pub trait OnBuyHandler<AccountId, ClassId, TokenId, Balance> {
+    /// called after the given asset is bought from the market
+    fn on_buy(
+        seller: &AccountId,
+        buyer: &AccountId,
+        asset: (ClassId, TokenId),
+        price: Balance,
+    ) -> DispatchResult;
+}
+
+impl<AccountId, ClassId, TokenId, Balance> OnBuyHandler<AccountId, ClassId, TokenId, Balance>
+    for ()
+{
+    fn on_buy(
+        _seller: &AccountId,
+        _buyer: &AccountId,
+        _asset: (ClassId, TokenId),
+        _price: Balance,
+    ) -> DispatchResult {
+        Ok(())
+    }
+}